@@ -0,0 +1,58 @@
+//! 比較一般路徑與快速路徑的 CSV 轉 JSON 吞吐量
+//!
+//! 執行：`cargo bench -p csv-converter --bench json_conversion`，列數可用 `BENCH_ROWS` 調整
+
+use csv_converter::{CsvConverter, ValueOptions};
+use std::io::{BufWriter, Write};
+use std::time::Instant;
+
+fn main() -> anyhow::Result<()> {
+    let rows: usize = std::env::var("BENCH_ROWS").ok().and_then(|s| s.parse().ok()).unwrap_or(500_000);
+    let dir = std::env::temp_dir().join("csv_converter_bench");
+    std::fs::create_dir_all(&dir)?;
+    let csv_path = dir.join("input.csv").to_string_lossy().into_owned();
+    let json_path = dir.join("output.json").to_string_lossy().into_owned();
+
+    generate_csv(&csv_path, rows)?;
+    let size = std::fs::metadata(&csv_path)?.len() as f64 / (1024.0 * 1024.0);
+    println!("輸入: {} 列, {:.1} MiB", rows, size);
+
+    let options = ValueOptions::default();
+    let mut results = Vec::new();
+    for (name, fast) in [("一般路徑", false), ("快速路徑", true)] {
+        let started = Instant::now();
+        if fast {
+            CsvConverter::convert_csv_to_json_file_fast(&csv_path, &json_path, &options)?;
+        } else {
+            CsvConverter::convert_csv_to_json_file_with(&csv_path, &json_path, &options)?;
+        }
+        let seconds = started.elapsed().as_secs_f64();
+        println!("{:<8} {:>8.2} 秒 {:>8.1} MiB/s", name, seconds, size / seconds);
+        results.push(seconds);
+    }
+    println!("加速 {:.1} 倍", results[0] / results[1]);
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+/// 產生混合整數、浮點數、布林值與需要跳脫的字串欄位的測試資料
+fn generate_csv(path: &str, rows: usize) -> anyhow::Result<()> {
+    let mut writer = BufWriter::new(std::fs::File::create(path)?);
+    writeln!(writer, "id,name,city,score,active,note")?;
+    let cities = ["Taipei", "Tokyo", "London", "New York", "São Paulo"];
+    for i in 0..rows {
+        writeln!(
+            writer,
+            "{},user_{},{},{:.2},{},\"line {} says \"\"hi\"\"\"",
+            i,
+            i,
+            cities[i % cities.len()],
+            (i % 1000) as f64 / 7.0,
+            i % 3 == 0,
+            i
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,203 @@
+//! 非同步轉換（需啟用 `async` feature）：從 `AsyncRead` 讀取 CSV，寫入 `AsyncWrite`
+//!
+//! 每讀入一塊資料就轉換並寫出，寫入端來不及消化時會暫停讀取，
+//! 記憶體用量只與緩衝區大小有關，不需要 `spawn_blocking`。
+
+use anyhow::{bail, Context};
+use csv_core::{ReadRecordResult, Reader};
+use serde_json::Map;
+use std::str::FromStr;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::converter::CsvConverter;
+use crate::report::{ConversionReport, ReportBuilder};
+use crate::value::ValueOptions;
+
+const UTF8_BOM: [u8; 3] = [0xef, 0xbb, 0xbf];
+
+/// 非同步轉換的輸出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AsyncFormat {
+    /// 每列一行 JSON
+    #[default]
+    Ndjson,
+    /// 單一 JSON 陣列
+    Json,
+}
+
+impl FromStr for AsyncFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ndjson" | "jsonl" => Ok(AsyncFormat::Ndjson),
+            "json" => Ok(AsyncFormat::Json),
+            _ => bail!("未知的輸出格式: {}（可用: ndjson, json）", s),
+        }
+    }
+}
+
+/// 非同步轉換設定
+#[derive(Debug, Clone)]
+pub struct AsyncOptions {
+    pub format: AsyncFormat,
+    /// 讀取與寫出緩衝區的大小（位元組）
+    pub buffer_size: usize,
+    pub values: ValueOptions,
+}
+
+impl Default for AsyncOptions {
+    fn default() -> Self {
+        Self {
+            format: AsyncFormat::default(),
+            buffer_size: 64 * 1024,
+            values: ValueOptions::default(),
+        }
+    }
+}
+
+impl CsvConverter {
+    /// 從 `reader` 串流讀取 CSV 並轉換為 JSON 寫入 `writer`，完成時會 flush 但不關閉寫入端
+    pub async fn convert_csv_async<R, W>(
+        mut reader: R,
+        mut writer: W,
+        options: &AsyncOptions,
+    ) -> anyhow::Result<ConversionReport>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let buffer_size = options.buffer_size.max(1);
+        let mut report = ReportBuilder::streaming();
+        let mut parser = Reader::new();
+
+        // 開頭至少要能放下 BOM，之後每次只讀 buffer_size 位元組
+        let mut input = vec![0u8; buffer_size.max(UTF8_BOM.len())];
+        let (mut start, mut end) = (0, 0);
+        let mut eof = false;
+
+        // 與同步路徑的 csv 讀取器相同，略過開頭的 UTF-8 BOM
+        while end < UTF8_BOM.len() {
+            let n = reader.read(&mut input[end..UTF8_BOM.len()]).await.context("讀取 CSV 輸入失敗")?;
+            if n == 0 {
+                break;
+            }
+            end += n;
+        }
+        let mut bytes_in = end as u64;
+        if input[..end] == UTF8_BOM {
+            start = end;
+        }
+
+        let mut fields = vec![0u8; 1024];
+        let mut ends = vec![0usize; 64];
+        let (mut outlen, mut endlen) = (0, 0);
+        // 目前記錄開始的行號；已讀過的換行數包含記錄之間略過的空白行
+        let mut line = 1;
+        let mut newlines = 0;
+        let mut at_record_start = true;
+
+        let mut headers: Option<Vec<String>> = None;
+        let mut rows = 0;
+        let mut out = Vec::with_capacity(buffer_size);
+        let mut bytes_out = 0u64;
+
+        if options.format == AsyncFormat::Json {
+            out.push(b'[');
+        }
+
+        loop {
+            // 輸入用完才讀下一塊，讀到 0 位元組表示結束
+            if start == end && !eof {
+                let n = reader.read(&mut input[..buffer_size]).await.context("讀取 CSV 輸入失敗")?;
+                bytes_in += n as u64;
+                (start, end) = (0, n);
+                eof = n == 0;
+            }
+
+            // 記錄之間的空白行與 csv 讀取器相同直接略過，記錄的行號從第一個非換行字元起算
+            if at_record_start {
+                let blank = input[start..end].iter().take_while(|b| matches!(b, b'\r' | b'\n')).count();
+                newlines += input[start..start + blank].iter().filter(|&&b| b == b'\n').count();
+                start += blank;
+                if start < end {
+                    at_record_start = false;
+                    line = newlines + 1;
+                } else if !eof {
+                    // 空的輸入對 csv_core 表示結束，先讀下一塊
+                    continue;
+                }
+            }
+
+            let (result, nin, nout, nend) =
+                parser.read_record(&input[start..end], &mut fields[outlen..], &mut ends[endlen..]);
+            newlines += input[start..start + nin].iter().filter(|&&b| b == b'\n').count();
+            start += nin;
+            outlen += nout;
+            endlen += nend;
+
+            match result {
+                ReadRecordResult::InputEmpty => {}
+                ReadRecordResult::OutputFull => fields.resize(fields.len() * 2, 0),
+                ReadRecordResult::OutputEndsFull => ends.resize(ends.len() * 2, 0),
+                ReadRecordResult::Record => {
+                    let record = std::str::from_utf8(&fields[..outlen])
+                        .with_context(|| format!("第 {} 行不是有效的 UTF-8", line))?;
+                    let mut field_start = 0;
+                    let values: Vec<&str> = ends[..endlen]
+                        .iter()
+                        .map(|&field_end| {
+                            let field = &record[field_start..field_end];
+                            field_start = field_end;
+                            field
+                        })
+                        .collect();
+
+                    match &headers {
+                        None => headers = Some(values.iter().map(|h| h.to_string()).collect()),
+                        Some(headers) => {
+                            if values.len() != headers.len() {
+                                bail!("第 {} 行有 {} 個欄位，標題列有 {} 個", line, values.len(), headers.len());
+                            }
+                            let mut row = Map::new();
+                            for (header, field) in headers.iter().zip(&values) {
+                                row.insert(header.clone(), options.values.parse(header, field)?);
+                            }
+                            match options.format {
+                                AsyncFormat::Ndjson => {
+                                    serde_json::to_writer(&mut out, &row)?;
+                                    out.push(b'\n');
+                                }
+                                AsyncFormat::Json => {
+                                    out.extend_from_slice(if rows == 0 { b"\n  " } else { b",\n  " });
+                                    serde_json::to_writer(&mut out, &row)?;
+                                }
+                            }
+                            rows += 1;
+                        }
+                    }
+                    (outlen, endlen) = (0, 0);
+                    at_record_start = true;
+
+                    // 寫入端跟不上時在這裡等待，暫停讀取
+                    if out.len() >= buffer_size {
+                        writer.write_all(&out).await.context("寫入輸出失敗")?;
+                        bytes_out += out.len() as u64;
+                        out.clear();
+                    }
+                }
+                ReadRecordResult::End => break,
+            }
+        }
+
+        if options.format == AsyncFormat::Json {
+            out.extend_from_slice(if rows == 0 { b"]\n" } else { b"\n]\n" });
+        }
+        writer.write_all(&out).await.context("寫入輸出失敗")?;
+        writer.flush().await.context("寫入輸出失敗")?;
+        bytes_out += out.len() as u64;
+
+        report.rows_read(rows);
+        Ok(report.finish_with_bytes(rows, 0, bytes_in, bytes_out))
+    }
+}
@@ -0,0 +1,422 @@
+//! Apache Avro 物件容器檔（object container file）的寫入與讀取
+//!
+//! 記錄 schema 由推斷出的 CSV schema 產生，可為 null 的欄位寫成 `["null", 型別]` 聯集。
+//! 讀取端只支援本模組會寫出的型別，用於在沒有 schema registry 的情況下驗證輸出。
+
+use anyhow::{bail, Context};
+use csv::StringRecord;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::str::FromStr;
+
+use crate::converter::CsvConverter;
+use crate::report::{ConversionReport, ReportBuilder};
+use crate::schema::ColumnType;
+use crate::value::ValueOptions;
+
+const MAGIC: &[u8; 4] = b"Obj\x01";
+const SYNC_SIZE: usize = 16;
+
+/// 資料區塊的壓縮方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AvroCodec {
+    #[default]
+    Null,
+    Deflate,
+    Snappy,
+}
+
+impl AvroCodec {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AvroCodec::Null => "null",
+            AvroCodec::Deflate => "deflate",
+            AvroCodec::Snappy => "snappy",
+        }
+    }
+
+    fn compress(self, data: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+        Ok(match self {
+            AvroCodec::Null => data,
+            AvroCodec::Deflate => {
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(&data)?;
+                encoder.finish()?
+            }
+            // Snappy 區塊後面接未壓縮資料的 CRC32（big-endian）
+            AvroCodec::Snappy => {
+                let mut compressed = snap::raw::Encoder::new().compress_vec(&data)?;
+                compressed.extend_from_slice(&crc32fast::hash(&data).to_be_bytes());
+                compressed
+            }
+        })
+    }
+
+    fn decompress(self, data: &[u8]) -> anyhow::Result<Vec<u8>> {
+        Ok(match self {
+            AvroCodec::Null => data.to_vec(),
+            AvroCodec::Deflate => {
+                let mut decompressed = Vec::new();
+                DeflateDecoder::new(data).read_to_end(&mut decompressed)?;
+                decompressed
+            }
+            AvroCodec::Snappy => {
+                let Some(split) = data.len().checked_sub(4) else {
+                    bail!("Snappy 區塊缺少 CRC32");
+                };
+                let decompressed = snap::raw::Decoder::new().decompress_vec(&data[..split])?;
+                if crc32fast::hash(&decompressed).to_be_bytes() != data[split..] {
+                    bail!("Snappy 區塊的 CRC32 不符");
+                }
+                decompressed
+            }
+        })
+    }
+}
+
+impl FromStr for AvroCodec {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "null" => Ok(AvroCodec::Null),
+            "deflate" => Ok(AvroCodec::Deflate),
+            "snappy" => Ok(AvroCodec::Snappy),
+            _ => bail!("未知的 Avro 壓縮方式: {}（可用: null, deflate, snappy）", s),
+        }
+    }
+}
+
+/// Avro 匯出設定
+#[derive(Debug, Clone)]
+pub struct AvroOptions {
+    /// 記錄 schema 的名稱
+    pub record_name: String,
+    pub namespace: Option<String>,
+    pub codec: AvroCodec,
+    /// 每個資料區塊的記錄數
+    pub block_size: usize,
+    pub values: ValueOptions,
+}
+
+impl Default for AvroOptions {
+    fn default() -> Self {
+        Self {
+            record_name: "Row".to_string(),
+            namespace: None,
+            codec: AvroCodec::default(),
+            block_size: 1000,
+            values: ValueOptions::default(),
+        }
+    }
+}
+
+impl CsvConverter {
+    /// 將記錄寫成 Avro 物件容器檔，schema 內嵌於檔頭
+    pub fn write_avro_file(
+        headers: &[String],
+        records: &[StringRecord],
+        avro_path: &str,
+        options: &AvroOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        if options.block_size == 0 {
+            bail!("block_size 必須大於 0");
+        }
+        let mut report = ReportBuilder::new(headers, records, &options.values);
+        let columns = report.schema().columns.clone();
+
+        // Avro 欄位名稱只能是英數字與底線，重複時加上序號
+        let mut names: Vec<String> = Vec::with_capacity(columns.len());
+        for column in &columns {
+            let base = avro_name(&column.name);
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while names.contains(&name) {
+                name = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+            if name != column.name {
+                report.warn(format!("欄位 {} 不是合法的 Avro 名稱，輸出為 {}", column.name, name));
+            }
+            names.push(name);
+        }
+
+        let fields: Vec<Value> = columns
+            .iter()
+            .zip(&names)
+            .map(|(column, name)| {
+                let primitive = avro_type(column.column_type);
+                let field_type = if column.nullable { json!(["null", primitive]) } else { json!(primitive) };
+                json!({ "name": name, "type": field_type })
+            })
+            .collect();
+        let mut schema = json!({ "type": "record", "name": avro_name(&options.record_name), "fields": fields });
+        if let Some(namespace) = &options.namespace {
+            schema["namespace"] = json!(namespace);
+        }
+
+        let sync: [u8; SYNC_SIZE] = rand::random();
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        let metadata = [
+            ("avro.schema", serde_json::to_vec(&schema)?),
+            ("avro.codec", options.codec.as_str().as_bytes().to_vec()),
+        ];
+        write_long(&mut out, metadata.len() as i64);
+        for (key, value) in &metadata {
+            write_bytes(&mut out, key.as_bytes());
+            write_bytes(&mut out, value);
+        }
+        write_long(&mut out, 0);
+        out.extend_from_slice(&sync);
+
+        for (block_index, block) in records.chunks(options.block_size).enumerate() {
+            let mut data = Vec::new();
+            for (offset, record) in block.iter().enumerate() {
+                let row = block_index * options.block_size + offset;
+                for (i, column) in columns.iter().enumerate() {
+                    let field = record.get(i).unwrap_or("");
+                    let value = field_value(row, i, &column.name, field, &options.values);
+                    if column.nullable {
+                        // 聯集的第 0 個分支是 null
+                        if value.is_null() {
+                            write_long(&mut data, 0);
+                            continue;
+                        }
+                        write_long(&mut data, 1);
+                    } else if value.is_null() {
+                        bail!("第 {} 列欄位 {} 為 null，但 schema 不允許空值", row + 1, column.name);
+                    }
+                    write_value(&mut data, &column.name, column.column_type, field, &value)?;
+                }
+            }
+            let data = options.codec.compress(data)?;
+            write_long(&mut out, block.len() as i64);
+            write_long(&mut out, data.len() as i64);
+            out.extend_from_slice(&data);
+            out.extend_from_slice(&sync);
+        }
+
+        std::fs::write(avro_path, out).with_context(|| format!("無法寫入 Avro 檔案: {}", avro_path))?;
+        Ok(report.finish(records.len(), 0, avro_path))
+    }
+
+    /// 讀取 Avro 物件容器檔，回傳欄位名稱與記錄（null 為空字串）
+    pub fn read_avro_file(avro_path: &str) -> anyhow::Result<(Vec<String>, Vec<StringRecord>)> {
+        let bytes = std::fs::read(avro_path).with_context(|| format!("無法讀取 Avro 檔案: {}", avro_path))?;
+        let mut input = bytes.as_slice();
+
+        if input.len() < MAGIC.len() || &input[..MAGIC.len()] != MAGIC {
+            bail!("{} 不是 Avro 物件容器檔", avro_path);
+        }
+        input = &input[MAGIC.len()..];
+
+        let mut schema = None;
+        let mut codec = AvroCodec::Null;
+        loop {
+            let mut count = read_long(&mut input)?;
+            if count == 0 {
+                break;
+            }
+            // 負數表示後面接著區塊的位元組數
+            if count < 0 {
+                count = count.checked_neg().with_context(|| format!("無效的 Avro 檔頭項目數: {}", count))?;
+                read_long(&mut input)?;
+            }
+            for _ in 0..count {
+                let key = String::from_utf8(read_bytes(&mut input)?.to_vec())?;
+                let value = read_bytes(&mut input)?;
+                match key.as_str() {
+                    "avro.schema" => schema = Some(serde_json::from_slice::<Value>(value)?),
+                    "avro.codec" => codec = std::str::from_utf8(value)?.parse()?,
+                    _ => {}
+                }
+            }
+        }
+        let schema = schema.context("Avro 檔頭缺少 avro.schema")?;
+        let sync = take(&mut input, SYNC_SIZE)?;
+
+        let fields = schema["fields"].as_array().context("只支援 record 型別的 Avro schema")?;
+        let columns = fields
+            .iter()
+            .map(|field| {
+                let name = field["name"].as_str().context("Avro 欄位缺少名稱")?;
+                Ok((name.to_string(), FieldType::parse(&field["type"])?))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let mut records = Vec::new();
+        while !input.is_empty() {
+            let count = read_long(&mut input)?;
+            let size = read_long(&mut input)?;
+            if count < 0 {
+                bail!("無效的 Avro 區塊記錄數: {}", count);
+            }
+            let size = usize::try_from(size).map_err(|_| anyhow::anyhow!("無效的 Avro 區塊長度: {}", size))?;
+            let data = codec.decompress(take(&mut input, size)?)?;
+            if take(&mut input, SYNC_SIZE)? != sync {
+                bail!("{} 的同步標記不符，檔案可能已損毀", avro_path);
+            }
+
+            let mut data = data.as_slice();
+            for _ in 0..count {
+                let mut record = StringRecord::new();
+                for (_, field_type) in &columns {
+                    record.push_field(&field_type.read(&mut data)?);
+                }
+                records.push(record);
+            }
+        }
+
+        let headers = columns.into_iter().map(|(name, _)| name).collect();
+        Ok((headers, records))
+    }
+}
+
+fn avro_type(column_type: ColumnType) -> &'static str {
+    match column_type {
+        ColumnType::Integer => "long",
+        ColumnType::Float => "double",
+        ColumnType::Boolean => "boolean",
+        ColumnType::String => "string",
+    }
+}
+
+/// 將欄位名稱轉為合法的 Avro 名稱：`[A-Za-z_][A-Za-z0-9_]*`
+fn avro_name(name: &str) -> String {
+    let mut result: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+    if !result.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        result.insert(0, '_');
+    }
+    result
+}
+
+/// 與 schema 推斷相同的轉換：空欄位為 null，轉換失敗（例如非有限數值設為錯誤）時視為字串
+fn field_value(row: usize, index: usize, column: &str, field: &str, options: &ValueOptions) -> Value {
+    if field.is_empty() {
+        return Value::Null;
+    }
+    options
+        .parse_cell(row, index, column, field)
+        .unwrap_or_else(|_| Value::String(field.to_string()))
+}
+
+/// 依欄位型別寫出一個非 null 的值；字串欄位保留原始文字
+fn write_value(out: &mut Vec<u8>, column: &str, column_type: ColumnType, field: &str, value: &Value) -> anyhow::Result<()> {
+    if column_type == ColumnType::String {
+        write_bytes(out, field.as_bytes());
+        return Ok(());
+    }
+    let mismatch = || anyhow::anyhow!("欄位 {} 的值 {} 不符合型別 {}", column, value, column_type);
+    match column_type {
+        ColumnType::Integer => write_long(out, value.as_i64().ok_or_else(mismatch)?),
+        ColumnType::Float => out.extend_from_slice(&value.as_f64().ok_or_else(mismatch)?.to_le_bytes()),
+        ColumnType::Boolean => out.push(value.as_bool().ok_or_else(mismatch)? as u8),
+        ColumnType::String => unreachable!("字串欄位已在上方處理"),
+    }
+    Ok(())
+}
+
+/// zigzag 編碼的變長整數
+fn write_long(out: &mut Vec<u8>, value: i64) {
+    let mut n = ((value << 1) ^ (value >> 63)) as u64;
+    while n >= 0x80 {
+        out.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_long(out, bytes.len() as i64);
+    out.extend_from_slice(bytes);
+}
+
+fn read_long(input: &mut &[u8]) -> anyhow::Result<i64> {
+    let mut n: u64 = 0;
+    for shift in (0..64).step_by(7) {
+        let [byte, rest @ ..] = *input else {
+            bail!("Avro 資料意外結束");
+        };
+        *input = rest;
+        n |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok((n >> 1) as i64 ^ -((n & 1) as i64));
+        }
+    }
+    bail!("無效的 Avro 整數編碼")
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> anyhow::Result<&'a [u8]> {
+    if input.len() < len {
+        bail!("Avro 資料意外結束");
+    }
+    let (head, rest) = input.split_at(len);
+    *input = rest;
+    Ok(head)
+}
+
+fn read_bytes<'a>(input: &mut &'a [u8]) -> anyhow::Result<&'a [u8]> {
+    let len = read_long(input)?;
+    if len < 0 {
+        bail!("無效的 Avro 長度: {}", len);
+    }
+    take(input, len as usize)
+}
+
+/// 讀取端支援的欄位型別
+enum FieldType {
+    Null,
+    Boolean,
+    Int,
+    Long,
+    Float,
+    Double,
+    String,
+    Union(Vec<FieldType>),
+}
+
+impl FieldType {
+    fn parse(schema: &Value) -> anyhow::Result<Self> {
+        Ok(match schema {
+            Value::Array(branches) => FieldType::Union(branches.iter().map(Self::parse).collect::<anyhow::Result<_>>()?),
+            Value::Object(object) => Self::parse(object.get("type").context("Avro 型別缺少 type")?)?,
+            Value::String(name) => match name.as_str() {
+                "null" => FieldType::Null,
+                "boolean" => FieldType::Boolean,
+                "int" => FieldType::Int,
+                "long" => FieldType::Long,
+                "float" => FieldType::Float,
+                "double" => FieldType::Double,
+                "string" | "bytes" => FieldType::String,
+                other => bail!("不支援的 Avro 型別: {}", other),
+            },
+            other => bail!("無效的 Avro 型別: {}", other),
+        })
+    }
+
+    /// 讀出一個值並轉為 CSV 文字
+    fn read(&self, input: &mut &[u8]) -> anyhow::Result<String> {
+        Ok(match self {
+            FieldType::Null => String::new(),
+            FieldType::Boolean => (take(input, 1)?[0] != 0).to_string(),
+            FieldType::Int | FieldType::Long => read_long(input)?.to_string(),
+            FieldType::Float => f32::from_le_bytes(take(input, 4)?.try_into()?).to_string(),
+            FieldType::Double => f64::from_le_bytes(take(input, 8)?.try_into()?).to_string(),
+            FieldType::String => String::from_utf8_lossy(read_bytes(input)?).into_owned(),
+            FieldType::Union(branches) => {
+                let index = read_long(input)?;
+                match usize::try_from(index).ok().and_then(|i| branches.get(i)) {
+                    Some(branch) => branch.read(input)?,
+                    None => bail!("無效的 Avro 聯集索引: {}", index),
+                }
+            }
+        })
+    }
+}
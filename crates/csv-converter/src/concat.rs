@@ -0,0 +1,118 @@
+//! 合併多個 CSV／試算表檔案：依欄位名稱對齊，缺少的欄位記錄為 null
+
+use anyhow::{bail, Context};
+use csv::StringRecord;
+use std::collections::HashMap;
+
+use crate::converter::CsvConverter;
+use crate::dialect::Dialect;
+use crate::spreadsheet::SpreadsheetOptions;
+use crate::value::NullCells;
+
+/// 記錄來源檔案的欄位名稱
+pub const SOURCE_FILE_COLUMN: &str = "_source_file";
+
+/// 合併設定
+#[derive(Debug, Clone, Default)]
+pub struct ConcatOptions {
+    /// 加上 [`SOURCE_FILE_COLUMN`] 欄位記錄每列的來源檔案
+    pub source_column: bool,
+    /// 欄位改名 (舊名稱, 新名稱)，用於對齊不同時期改過名的標題
+    pub renames: Vec<(String, String)>,
+    /// 試算表輸入的讀取設定
+    pub spreadsheet: SpreadsheetOptions,
+    /// CSV 輸入的方言；未指定時依各檔案的副檔名決定
+    pub dialect: Option<Dialect>,
+}
+
+/// 合併結果；記錄中缺少的儲存格為空字串，另外記錄在 missing 中
+#[derive(Debug, Clone, Default)]
+pub struct ConcatData {
+    pub headers: Vec<String>,
+    pub records: Vec<StringRecord>,
+    /// 來源檔案沒有的欄位，轉換前指定給 [`ValueOptions::null_cells`](crate::ValueOptions::null_cells) 即輸出為 null
+    pub missing: NullCells,
+}
+
+impl CsvConverter {
+    /// 展開 glob 樣式並依路徑排序，沒有符合的檔案時回傳錯誤
+    pub fn expand_glob(pattern: &str) -> anyhow::Result<Vec<String>> {
+        let mut paths = Vec::new();
+        for entry in glob::glob(pattern).with_context(|| format!("無效的檔案樣式: {}", pattern))? {
+            let path = entry?;
+            if path.is_file() {
+                paths.push(path.to_string_lossy().into_owned());
+            }
+        }
+        if paths.is_empty() {
+            bail!("沒有符合 {} 的檔案", pattern);
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// 依欄位名稱合併多個檔案；欄位順序為首次出現的順序，檔案中缺少的欄位記錄在 [`ConcatData::missing`]
+    ///
+    /// 型別不一致時由後續的 [`Schema`](crate::Schema) 推斷放寬（整數與浮點數合併為浮點數）。
+    pub fn concat_files(paths: &[String], options: &ConcatOptions) -> anyhow::Result<ConcatData> {
+        let renames: HashMap<&str, &str> = options
+            .renames
+            .iter()
+            .map(|(from, to)| (from.as_str(), to.as_str()))
+            .collect();
+
+        let mut headers: Vec<String> = Vec::new();
+        let mut files = Vec::with_capacity(paths.len());
+        for path in paths {
+            let dialect = options.dialect.unwrap_or_else(|| Dialect::for_path(path));
+            let (file_headers, records) = Self::read_input_file_with_dialect(path, &options.spreadsheet, &dialect)
+                .with_context(|| format!("無法讀取 {}", path))?;
+
+            // 每個檔案的欄位在合併後標題中的位置
+            let mut positions = Vec::with_capacity(file_headers.len());
+            for header in &file_headers {
+                let header = header.trim();
+                let name = renames.get(header).copied().unwrap_or(header);
+                let position = match headers.iter().position(|h| h == name) {
+                    Some(position) => position,
+                    None => {
+                        headers.push(name.to_string());
+                        headers.len() - 1
+                    }
+                };
+                if positions.contains(&position) {
+                    bail!("{} 中的欄位 {} 重複出現（可能是改名後撞名）", path, name);
+                }
+                positions.push(position);
+            }
+            files.push((path, positions, records));
+        }
+
+        if options.source_column {
+            if headers.iter().any(|h| h == SOURCE_FILE_COLUMN) {
+                bail!("輸入檔已有 {} 欄位", SOURCE_FILE_COLUMN);
+            }
+            headers.push(SOURCE_FILE_COLUMN.to_string());
+        }
+
+        let data_columns = headers.len() - usize::from(options.source_column);
+        let mut merged = Vec::new();
+        let mut missing = NullCells::default();
+        for (path, positions, records) in files {
+            let absent = (0..data_columns).filter(|i| !positions.contains(i)).collect();
+            missing.push(merged.len()..merged.len() + records.len(), absent);
+            for record in records {
+                let mut fields = vec![""; headers.len()];
+                for (field, &position) in record.iter().zip(&positions) {
+                    fields[position] = field;
+                }
+                if options.source_column {
+                    fields[headers.len() - 1] = path;
+                }
+                merged.push(StringRecord::from(fields));
+            }
+        }
+
+        Ok(ConcatData { headers, records: merged, missing })
+    }
+}
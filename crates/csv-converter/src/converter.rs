@@ -1,64 +1,81 @@
-use csv::Reader;
-use std::fs::File;
-use std::collections::HashMap;
+use csv::StringRecord;
+use std::time::Instant;
+use serde_json::{Map, Value};
+
+use crate::dialect::Dialect;
+use crate::report::{ConversionReport, ReportBuilder};
+use crate::value::ValueOptions;
 
 /// CSV 轉換器
 pub struct CsvConverter;
 
 impl CsvConverter {
     /// 將 CSV 檔案轉換並儲存為 JSON 檔案
-    pub fn convert_csv_to_json_file(csv_path: &str, json_path: &str) -> std::io::Result<()> {
-        // 開啟 CSV 檔案
-        let file = File::open(csv_path)?;
-        let mut reader = Reader::from_reader(file);
+    pub fn convert_csv_to_json_file(csv_path: &str, json_path: &str) -> std::io::Result<ConversionReport> {
+        Self::convert_csv_to_json_file_with(csv_path, json_path, &ValueOptions::default())
+            .map_err(std::io::Error::other)
+    }
 
-        // 讀取標題行
-        let headers: Vec<String> = reader.headers()?
-            .iter()
-            .map(|h| h.to_string())
-            .collect();
+    /// 依指定的欄位值轉換設定，將 CSV 檔案轉換並儲存為 JSON 檔案
+    pub fn convert_csv_to_json_file_with(
+        csv_path: &str,
+        json_path: &str,
+        options: &ValueOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let started = Instant::now();
+        let (headers, records) = Self::read_csv_file(csv_path)?;
+        Self::write_json_file(&headers, &records, json_path, options)?.with_input(csv_path, started)
+    }
 
-        let mut records = Vec::new();
+    /// 將已讀取的標題列與記錄轉換並儲存為 JSON 檔案
+    pub fn write_json_file(
+        headers: &[String],
+        records: &[StringRecord],
+        json_path: &str,
+        options: &ValueOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let report = ReportBuilder::new(headers, records, options);
+        let rows = Self::typed_records(headers, records, options)?;
+
+        // 將記錄序列化為 JSON
+        let json_string = serde_json::to_string_pretty(&rows)?;
+
+        // 寫入 JSON 檔案
+        std::fs::write(json_path, &json_string)?;
+
+        Ok(report.finish(rows.len(), 0, json_path))
+    }
+
+    /// 將記錄轉為有型別的 JSON 物件（依標題順序），所有輸出格式共用此轉換流程
+    pub fn typed_records(
+        headers: &[String],
+        records: &[StringRecord],
+        options: &ValueOptions,
+    ) -> anyhow::Result<Vec<Map<String, Value>>> {
+        let mut rows = Vec::with_capacity(records.len());
 
         // 處理每一行資料
-        for result in reader.records() {
-            let record = result?;
-            let mut row_map = HashMap::new();
+        for (row, record) in records.iter().enumerate() {
+            let mut row_map = Map::new();
 
             // 將每一行轉換為鍵值對
             for (i, field) in record.iter().enumerate() {
                 if i < headers.len() {
                     let header = &headers[i];
-                    let value = if let Ok(num) = field.parse::<i64>() {
-                        serde_json::Value::Number(num.into())
-                    } else if let Ok(num) = field.parse::<f64>() {
-                        if let Some(n) = serde_json::Number::from_f64(num) {
-                            serde_json::Value::Number(n)
-                        } else {
-                            serde_json::Value::String(field.to_string())
-                        }
-                    } else if field.to_lowercase() == "true" {
-                        serde_json::Value::Bool(true)
-                    } else if field.to_lowercase() == "false" {
-                        serde_json::Value::Bool(false)
-                    } else {
-                        serde_json::Value::String(field.to_string())
-                    };
+                    let value = options.parse_cell(row, i, header, field)?;
 
                     row_map.insert(header.clone(), value);
                 }
             }
 
-            records.push(row_map);
+            rows.push(row_map);
         }
 
-        // 將記錄序列化為 JSON
-        let json_string = serde_json::to_string_pretty(&records)?;
-
-        // 寫入 JSON 檔案
-        std::fs::write(json_path, &json_string)?;
+        Ok(rows)
+    }
 
-        Ok(())
+    /// 讀取整個 CSV 檔案，回傳標題列與所有記錄
+    pub fn read_csv_file(csv_path: &str) -> std::io::Result<(Vec<String>, Vec<StringRecord>)> {
+        Self::read_csv_file_with_dialect(csv_path, &Dialect::default())
     }
 }
-
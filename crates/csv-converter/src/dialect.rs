@@ -0,0 +1,105 @@
+//! CSV 方言：分隔字元、引號字元與註解字元
+
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use crate::converter::CsvConverter;
+use crate::report::{ConversionReport, ReportBuilder};
+use crate::value::ValueOptions;
+
+/// 以 tab 分隔的副檔名
+pub const TSV_EXTENSIONS: &[&str] = &["tsv", "tab"];
+
+/// CSV 方言設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dialect {
+    pub delimiter: u8,
+    pub quote: u8,
+    /// 以此字元開頭的行視為註解並略過；只影響讀取
+    pub comment: Option<u8>,
+}
+
+impl Default for Dialect {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            comment: None,
+        }
+    }
+}
+
+impl Dialect {
+    /// 依副檔名決定預設方言：.tsv 與 .tab 以 tab 分隔，其餘以逗號分隔
+    pub fn for_path(path: &str) -> Self {
+        let is_tsv = Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| TSV_EXTENSIONS.iter().any(|t| ext.eq_ignore_ascii_case(t)));
+        if is_tsv {
+            Self { delimiter: b'\t', ..Self::default() }
+        } else {
+            Self::default()
+        }
+    }
+
+    pub fn reader_builder(&self) -> ReaderBuilder {
+        let mut builder = ReaderBuilder::new();
+        builder.delimiter(self.delimiter).quote(self.quote).comment(self.comment);
+        builder
+    }
+
+    pub fn writer_builder(&self) -> WriterBuilder {
+        let mut builder = WriterBuilder::new();
+        builder.delimiter(self.delimiter).quote(self.quote);
+        builder
+    }
+}
+
+impl CsvConverter {
+    /// 依指定方言讀取整個 CSV 檔案，回傳標題列與所有記錄
+    pub fn read_csv_file_with_dialect(
+        csv_path: &str,
+        dialect: &Dialect,
+    ) -> std::io::Result<(Vec<String>, Vec<StringRecord>)> {
+        Self::read_csv(File::open(csv_path)?, dialect)
+    }
+
+    /// 依指定方言從 `reader` 讀取整份 CSV，回傳標題列與所有記錄
+    pub fn read_csv<R: Read>(reader: R, dialect: &Dialect) -> std::io::Result<(Vec<String>, Vec<StringRecord>)> {
+        let mut reader = dialect.reader_builder().from_reader(reader);
+        let headers: Vec<String> = reader.headers()?.iter().map(|h| h.to_string()).collect();
+        let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+        Ok((headers, records))
+    }
+
+    /// 以指定方言將標題列與記錄寫出為 CSV
+    pub fn write_csv<W: Write>(
+        headers: &[String],
+        records: &[StringRecord],
+        writer: W,
+        dialect: &Dialect,
+    ) -> anyhow::Result<()> {
+        let mut writer = dialect.writer_builder().from_writer(writer);
+        writer.write_record(headers)?;
+        for record in records {
+            writer.write_record(record)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// 以指定方言將標題列與記錄寫出為 CSV 檔案
+    pub fn write_csv_file(
+        headers: &[String],
+        records: &[StringRecord],
+        csv_path: &str,
+        dialect: &Dialect,
+    ) -> anyhow::Result<ConversionReport> {
+        let report = ReportBuilder::new(headers, records, &ValueOptions::default());
+        Self::write_csv(headers, records, File::create(csv_path)?, dialect)?;
+        Ok(report.finish(records.len(), 0, csv_path))
+    }
+}
@@ -0,0 +1,130 @@
+//! 比較兩份表格：依鍵欄位（或列順序）找出新增、刪除與修改的列
+
+use anyhow::{bail, Context};
+use csv::StringRecord;
+use std::collections::HashMap;
+
+use crate::converter::CsvConverter;
+
+/// 比較設定
+#[derive(Debug, Clone, Default)]
+pub struct DiffOptions {
+    /// 識別同一列的鍵欄位；空白時依列順序比較
+    pub key_columns: Vec<String>,
+}
+
+/// 單一儲存格的變更
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellChange {
+    pub column: String,
+    pub left: String,
+    pub right: String,
+}
+
+/// 一列的差異；`key` 為各鍵欄位的值，依列順序比較時為列號
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RowChange {
+    /// 只出現在右側
+    Added { key: Vec<String> },
+    /// 只出現在左側
+    Removed { key: Vec<String> },
+    /// 兩側都有但共同欄位的值不同
+    Changed { key: Vec<String>, cells: Vec<CellChange> },
+}
+
+/// 兩份表格的差異
+#[derive(Debug, Clone, Default)]
+pub struct TableDiff {
+    /// 只出現在右側的欄位
+    pub added_columns: Vec<String>,
+    /// 只出現在左側的欄位
+    pub removed_columns: Vec<String>,
+    pub rows: Vec<RowChange>,
+}
+
+impl TableDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_columns.is_empty() && self.removed_columns.is_empty() && self.rows.is_empty()
+    }
+}
+
+impl CsvConverter {
+    /// 比較兩份表格；只比較兩側共同的欄位，欄位值以原始字串比較
+    ///
+    /// 刪除與修改依左側的順序列出，新增依右側的順序列於最後。
+    pub fn diff_records(
+        left: (&[String], &[StringRecord]),
+        right: (&[String], &[StringRecord]),
+        options: &DiffOptions,
+    ) -> anyhow::Result<TableDiff> {
+        let (left_headers, left_records) = left;
+        let (right_headers, right_records) = right;
+
+        let mut diff = TableDiff {
+            added_columns: right_headers.iter().filter(|h| !left_headers.contains(h)).cloned().collect(),
+            removed_columns: left_headers.iter().filter(|h| !right_headers.contains(h)).cloned().collect(),
+            rows: Vec::new(),
+        };
+        // 共同欄位在左右兩側的索引
+        let common: Vec<(&String, usize, usize)> = left_headers
+            .iter()
+            .enumerate()
+            .filter_map(|(i, h)| right_headers.iter().position(|r| r == h).map(|j| (h, i, j)))
+            .collect();
+
+        let left_keys = row_keys(left_headers, left_records, &options.key_columns).context("左側檔案")?;
+        let right_keys = row_keys(right_headers, right_records, &options.key_columns).context("右側檔案")?;
+        let right_index: HashMap<&[String], usize> =
+            right_keys.iter().enumerate().map(|(i, key)| (key.as_slice(), i)).collect();
+        let left_index: HashMap<&[String], usize> =
+            left_keys.iter().enumerate().map(|(i, key)| (key.as_slice(), i)).collect();
+
+        for (key, left_record) in left_keys.iter().zip(left_records) {
+            let Some(&j) = right_index.get(key.as_slice()) else {
+                diff.rows.push(RowChange::Removed { key: key.clone() });
+                continue;
+            };
+            let right_record = &right_records[j];
+            let cells: Vec<CellChange> = common
+                .iter()
+                .filter_map(|&(column, i, j)| {
+                    let (l, r) = (left_record.get(i).unwrap_or(""), right_record.get(j).unwrap_or(""));
+                    (l != r).then(|| CellChange { column: column.clone(), left: l.to_string(), right: r.to_string() })
+                })
+                .collect();
+            if !cells.is_empty() {
+                diff.rows.push(RowChange::Changed { key: key.clone(), cells });
+            }
+        }
+        for key in &right_keys {
+            if !left_index.contains_key(key.as_slice()) {
+                diff.rows.push(RowChange::Added { key: key.clone() });
+            }
+        }
+
+        Ok(diff)
+    }
+}
+
+/// 每列的鍵值；各欄位分開保存，值中含有分隔字元也不會混淆。鍵值重複時無法對應，回傳錯誤
+fn row_keys(headers: &[String], records: &[StringRecord], key_columns: &[String]) -> anyhow::Result<Vec<Vec<String>>> {
+    if key_columns.is_empty() {
+        return Ok((1..=records.len()).map(|n| vec![n.to_string()]).collect());
+    }
+
+    let indexes = key_columns
+        .iter()
+        .map(|name| headers.iter().position(|h| h == name).with_context(|| format!("找不到鍵欄位: {}", name)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mut seen = HashMap::new();
+    let mut keys = Vec::with_capacity(records.len());
+    for (n, record) in records.iter().enumerate() {
+        let key: Vec<String> = indexes.iter().map(|&i| record.get(i).unwrap_or("").to_string()).collect();
+        if let Some(first) = seen.insert(key.clone(), n + 1) {
+            bail!("鍵值 {:?} 重複出現在第 {} 筆與第 {} 筆記錄", key, first, n + 1);
+        }
+        keys.push(key);
+    }
+    Ok(keys)
+}
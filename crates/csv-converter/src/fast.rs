@@ -0,0 +1,158 @@
+//! CSV 轉 JSON 的快速路徑：記憶體映射輸入、重複使用 `ByteRecord`，直接由位元組寫出 JSON
+//!
+//! 輸出內容與 [`CsvConverter::write_json_file`] 相同，排版為每列一行。
+//! 常見的欄位值（整數、浮點數、布林值、ASCII 字串）不建立中間的 `Map` 或 `Value`，
+//! 其餘情況（包括保留原始字面、一律為字串的欄位與 [`ValueOptions::null_cells`]）
+//! 退回與一般路徑相同的轉換，確保轉換結果一致。
+
+use anyhow::Context;
+use csv::{ByteRecord, ReaderBuilder};
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::Instant;
+
+use crate::converter::CsvConverter;
+use crate::report::{ConversionReport, ReportBuilder};
+use crate::value::{is_integer_literal, NumberLocale, NumericPolicy, ValueOptions, MAX_SAFE_INTEGER};
+
+impl CsvConverter {
+    /// 以快速路徑將 CSV 檔案轉換為 JSON 檔案
+    pub fn convert_csv_to_json_file_fast(
+        csv_path: &str,
+        json_path: &str,
+        options: &ValueOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let started = Instant::now();
+        let file = File::open(csv_path).with_context(|| format!("無法開啟 {}", csv_path))?;
+        // SAFETY: 只讀取映射內容；轉換期間輸入檔被其他程式修改的結果未定義，與一般讀檔的競態相同
+        let input = unsafe { Mmap::map(&file) }.with_context(|| format!("無法映射 {}", csv_path))?;
+
+        let mut reader = ReaderBuilder::new().from_reader(&input[..]);
+        let headers: Vec<String> = reader.headers()?.iter().map(|h| h.to_string()).collect();
+        let fields = FieldWriter::new(&headers, options)?;
+
+        let mut out = BufWriter::with_capacity(1 << 20, File::create(json_path)?);
+        let mut record = ByteRecord::new();
+        let mut rows = 0;
+
+        out.write_all(b"[")?;
+        while reader.read_byte_record(&mut record)? {
+            out.write_all(if rows == 0 { b"\n  {" } else { b",\n  {" })?;
+            for (n, (key, index)) in fields.columns.iter().enumerate() {
+                if n > 0 {
+                    out.write_all(b",")?;
+                }
+                out.write_all(key)?;
+                let field = record.get(*index).unwrap_or_default();
+                let line = record.position().map(|p| p.line());
+                fields.write_value(&mut out, rows, *index, &headers[*index], field, line)?;
+            }
+            out.write_all(b"}")?;
+            rows += 1;
+        }
+        out.write_all(if rows == 0 { b"]\n" } else { b"\n]\n" })?;
+        out.flush()?;
+
+        let mut report = ReportBuilder::streaming();
+        report.rows_read(rows);
+        report.finish(rows, 0, json_path).with_input(csv_path, started)
+    }
+}
+
+/// 預先計算好的欄位鍵與轉換設定
+struct FieldWriter<'a> {
+    /// 已跳脫的 `"欄位":` 與取值的欄位索引；重複的欄位名稱只輸出一次，取最後一個值
+    columns: Vec<(Vec<u8>, usize)>,
+    options: &'a ValueOptions,
+    /// 沒有地區、貨幣或百分比設定，數字只需 Rust `parse` 判斷
+    plain_numbers: bool,
+    /// 需交給 [`ValueOptions::parse`] 的欄位：保留原始字面或一律為字串
+    general: Vec<bool>,
+    /// 轉為小寫的布林字詞，只用於比對 ASCII 欄位值
+    true_tokens: Vec<String>,
+    false_tokens: Vec<String>,
+}
+
+impl<'a> FieldWriter<'a> {
+    fn new(headers: &[String], options: &'a ValueOptions) -> anyhow::Result<Self> {
+        let mut columns: Vec<(Vec<u8>, usize)> = Vec::with_capacity(headers.len());
+        for (i, header) in headers.iter().enumerate() {
+            let mut key = serde_json::to_vec(header)?;
+            key.push(b':');
+            match columns.iter_mut().find(|(existing, _)| *existing == key) {
+                Some(column) => column.1 = i,
+                None => columns.push((key, i)),
+            }
+        }
+
+        let lowercase = |tokens: &[String]| tokens.iter().map(|t| t.to_lowercase()).collect();
+        Ok(Self {
+            columns,
+            options,
+            plain_numbers: options.locale == NumberLocale::Plain
+                && options.currency_symbols.is_empty()
+                && !options.percent,
+            general: headers
+                .iter()
+                .map(|h| {
+                    options.numeric == NumericPolicy::Preserve
+                        || options.decimal_columns.contains(h)
+                        || options.text_columns.contains(h)
+                })
+                .collect(),
+            true_tokens: lowercase(&options.true_tokens),
+            false_tokens: lowercase(&options.false_tokens),
+        })
+    }
+
+    fn write_value(
+        &self,
+        out: &mut impl Write,
+        row: usize,
+        index: usize,
+        column: &str,
+        field: &[u8],
+        line: Option<u64>,
+    ) -> anyhow::Result<()> {
+        if !self.options.null_cells.is_empty() && self.options.null_cells.contains(row, index) {
+            out.write_all(b"null")?;
+            return Ok(());
+        }
+        let text = std::str::from_utf8(field)
+            .with_context(|| format!("第 {} 行的欄位 {} 不是有效的 UTF-8", line.unwrap_or(0), column))?;
+
+        if text.is_empty() {
+            out.write_all(if self.options.empty_as_null { b"null" } else { b"\"\"" })?;
+            return Ok(());
+        }
+
+        if self.plain_numbers && !self.general[index] {
+            if let Ok(n) = text.parse::<i64>() {
+                if self.options.numeric != NumericPolicy::JsSafe || n.unsigned_abs() <= MAX_SAFE_INTEGER as u64 {
+                    serde_json::to_writer(out, &n)?;
+                    return Ok(());
+                }
+            } else if let Ok(f) = text.parse::<f64>() {
+                // 非有限數值與超出範圍的整數依設定處理，交給一般路徑
+                if f.is_finite() && !(self.options.numeric == NumericPolicy::JsSafe && is_integer_literal(text)) {
+                    serde_json::to_writer(out, &f)?;
+                    return Ok(());
+                }
+            } else if text.is_ascii() {
+                let is_token = |tokens: &[String]| tokens.iter().any(|t| text.eq_ignore_ascii_case(t));
+                if is_token(&self.true_tokens) {
+                    out.write_all(b"true")?;
+                } else if is_token(&self.false_tokens) {
+                    out.write_all(b"false")?;
+                } else {
+                    serde_json::to_writer(out, text)?;
+                }
+                return Ok(());
+            }
+        }
+
+        serde_json::to_writer(out, &self.options.parse(column, text)?)?;
+        Ok(())
+    }
+}
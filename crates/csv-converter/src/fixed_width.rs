@@ -0,0 +1,188 @@
+use anyhow::{bail, Context};
+use csv::StringRecord;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use crate::converter::CsvConverter;
+use crate::schema::ColumnType;
+use crate::value::ValueOptions;
+
+/// 固定寬度檔案中的一個欄位
+#[derive(Debug, Clone)]
+pub struct FixedWidthColumn {
+    pub name: String,
+    /// 起始位置（從 1 開始，以字元計）
+    pub start: usize,
+    /// 欄位寬度（字元數）
+    pub width: usize,
+    /// 宣告的型別；有宣告時會檢查每一列的值
+    pub column_type: Option<ColumnType>,
+}
+
+/// 固定寬度檔案的欄位配置
+#[derive(Debug, Clone, Default)]
+pub struct FixedWidthLayout {
+    pub columns: Vec<FixedWidthColumn>,
+    /// 開頭要略過的列數（例如報表標題）
+    pub skip_lines: usize,
+    /// 是否接受尾端空白被截掉的較短行
+    pub allow_short_lines: bool,
+}
+
+/// 有問題的行與其行號（從 1 開始）
+#[derive(Debug, Clone)]
+pub struct LineIssue {
+    pub line: usize,
+    pub message: String,
+}
+
+/// 固定寬度檔案的解析結果，有問題的行不會出現在 records 中
+#[derive(Debug, Clone, Default)]
+pub struct FixedWidthData {
+    pub headers: Vec<String>,
+    pub records: Vec<StringRecord>,
+    pub issues: Vec<LineIssue>,
+}
+
+impl FixedWidthLayout {
+    /// 從 CSV 格式的配置檔讀取欄位配置，欄位為 `name,start,width[,type]`
+    pub fn from_csv_file(path: &str) -> anyhow::Result<Self> {
+        let (headers, records) = CsvConverter::read_csv_file(path)
+            .with_context(|| format!("無法讀取欄位配置檔: {}", path))?;
+
+        let position = |name: &str| headers.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+        let (Some(name_idx), Some(start_idx), Some(width_idx)) =
+            (position("name"), position("start"), position("width"))
+        else {
+            bail!("欄位配置檔必須包含 name、start、width 欄位: {}", path);
+        };
+        let type_idx = position("type");
+
+        let mut columns = Vec::with_capacity(records.len());
+        for (i, record) in records.iter().enumerate() {
+            let field = |idx: usize| record.get(idx).unwrap_or("").trim();
+            let column_type = match type_idx.map(field) {
+                Some(t) if !t.is_empty() => Some(t.parse()?),
+                _ => None,
+            };
+            columns.push(FixedWidthColumn {
+                name: field(name_idx).to_string(),
+                start: field(start_idx)
+                    .parse()
+                    .with_context(|| format!("欄位配置第 {} 列的 start 無效", i + 2))?,
+                width: field(width_idx)
+                    .parse()
+                    .with_context(|| format!("欄位配置第 {} 列的 width 無效", i + 2))?,
+                column_type,
+            });
+        }
+
+        let layout = FixedWidthLayout { columns, ..Default::default() };
+        layout.validate()?;
+        Ok(layout)
+    }
+
+    /// 檢查欄位配置：起始位置從 1 開始、寬度大於 0 且欄位不重疊
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.columns.is_empty() {
+            bail!("欄位配置沒有任何欄位");
+        }
+
+        let mut sorted: Vec<&FixedWidthColumn> = self.columns.iter().collect();
+        sorted.sort_by_key(|c| c.start);
+        for column in &sorted {
+            if column.start == 0 || column.width == 0 {
+                bail!("欄位 {} 的 start 必須從 1 開始且 width 必須大於 0", column.name);
+            }
+        }
+        for pair in sorted.windows(2) {
+            if pair[0].start + pair[0].width > pair[1].start {
+                bail!("欄位 {} 與 {} 的位置重疊", pair[0].name, pair[1].name);
+            }
+        }
+        Ok(())
+    }
+
+    /// 每一行應有的字元數
+    pub fn record_length(&self) -> usize {
+        self.columns.iter().map(|c| c.start - 1 + c.width).max().unwrap_or(0)
+    }
+}
+
+impl CsvConverter {
+    /// 依欄位配置讀取固定寬度檔案；欄位值會去除前後空白，空白欄位為空字串
+    /// （轉換時是否視為 null 由 [`ValueOptions::empty_as_null`] 決定）
+    ///
+    /// 長度不符、值無法轉換或不符合宣告型別的行會連同行號記錄在 issues 中
+    pub fn read_fixed_width_file(
+        path: &str,
+        layout: &FixedWidthLayout,
+        options: &ValueOptions,
+    ) -> anyhow::Result<FixedWidthData> {
+        layout.validate()?;
+        let reader = BufReader::new(File::open(path)?);
+        let record_length = layout.record_length();
+
+        let mut data = FixedWidthData {
+            headers: layout.columns.iter().map(|c| c.name.clone()).collect(),
+            ..Default::default()
+        };
+
+        for (i, line) in reader.lines().enumerate().skip(layout.skip_lines) {
+            let line_number = i + 1;
+            let line = line?;
+            let line = line.strip_suffix('\r').unwrap_or(&line);
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let chars: Vec<char> = line.chars().collect();
+            let misaligned = if layout.allow_short_lines {
+                chars.len() > record_length
+            } else {
+                chars.len() != record_length
+            };
+            if misaligned {
+                data.issues.push(LineIssue {
+                    line: line_number,
+                    message: format!("長度為 {} 個字元，預期為 {}", chars.len(), record_length),
+                });
+                continue;
+            }
+
+            let mut record = StringRecord::new();
+            let mut issue = None;
+            for column in &layout.columns {
+                let start = (column.start - 1).min(chars.len());
+                let end = (start + column.width).min(chars.len());
+                let field: String = chars[start..end].iter().collect();
+                let field = field.trim();
+
+                if let (Some(expected), false) = (column.column_type, field.is_empty()) {
+                    let actual = match options.parse(&column.name, field) {
+                        Ok(value) => ColumnType::of_value(&value),
+                        Err(error) => {
+                            issue = Some(error.to_string());
+                            break;
+                        }
+                    };
+                    if !actual.is_none_or(|t| expected.accepts(t)) {
+                        issue = Some(format!(
+                            "欄位 {} 的值 {:?} 不符合宣告型別 {}",
+                            column.name, field, expected
+                        ));
+                        break;
+                    }
+                }
+                record.push_field(field);
+            }
+
+            match issue {
+                Some(message) => data.issues.push(LineIssue { line: line_number, message }),
+                None => data.records.push(record),
+            }
+        }
+
+        Ok(data)
+    }
+}
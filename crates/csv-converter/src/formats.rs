@@ -0,0 +1,295 @@
+//! YAML、TOML 與 XML 輸出，與 JSON 共用 [`CsvConverter::typed_records`] 的型別轉換流程
+
+use csv::StringRecord;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+use serde_json::{Number, Value};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use crate::converter::CsvConverter;
+use crate::report::{ConversionReport, ReportBuilder};
+use crate::value::ValueOptions;
+
+/// YAML 匯出設定
+#[derive(Debug, Clone, Default)]
+pub struct YamlOptions {
+    /// true 時每一列輸出為一個 YAML 文件（以 `---` 分隔），否則整份為一個序列
+    pub document_per_row: bool,
+    pub values: ValueOptions,
+}
+
+/// TOML 匯出設定
+#[derive(Debug, Clone)]
+pub struct TomlOptions {
+    /// 陣列表格的名稱，輸出為 `[[rows]]`
+    pub table_name: String,
+    pub values: ValueOptions,
+}
+
+impl Default for TomlOptions {
+    fn default() -> Self {
+        Self {
+            table_name: "rows".to_string(),
+            values: ValueOptions::default(),
+        }
+    }
+}
+
+/// XML 匯出設定
+#[derive(Debug, Clone)]
+pub struct XmlOptions {
+    /// 根元素名稱
+    pub root_element: String,
+    /// 每一列的元素名稱
+    pub row_element: String,
+    /// 以屬性（而非子元素）輸出的欄位
+    pub attribute_columns: Vec<String>,
+    /// true 時所有欄位都以屬性輸出
+    pub all_attributes: bool,
+    pub values: ValueOptions,
+}
+
+impl Default for XmlOptions {
+    fn default() -> Self {
+        Self {
+            root_element: "rows".to_string(),
+            row_element: "row".to_string(),
+            attribute_columns: Vec::new(),
+            all_attributes: false,
+            values: ValueOptions::default(),
+        }
+    }
+}
+
+impl CsvConverter {
+    /// 將記錄寫成 YAML 檔案
+    pub fn write_yaml_file(
+        headers: &[String],
+        records: &[StringRecord],
+        yaml_path: &str,
+        options: &YamlOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let report = ReportBuilder::new(headers, records, &options.values);
+        let rows = Self::typed_records(headers, records, &options.values)?;
+        let mut out = BufWriter::new(File::create(yaml_path)?);
+
+        if options.document_per_row {
+            for row in &rows {
+                writeln!(out, "---")?;
+                serde_yaml::to_writer(&mut out, &yaml_value(&Value::Object(row.clone())))?;
+            }
+        } else {
+            let rows = rows.into_iter().map(Value::Object).collect();
+            serde_yaml::to_writer(&mut out, &yaml_value(&Value::Array(rows)))?;
+        }
+
+        out.flush()?;
+        drop(out);
+        Ok(report.finish(records.len(), 0, yaml_path))
+    }
+
+    /// 將記錄寫成 TOML 陣列表格；TOML 沒有 null，null 值的欄位會被省略
+    pub fn write_toml_file(
+        headers: &[String],
+        records: &[StringRecord],
+        toml_path: &str,
+        options: &TomlOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let mut report = ReportBuilder::new(headers, records, &options.values);
+        let rows = Self::typed_records(headers, records, &options.values)?;
+
+        let nulls = rows.iter().flat_map(|row| row.values()).filter(|v| v.is_null()).count();
+        if nulls > 0 {
+            report.warn(format!("TOML 不支援 null，省略了 {} 個欄位值", nulls));
+        }
+
+        let tables = rows
+            .iter()
+            .map(|row| {
+                let table: toml::Table = row
+                    .iter()
+                    .filter_map(|(key, value)| toml_value(value).map(|v| (key.clone(), v)))
+                    .collect();
+                toml::Value::Table(table)
+            })
+            .collect();
+
+        let mut document = toml::Table::new();
+        document.insert(options.table_name.clone(), toml::Value::Array(tables));
+
+        std::fs::write(toml_path, toml::to_string(&document)?)?;
+        Ok(report.finish(records.len(), 0, toml_path))
+    }
+
+    /// 將記錄寫成 XML，例如 `<rows><row><name>..</name></row></rows>`
+    pub fn write_xml_file(
+        headers: &[String],
+        records: &[StringRecord],
+        xml_path: &str,
+        options: &XmlOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let mut report = ReportBuilder::new(headers, records, &options.values);
+        let names = xml_names(headers);
+        for (header, name) in &names {
+            if name != header {
+                report.warn(format!("欄位 {} 不是合法或不重複的 XML 名稱，輸出為 {}", header, name));
+            }
+        }
+        let rows = Self::typed_records(headers, records, &options.values)?;
+        let mut writer = Writer::new_with_indent(BufWriter::new(File::create(xml_path)?), b' ', 2);
+
+        let root = xml_name(&options.root_element);
+        let row_element = xml_name(&options.row_element);
+
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+        writer.write_event(Event::Start(BytesStart::new(root.as_str())))?;
+
+        for row in &rows {
+            let mut start = BytesStart::new(row_element.as_str());
+            let mut children = Vec::new();
+
+            for (key, value) in row {
+                let name = names.get(key).cloned().unwrap_or_else(|| xml_name(key));
+                let as_attribute =
+                    options.all_attributes || options.attribute_columns.iter().any(|c| c == key);
+                match (as_attribute, value) {
+                    // null 的屬性直接省略
+                    (true, Value::Null) => {}
+                    (true, value) => start.push_attribute((name.as_str(), xml_text(value).as_str())),
+                    (false, value) => children.push((name, value)),
+                }
+            }
+
+            if children.is_empty() {
+                writer.write_event(Event::Empty(start))?;
+                continue;
+            }
+
+            writer.write_event(Event::Start(start))?;
+            for (name, value) in children {
+                match value {
+                    // null 輸出為空元素
+                    Value::Null => writer.write_event(Event::Empty(BytesStart::new(name.as_str())))?,
+                    value => {
+                        writer.write_event(Event::Start(BytesStart::new(name.as_str())))?;
+                        writer.write_event(Event::Text(BytesText::new(&xml_text(value))))?;
+                        writer.write_event(Event::End(BytesEnd::new(name.as_str())))?;
+                    }
+                }
+            }
+            writer.write_event(Event::End(BytesEnd::new(row_element.as_str())))?;
+        }
+
+        writer.write_event(Event::End(BytesEnd::new(root.as_str())))?;
+        writer.get_mut().write_all(b"\n")?;
+        writer.into_inner().flush()?;
+        Ok(report.finish(records.len(), 0, xml_path))
+    }
+}
+
+/// 將數字轉為一般的 i64 / u64 / f64，供非 JSON 的序列化器使用
+///
+/// 啟用 `arbitrary_precision` 後 `serde_json::Number` 只能由 serde_json 本身序列化
+enum PlainNumber {
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+}
+
+/// 保留原始字面的小數（例如 `0.10` 或超過 f64 精度的位數）轉為 f64 會改變寫法或數值，回傳 None
+fn plain_number(n: &Number) -> Option<PlainNumber> {
+    if let Some(i) = n.as_i64() {
+        Some(PlainNumber::Int(i))
+    } else if let Some(u) = n.as_u64() {
+        Some(PlainNumber::UInt(u))
+    } else {
+        // 超出 u64 範圍的整數同樣無法以 f64 寫回原字面
+        let f = n.as_f64()?;
+        let round_trips = Number::from_f64(f).is_some_and(|plain| plain.to_string() == n.to_string());
+        round_trips.then_some(PlainNumber::Float(f))
+    }
+}
+
+/// 轉為 YAML 值；無法以原生數字精確表示的大整數與小數保留為字串
+fn yaml_value(value: &Value) -> serde_yaml::Value {
+    match value {
+        Value::Null => serde_yaml::Value::Null,
+        Value::Bool(b) => serde_yaml::Value::Bool(*b),
+        Value::Number(n) => match plain_number(n) {
+            Some(PlainNumber::Int(i)) => serde_yaml::Value::Number(i.into()),
+            Some(PlainNumber::UInt(u)) => serde_yaml::Value::Number(u.into()),
+            Some(PlainNumber::Float(f)) => serde_yaml::Value::Number(f.into()),
+            None => serde_yaml::Value::String(n.to_string()),
+        },
+        Value::String(s) => serde_yaml::Value::String(s.clone()),
+        Value::Array(items) => serde_yaml::Value::Sequence(items.iter().map(yaml_value).collect()),
+        Value::Object(map) => serde_yaml::Value::Mapping(
+            map.iter()
+                .map(|(k, v)| (serde_yaml::Value::String(k.clone()), yaml_value(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// 轉為 TOML 值；null 回傳 None，超出 i64 範圍的整數與無法以 f64 精確表示的小數保留為字串
+fn toml_value(value: &Value) -> Option<toml::Value> {
+    Some(match value {
+        Value::Null => return None,
+        Value::Bool(b) => toml::Value::Boolean(*b),
+        Value::Number(n) => match plain_number(n) {
+            Some(PlainNumber::Int(i)) => toml::Value::Integer(i),
+            Some(PlainNumber::Float(f)) => toml::Value::Float(f),
+            Some(PlainNumber::UInt(_)) | None => toml::Value::String(n.to_string()),
+        },
+        Value::String(s) => toml::Value::String(s.clone()),
+        Value::Array(items) => toml::Value::Array(items.iter().filter_map(toml_value).collect()),
+        Value::Object(map) => toml::Value::Table(
+            map.iter()
+                .filter_map(|(k, v)| toml_value(v).map(|v| (k.clone(), v)))
+                .collect(),
+        ),
+    })
+}
+
+/// XML 文字內容；數字保留原始字面
+fn xml_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// 每個欄位對應的 XML 名稱；轉換後與先前的名稱重複時加上 `_2`、`_3` 等後綴
+fn xml_names(headers: &[String]) -> HashMap<String, String> {
+    let mut names: HashMap<String, String> = HashMap::new();
+    let mut used = HashSet::new();
+    for header in headers {
+        if names.contains_key(header) {
+            continue;
+        }
+        let base = xml_name(header);
+        let mut name = base.clone();
+        let mut n = 1;
+        while !used.insert(name.clone()) {
+            n += 1;
+            name = format!("{}_{}", base, n);
+        }
+        names.insert(header.clone(), name);
+    }
+    names
+}
+
+/// 將欄位名稱轉為合法的 XML 元素名稱：非法字元換成 `_`，不能以數字或符號開頭
+fn xml_name(name: &str) -> String {
+    let mut result: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '_' | '-' | '.') { c } else { '_' })
+        .collect();
+    if !result.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        result.insert(0, '_');
+    }
+    result
+}
@@ -0,0 +1,165 @@
+use anyhow::{bail, Context};
+use csv::StringRecord;
+use serde_json::{json, Map, Value};
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::converter::CsvConverter;
+use crate::rejects::RejectWriter;
+use crate::report::{ConversionReport, ReportBuilder};
+use crate::value::{NumberLocale, ValueOptions};
+
+/// 自動偵測緯度欄位時使用的名稱（不分大小寫）
+pub const LATITUDE_NAMES: &[&str] = &["lat", "latitude", "緯度"];
+/// 自動偵測經度欄位時使用的名稱（不分大小寫）
+pub const LONGITUDE_NAMES: &[&str] = &["lon", "lng", "long", "longitude", "經度"];
+
+/// 經緯度範圍，順序與 GeoJSON `bbox` 相同
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min_lon: f64,
+    pub min_lat: f64,
+    pub max_lon: f64,
+    pub max_lat: f64,
+}
+
+impl BoundingBox {
+    pub fn contains(&self, lon: f64, lat: f64) -> bool {
+        (self.min_lon..=self.max_lon).contains(&lon) && (self.min_lat..=self.max_lat).contains(&lat)
+    }
+}
+
+impl FromStr for BoundingBox {
+    type Err = anyhow::Error;
+
+    /// 解析 `最小經度,最小緯度,最大經度,最大緯度`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s
+            .split(',')
+            .map(|p| p.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("無效的範圍: {}", s))?;
+        let [min_lon, min_lat, max_lon, max_lat] = parts[..] else {
+            bail!("範圍必須為 min_lon,min_lat,max_lon,max_lat: {}", s);
+        };
+        if min_lon > max_lon || min_lat > max_lat {
+            bail!("範圍的最小值大於最大值: {}", s);
+        }
+        Ok(BoundingBox { min_lon, min_lat, max_lon, max_lat })
+    }
+}
+
+/// GeoJSON 匯出設定
+#[derive(Debug, Clone, Default)]
+pub struct GeoJsonOptions {
+    /// 緯度欄位，未指定時依 [`LATITUDE_NAMES`] 自動偵測
+    pub lat_column: Option<String>,
+    /// 經度欄位，未指定時依 [`LONGITUDE_NAMES`] 自動偵測
+    pub lon_column: Option<String>,
+    /// 只輸出位於此範圍內的點
+    pub bbox: Option<BoundingBox>,
+    /// 座標無效的記錄寫到此 CSV 檔案
+    pub rejects_path: Option<String>,
+    /// 其餘欄位（properties）的值轉換設定
+    pub values: ValueOptions,
+}
+
+impl CsvConverter {
+    /// 將含經緯度欄位的 CSV 檔案轉換為 GeoJSON 檔案
+    pub fn convert_csv_to_geojson_file(
+        csv_path: &str,
+        geojson_path: &str,
+        options: &GeoJsonOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let started = Instant::now();
+        let (headers, records) = Self::read_csv_file(csv_path)?;
+        Self::write_geojson_file(&headers, &records, geojson_path, options)?.with_input(csv_path, started)
+    }
+
+    /// 將記錄寫成 `FeatureCollection`，每筆記錄為一個 `Point`，其餘欄位作為 properties
+    pub fn write_geojson_file(
+        headers: &[String],
+        records: &[StringRecord],
+        geojson_path: &str,
+        options: &GeoJsonOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let mut report = ReportBuilder::new(headers, records, &options.values);
+        let lat_idx = find_column(headers, options.lat_column.as_deref(), LATITUDE_NAMES, "緯度")?;
+        let lon_idx = find_column(headers, options.lon_column.as_deref(), LONGITUDE_NAMES, "經度")?;
+        let mut rejects = RejectWriter::create(options.rejects_path.as_deref(), headers)?;
+
+        let locale = options.values.locale;
+        let mut features = Vec::new();
+        let mut outside = 0;
+        for (row, record) in records.iter().enumerate() {
+            let lat = record.get(lat_idx).unwrap_or("");
+            let lon = record.get(lon_idx).unwrap_or("");
+            let (lat, lon) = match (parse_coordinate(lat, locale, 90.0), parse_coordinate(lon, locale, 180.0)) {
+                (Some(lat), Some(lon)) => (lat, lon),
+                _ => {
+                    rejects.reject(record, &format!("無效的座標: lat={:?}, lon={:?}", lat, lon))?;
+                    continue;
+                }
+            };
+
+            if options.bbox.is_some_and(|bbox| !bbox.contains(lon, lat)) {
+                outside += 1;
+                continue;
+            }
+
+            let mut properties = Map::new();
+            for (i, (header, field)) in headers.iter().zip(record.iter()).enumerate() {
+                if i != lat_idx && i != lon_idx {
+                    properties.insert(header.clone(), options.values.parse_cell(row, i, header, field)?);
+                }
+            }
+
+            features.push(json!({
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": [lon, lat] },
+                "properties": Value::Object(properties),
+            }));
+        }
+        let rejected = rejects.finish()?;
+        if rejected > 0 {
+            report.warn(format!("{} 筆記錄的座標無效", rejected));
+        }
+        if outside > 0 {
+            report.warn(format!("{} 筆記錄不在指定範圍內，已略過", outside));
+        }
+
+        let written = features.len();
+        let mut collection = json!({ "type": "FeatureCollection", "features": features });
+        if let Some(bbox) = options.bbox {
+            collection["bbox"] = json!([bbox.min_lon, bbox.min_lat, bbox.max_lon, bbox.max_lat]);
+        }
+
+        std::fs::write(geojson_path, serde_json::to_string_pretty(&collection)?)?;
+        Ok(report.finish(written, rejected, geojson_path))
+    }
+}
+
+/// 找出指定的欄位，未指定時依候選名稱自動偵測
+fn find_column(headers: &[String], name: Option<&str>, candidates: &[&str], label: &str) -> anyhow::Result<usize> {
+    match name {
+        Some(name) => headers
+            .iter()
+            .position(|h| h == name)
+            .with_context(|| format!("找不到{}欄位: {}", label, name)),
+        None => headers
+            .iter()
+            .position(|h| candidates.iter().any(|c| h.trim().eq_ignore_ascii_case(c)))
+            .with_context(|| format!("無法自動偵測{}欄位，請明確指定", label)),
+    }
+}
+
+/// 解析座標值，必須是有限數值且絕對值不超過 `limit`；小數點依地區格式，例如 de 的 `25,03`
+fn parse_coordinate(field: &str, locale: NumberLocale, limit: f64) -> Option<f64> {
+    let field = field.trim();
+    let (_, decimal) = locale.separators();
+    let value = match decimal {
+        '.' => field.parse::<f64>(),
+        decimal => field.replacen(decimal, ".", 1).parse::<f64>(),
+    };
+    value.ok().filter(|v| v.is_finite() && v.abs() <= limit)
+}
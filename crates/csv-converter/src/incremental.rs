@@ -0,0 +1,380 @@
+//! 增量轉換：只轉換上次執行後附加到 CSV 檔尾的新列
+//!
+//! 狀態檔記錄已轉換的位元組位置，以及標題列與最後一筆記錄的雜湊。
+//! 下次執行時若雜湊不符或檔案變短，表示檔案被截斷或輪替，會改為完整重新轉換。
+
+use anyhow::{bail, Context};
+use csv::{ByteRecord, Reader, ReaderBuilder, StringRecord};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use crate::converter::CsvConverter;
+use crate::report::{output_size, ConversionReport, ReportBuilder};
+use crate::schema::ColumnType;
+use crate::value::ValueOptions;
+
+/// 狀態檔格式版本，格式改變時舊狀態檔會觸發完整重新轉換
+const STATE_VERSION: u32 = 1;
+
+/// 增量輸出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncrementalFormat {
+    /// 每列一個 JSON 物件，新列直接附加到檔尾
+    Ndjson,
+    /// 輸出路徑為目錄，每次新增一個 `part-NNNNN.parquet` 檔案
+    Parquet,
+}
+
+impl IncrementalFormat {
+    /// 依副檔名判斷：`.ndjson`、`.jsonl` 或 `.parquet`
+    pub fn from_path(path: &str) -> anyhow::Result<Self> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("ndjson" | "jsonl") => Ok(IncrementalFormat::Ndjson),
+            Some("parquet") => Ok(IncrementalFormat::Parquet),
+            _ => bail!("增量轉換只支援 .ndjson、.jsonl 或 .parquet 輸出: {}", path),
+        }
+    }
+}
+
+/// 增量轉換設定
+#[derive(Debug, Clone, Default)]
+pub struct IncrementalOptions {
+    /// 狀態檔路徑，未指定時為 `<輸出路徑>.state.json`
+    pub state_path: Option<String>,
+    pub values: ValueOptions,
+}
+
+/// 一次增量轉換的結果
+#[derive(Debug, Clone)]
+pub struct IncrementalSummary {
+    /// 本次轉換的報告，位元組數只計算本次新讀取與新寫出的部分
+    pub report: ConversionReport,
+    /// 輸出中的總列數
+    pub total_rows: u64,
+    /// 進行完整重新轉換時的原因
+    pub full_rebuild: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IncrementalState {
+    version: u32,
+    header_checksum: String,
+    /// 已轉換資料的結尾位置
+    offset: u64,
+    /// 最後一筆已轉換記錄的起點，與 `offset` 之間的內容雜湊為 `last_record_checksum`
+    last_record_start: u64,
+    last_record_checksum: String,
+    rows: u64,
+    /// Parquet 已寫出的檔案數
+    parts: usize,
+    /// Parquet 各欄位型別，後續的檔案沿用相同型別
+    column_types: Vec<String>,
+}
+
+/// 從檔案讀出的新記錄與其位置
+struct NewRecords {
+    records: Vec<StringRecord>,
+    /// 最後一筆完整記錄的起點與結尾
+    last_record: Option<(u64, u64)>,
+}
+
+impl CsvConverter {
+    /// 將 CSV 檔案增量轉換為 NDJSON 或 Parquet，回傳本次轉換的摘要
+    ///
+    /// 輸出寫入完成後才更新狀態檔；若兩者之間中斷，下次執行可能重複輸出最後一批記錄。
+    pub fn convert_csv_incremental(
+        csv_path: &str,
+        output_path: &str,
+        options: &IncrementalOptions,
+    ) -> anyhow::Result<IncrementalSummary> {
+        let started = Instant::now();
+        let format = IncrementalFormat::from_path(output_path)?;
+        let state_path = options
+            .state_path
+            .clone()
+            .unwrap_or_else(|| format!("{}.state.json", output_path));
+
+        let mut file = File::open(csv_path)?;
+        let file_len = file.metadata()?.len();
+        let mut reader = Reader::from_reader(&mut file);
+        let headers: Vec<String> = reader.headers()?.iter().map(|h| h.to_string()).collect();
+        let header_end = reader.position().byte();
+        drop(reader);
+        let header_checksum = checksum_range(&mut file, 0, header_end)?;
+
+        // 沒有狀態檔時從頭轉換；狀態檔失效時記錄原因
+        let (previous, rebuild_reason) = match load_state(&state_path) {
+            Ok(Some(state)) => match check_state(state, &mut file, file_len, &header_checksum, output_path) {
+                Ok(state) => (Some(state), None),
+                Err(reason) => (None, Some(reason)),
+            },
+            Ok(None) => (None, None),
+            Err(reason) => (None, Some(reason)),
+        };
+        let fresh = previous.is_none();
+        let mut state = previous.unwrap_or(IncrementalState {
+            version: STATE_VERSION,
+            header_checksum,
+            offset: header_end,
+            last_record_start: header_end,
+            last_record_checksum: String::new(),
+            rows: 0,
+            parts: 0,
+            column_types: Vec::new(),
+        });
+
+        let new = read_new_records(&mut file, state.offset, file_len, headers.len())?;
+        let report = ReportBuilder::new(&headers, &new.records, &options.values);
+        let size_before = if fresh { 0 } else { output_size(Path::new(output_path)) };
+        let bytes_in = new.last_record.map_or(0, |(_, end)| end - state.offset);
+        match format {
+            IncrementalFormat::Ndjson => write_ndjson(&headers, &new.records, output_path, !fresh, &options.values)?,
+            IncrementalFormat::Parquet => {
+                if !fresh {
+                    if let Some(reason) = incompatible_types(&headers, &new.records, &state, &options.values)? {
+                        // 型別改變時無法沿用既有檔案，從頭重新轉換
+                        return Self::rebuild_with_reason(csv_path, output_path, options, &state_path, reason);
+                    }
+                }
+                write_parquet_part(&headers, &new.records, output_path, &mut state, fresh, &options.values)?;
+            }
+        }
+
+        if let Some((start, end)) = new.last_record {
+            state.last_record_start = start;
+            state.last_record_checksum = checksum_range(&mut file, start, end)?;
+            state.offset = end;
+        }
+        state.rows += new.records.len() as u64;
+        save_state(&state_path, &state)?;
+
+        let mut report = report.finish(new.records.len(), 0, output_path);
+        report.bytes_in = bytes_in;
+        report.bytes_out = report.bytes_out.saturating_sub(size_before);
+        report.elapsed_ms = started.elapsed().as_millis() as u64;
+        Ok(IncrementalSummary {
+            report,
+            total_rows: state.rows,
+            full_rebuild: rebuild_reason,
+        })
+    }
+
+    /// 刪除狀態檔後重新執行，讓摘要帶有重新轉換的原因
+    fn rebuild_with_reason(
+        csv_path: &str,
+        output_path: &str,
+        options: &IncrementalOptions,
+        state_path: &str,
+        reason: String,
+    ) -> anyhow::Result<IncrementalSummary> {
+        std::fs::remove_file(state_path)?;
+        let summary = Self::convert_csv_incremental(csv_path, output_path, options)?;
+        Ok(IncrementalSummary { full_rebuild: Some(reason), ..summary })
+    }
+}
+
+fn load_state(path: &str) -> Result<Option<IncrementalState>, String> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("無法讀取狀態檔: {}", e)),
+    };
+    serde_json::from_str(&text).map(Some).map_err(|e| format!("狀態檔格式錯誤: {}", e))
+}
+
+/// 確認上次轉換的內容仍在原位，否則回傳需要重新轉換的原因
+fn check_state(
+    state: IncrementalState,
+    file: &mut File,
+    file_len: u64,
+    header_checksum: &str,
+    output_path: &str,
+) -> Result<IncrementalState, String> {
+    if state.version != STATE_VERSION {
+        return Err(format!("狀態檔版本不符: {}", state.version));
+    }
+    if state.header_checksum != header_checksum {
+        return Err("標題列已改變".to_string());
+    }
+    if file_len < state.offset {
+        return Err("檔案被截斷".to_string());
+    }
+    if state.last_record_start < state.offset {
+        let checksum = checksum_range(file, state.last_record_start, state.offset).map_err(|e| e.to_string())?;
+        if checksum != state.last_record_checksum {
+            return Err("檔案已被輪替或改寫".to_string());
+        }
+    }
+    if !Path::new(output_path).exists() {
+        return Err("輸出檔不存在".to_string());
+    }
+    Ok(state)
+}
+
+fn save_state(path: &str, state: &IncrementalState) -> anyhow::Result<()> {
+    // 先寫到暫存檔再改名，避免中斷時留下不完整的狀態檔
+    let temp_path = format!("{}.tmp", path);
+    std::fs::write(&temp_path, serde_json::to_string_pretty(state)?)?;
+    std::fs::rename(&temp_path, path).with_context(|| format!("無法寫入狀態檔: {}", path))?;
+    Ok(())
+}
+
+fn checksum_range(file: &mut File, start: u64, end: u64) -> anyhow::Result<String> {
+    file.seek(SeekFrom::Start(start))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file.take(end - start), &mut hasher)?;
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// 從 `offset` 讀到檔尾；最後一列沒有換行時可能仍在寫入，留到下次再轉換
+fn read_new_records(file: &mut File, offset: u64, file_len: u64, field_count: usize) -> anyhow::Result<NewRecords> {
+    let ends_with_newline = file_len > 0 && {
+        let mut last = [0u8];
+        file.seek(SeekFrom::Start(file_len - 1))?;
+        file.read_exact(&mut last)?;
+        last[0] == b'\n'
+    };
+
+    file.seek(SeekFrom::Start(offset))?;
+    // 允許欄位數不一致，才能先略過寫到一半的最後一列再檢查
+    let mut reader = ReaderBuilder::new().has_headers(false).flexible(true).from_reader(&mut *file);
+    let mut records = Vec::new();
+    let mut last_record = None;
+    let mut record = ByteRecord::new();
+    loop {
+        let start = offset + reader.position().byte();
+        if !reader.read_byte_record(&mut record)? {
+            break;
+        }
+        let end = offset + reader.position().byte();
+        if end == file_len && !ends_with_newline {
+            break;
+        }
+        if record.len() != field_count {
+            bail!("位元組位置 {} 的記錄有 {} 個欄位，標題列有 {} 個", start, record.len(), field_count);
+        }
+        records.push(StringRecord::from_byte_record(record.clone())?);
+        last_record = Some((start, end));
+    }
+    Ok(NewRecords { records, last_record })
+}
+
+fn write_ndjson(
+    headers: &[String],
+    records: &[StringRecord],
+    path: &str,
+    append: bool,
+    options: &ValueOptions,
+) -> anyhow::Result<()> {
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)?;
+    let mut out = BufWriter::new(file);
+    for row in CsvConverter::typed_records(headers, records, options)? {
+        serde_json::to_writer(&mut out, &Value::Object(row))?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// 檢查新記錄是否符合已寫出的 Parquet 欄位型別，不符合時回傳原因
+fn incompatible_types(
+    headers: &[String],
+    records: &[StringRecord],
+    state: &IncrementalState,
+    options: &ValueOptions,
+) -> anyhow::Result<Option<String>> {
+    if state.column_types.len() != headers.len() {
+        return Ok(Some("欄位型別記錄不完整".to_string()));
+    }
+    for (i, (header, column_type)) in headers.iter().zip(&state.column_types).enumerate() {
+        let column_type: ColumnType = column_type.parse()?;
+        for field in records.iter().map(|record| record.get(i).unwrap_or("")) {
+            if field.is_empty() {
+                continue;
+            }
+            let value = options.parse(header, field)?;
+            if let Some(new_type) = ColumnType::of_value(&value) {
+                if !column_type.accepts(new_type) {
+                    return Ok(Some(format!("欄位 {} 的型別由 {} 變為 {}", header, column_type, new_type)));
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(feature = "parquet")]
+fn write_parquet_part(
+    headers: &[String],
+    records: &[StringRecord],
+    dir: &str,
+    state: &mut IncrementalState,
+    fresh: bool,
+    options: &ValueOptions,
+) -> anyhow::Result<()> {
+    if fresh {
+        remove_parts(dir)?;
+        std::fs::create_dir_all(dir)?;
+        let schema = crate::schema::Schema::infer_with(headers, records, options);
+        state.column_types = schema.columns.iter().map(|c| c.column_type.as_str().to_string()).collect();
+        state.parts = 0;
+    }
+    if records.is_empty() && state.parts > 0 {
+        return Ok(());
+    }
+
+    let types = state
+        .column_types
+        .iter()
+        .map(|t| t.parse())
+        .collect::<anyhow::Result<Vec<ColumnType>>>()?;
+    let part_path = Path::new(dir).join(format!("part-{:05}.parquet", state.parts));
+    let part_path = part_path.to_str().context("輸出路徑不是有效的 UTF-8")?;
+    CsvConverter::write_parquet_file_with_types(headers, records, &types, part_path, options)?;
+    state.parts += 1;
+    Ok(())
+}
+
+#[cfg(not(feature = "parquet"))]
+fn write_parquet_part(
+    _headers: &[String],
+    _records: &[StringRecord],
+    _dir: &str,
+    _state: &mut IncrementalState,
+    _fresh: bool,
+    _options: &ValueOptions,
+) -> anyhow::Result<()> {
+    bail!("此版本未啟用 Parquet 支援，請以 --features parquet 重新編譯")
+}
+
+/// 只刪除先前寫出的 `part-*.parquet`，不動目錄中的其他檔案
+#[cfg(feature = "parquet")]
+fn remove_parts(dir: &str) -> anyhow::Result<()> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    for entry in entries {
+        let path = entry?.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        if name.starts_with("part-") && name.ends_with(".parquet") {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
@@ -1,2 +1,66 @@
+#[cfg(feature = "async")]
+pub mod async_io;
+pub mod avro;
+pub mod concat;
 mod converter;
+pub mod dialect;
+pub mod diff;
+mod fast;
+pub mod fixed_width;
+pub mod formats;
+pub mod geojson;
+pub mod incremental;
+pub mod lint;
+pub mod mask;
+pub mod nested;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+pub mod profile;
+pub mod progress;
+#[cfg(feature = "query")]
+pub mod query;
+pub mod rejects;
+pub mod report;
+pub mod reshape;
+pub mod sample;
+pub mod schema;
+#[cfg(feature = "serve")]
+pub mod serve;
+pub mod spreadsheet;
+pub mod sql;
+pub mod table;
+pub mod template;
+pub mod value;
+pub mod watch;
+
+#[cfg(feature = "async")]
+pub use async_io::{AsyncFormat, AsyncOptions};
+pub use avro::{AvroCodec, AvroOptions};
+pub use concat::{ConcatData, ConcatOptions, SOURCE_FILE_COLUMN};
 pub use converter::*;
+pub use dialect::Dialect;
+pub use diff::{CellChange, DiffOptions, RowChange, TableDiff};
+pub use fixed_width::{FixedWidthColumn, FixedWidthData, FixedWidthLayout, LineIssue};
+pub use formats::{TomlOptions, XmlOptions, YamlOptions};
+pub use geojson::{BoundingBox, GeoJsonOptions};
+pub use incremental::{IncrementalFormat, IncrementalOptions, IncrementalSummary};
+pub use lint::{FormatOptions, LintIssue, LintKind, QuoteStyle};
+pub use mask::{ColumnMask, Generalization, MaskRule, MaskedData, MaskingRules};
+pub use nested::{GroupAggregate, NestShape, NestedOptions};
+pub use profile::ColumnProfile;
+pub use progress::{CancellationToken, Cancelled, Progress, ProgressMonitor, ProgressObserver};
+#[cfg(feature = "query")]
+pub use query::{QueryData, QueryOptions};
+pub use rejects::RejectWriter;
+pub use report::ConversionReport;
+pub use reshape::{Aggregate, PivotOptions, UnpivotOptions};
+pub use sample::{SampleOptions, Sampling};
+pub use schema::{ColumnSchema, ColumnType, Schema, SchemaViolation};
+#[cfg(feature = "serve")]
+pub use serve::ServeOptions;
+pub use spreadsheet::{SheetSelector, SpreadsheetOptions, XlsxExportOptions};
+pub use sql::SqlExportOptions;
+pub use table::{Alignment, TableOptions};
+pub use template::TemplateOptions;
+pub use value::{NonFinitePolicy, NullCells, NumberLocale, NumericPolicy, ParseRule, ValueOptions};
+pub use watch::{DirectoryWatcher, WatchConfig, WatchFormat, WatchOutcome};
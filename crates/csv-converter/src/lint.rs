@@ -0,0 +1,308 @@
+//! CSV 檢查（lint）與標準格式化（fmt），可作為 CSV 測試資料的 pre-commit 檢查
+
+use anyhow::{bail, Context};
+use csv::{ReaderBuilder, Terminator, WriterBuilder};
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use crate::converter::CsvConverter;
+
+/// 檢查項目
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintKind {
+    /// 欄位數與標題列不同
+    FieldCount,
+    /// 同一檔案混用 LF、CRLF 或 CR 換行
+    MixedLineEndings,
+    /// 未加引號的欄位中出現引號、引號後接其他字元或引號未關閉
+    StrayQuote,
+    /// 未加引號的欄位或標題前後有空白
+    TrailingWhitespace,
+    DuplicateHeader,
+    BlankHeader,
+    /// 不是有效的 UTF-8
+    InvalidUtf8,
+}
+
+impl LintKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintKind::FieldCount => "field-count",
+            LintKind::MixedLineEndings => "mixed-line-endings",
+            LintKind::StrayQuote => "stray-quote",
+            LintKind::TrailingWhitespace => "trailing-whitespace",
+            LintKind::DuplicateHeader => "duplicate-header",
+            LintKind::BlankHeader => "blank-header",
+            LintKind::InvalidUtf8 => "invalid-utf8",
+        }
+    }
+}
+
+/// 檢查發現的問題與所在行號（從 1 開始）
+#[derive(Debug, Clone)]
+pub struct LintIssue {
+    pub line: usize,
+    pub kind: LintKind,
+    pub message: String,
+}
+
+/// 標準格式的引號規則
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuoteStyle {
+    /// 只在需要時加引號
+    #[default]
+    Necessary,
+    Always,
+    /// 非數值欄位一律加引號
+    NonNumeric,
+}
+
+impl QuoteStyle {
+    fn to_csv(self) -> csv::QuoteStyle {
+        match self {
+            QuoteStyle::Necessary => csv::QuoteStyle::Necessary,
+            QuoteStyle::Always => csv::QuoteStyle::Always,
+            QuoteStyle::NonNumeric => csv::QuoteStyle::NonNumeric,
+        }
+    }
+}
+
+impl FromStr for QuoteStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "necessary" => Ok(QuoteStyle::Necessary),
+            "always" => Ok(QuoteStyle::Always),
+            "non-numeric" => Ok(QuoteStyle::NonNumeric),
+            _ => bail!("未知的引號規則: {}（可用: necessary, always, non-numeric）", s),
+        }
+    }
+}
+
+/// 標準格式設定；輸出一律使用 LF 換行，標題去除前後空白
+#[derive(Debug, Clone, Default)]
+pub struct FormatOptions {
+    pub quote_style: QuoteStyle,
+    /// 同時去除每個欄位值的前後空白
+    pub trim_fields: bool,
+}
+
+impl CsvConverter {
+    /// 檢查 CSV 檔案，依行號順序回傳所有問題
+    pub fn lint_csv_file(csv_path: &str) -> anyhow::Result<Vec<LintIssue>> {
+        let bytes = std::fs::read(csv_path).with_context(|| format!("無法讀取 {}", csv_path))?;
+        Ok(lint_bytes(&bytes))
+    }
+
+    /// 以標準格式重寫 CSV，回傳格式化後的內容；輸入有無法解析的問題時回傳錯誤
+    pub fn format_csv_file(csv_path: &str, options: &FormatOptions) -> anyhow::Result<Vec<u8>> {
+        let mut reader = ReaderBuilder::new()
+            .from_path(csv_path)
+            .with_context(|| format!("無法讀取 {}", csv_path))?;
+        let mut writer = WriterBuilder::new()
+            .quote_style(options.quote_style.to_csv())
+            .terminator(Terminator::Any(b'\n'))
+            .from_writer(Vec::new());
+
+        let headers: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_string()).collect();
+        writer.write_record(&headers)?;
+        for record in reader.records() {
+            let record = record?;
+            if options.trim_fields {
+                writer.write_record(record.iter().map(str::trim))?;
+            } else {
+                writer.write_record(&record)?;
+            }
+        }
+        Ok(writer.into_inner().map_err(|e| e.into_error())?)
+    }
+}
+
+/// 一筆記錄的原始欄位
+struct RawRecord {
+    line: usize,
+    fields: Vec<RawField>,
+}
+
+impl RawRecord {
+    /// 空白行不算記錄，csv 讀取器也會略過
+    fn is_blank(&self) -> bool {
+        self.fields.len() == 1 && self.fields[0].value.is_empty() && !self.fields[0].quoted
+    }
+}
+
+struct RawField {
+    value: Vec<u8>,
+    quoted: bool,
+}
+
+/// 逐位元組掃描，保留 csv 讀取器會吞掉的細節（換行種類、引號位置、原始位元組）
+fn lint_bytes(bytes: &[u8]) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let mut records: Vec<RawRecord> = Vec::new();
+    // 檔案中第一次出現的換行種類
+    let mut first_ending: Option<&str> = None;
+
+    let mut line = 1;
+    let mut record = RawRecord { line, fields: Vec::new() };
+    let mut field = RawField { value: Vec::new(), quoted: false };
+    let mut in_quotes = false;
+    // 已關閉的引號欄位後又出現其他字元時只回報一次
+    let mut after_closing_quote = false;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let byte = bytes[i];
+        if in_quotes {
+            match byte {
+                b'"' if bytes.get(i + 1) == Some(&b'"') => {
+                    field.value.push(b'"');
+                    i += 1;
+                }
+                b'"' => {
+                    in_quotes = false;
+                    after_closing_quote = true;
+                }
+                b'\n' => {
+                    line += 1;
+                    field.value.push(byte);
+                }
+                _ => field.value.push(byte),
+            }
+            i += 1;
+            continue;
+        }
+
+        match byte {
+            b',' => {
+                record.fields.push(std::mem::replace(&mut field, RawField { value: Vec::new(), quoted: false }));
+                after_closing_quote = false;
+            }
+            b'\r' | b'\n' => {
+                let ending = match (byte, bytes.get(i + 1)) {
+                    (b'\r', Some(b'\n')) => {
+                        i += 1;
+                        "CRLF"
+                    }
+                    (b'\r', _) => "CR",
+                    _ => "LF",
+                };
+                match first_ending {
+                    None => first_ending = Some(ending),
+                    // 只回報第一次出現不同換行的位置
+                    Some(first)
+                        if first != ending
+                            && !issues.iter().any(|issue: &LintIssue| issue.kind == LintKind::MixedLineEndings) =>
+                    {
+                        issues.push(LintIssue {
+                            line,
+                            kind: LintKind::MixedLineEndings,
+                            message: format!("以 {} 換行，但檔案先前使用 {}", ending, first),
+                        });
+                    }
+                    _ => {}
+                }
+                record.fields.push(std::mem::replace(&mut field, RawField { value: Vec::new(), quoted: false }));
+                records.push(std::mem::replace(&mut record, RawRecord { line: line + 1, fields: Vec::new() }));
+                after_closing_quote = false;
+                line += 1;
+            }
+            b'"' if field.value.is_empty() && !field.quoted => {
+                field.quoted = true;
+                in_quotes = true;
+            }
+            _ => {
+                if after_closing_quote {
+                    issues.push(LintIssue {
+                        line,
+                        kind: LintKind::StrayQuote,
+                        message: "引號欄位的結尾引號後還有其他字元".to_string(),
+                    });
+                    after_closing_quote = false;
+                } else if byte == b'"' && !field.quoted {
+                    issues.push(LintIssue {
+                        line,
+                        kind: LintKind::StrayQuote,
+                        message: "未加引號的欄位中出現引號".to_string(),
+                    });
+                }
+                field.value.push(byte);
+            }
+        }
+        i += 1;
+    }
+
+    if in_quotes {
+        issues.push(LintIssue {
+            line: record.line,
+            kind: LintKind::StrayQuote,
+            message: "引號未關閉，直到檔案結尾".to_string(),
+        });
+    }
+    // 沒有結尾換行的最後一筆記錄
+    if !record.fields.is_empty() || !field.value.is_empty() || field.quoted {
+        record.fields.push(field);
+        records.push(record);
+    }
+
+    check_records(&records, &mut issues);
+    issues.sort_by_key(|issue| issue.line);
+    issues
+}
+
+/// 檢查標題、欄位數、空白與編碼
+fn check_records(records: &[RawRecord], issues: &mut Vec<LintIssue>) {
+    // 與 csv 讀取器相同，標題列是第一個非空白行
+    let Some(header) = records.iter().find(|record| !record.is_blank()) else {
+        return;
+    };
+
+    let mut seen = HashSet::new();
+    for (i, field) in header.fields.iter().enumerate() {
+        let name = String::from_utf8_lossy(&field.value);
+        if name.trim().is_empty() {
+            issues.push(LintIssue {
+                line: header.line,
+                kind: LintKind::BlankHeader,
+                message: format!("第 {} 欄的標題為空白", i + 1),
+            });
+        } else if !seen.insert(name.trim().to_string()) {
+            issues.push(LintIssue {
+                line: header.line,
+                kind: LintKind::DuplicateHeader,
+                message: format!("標題 {} 重複", name.trim()),
+            });
+        }
+    }
+
+    for record in records {
+        if record.is_blank() {
+            continue;
+        }
+        if record.line != header.line && record.fields.len() != header.fields.len() {
+            issues.push(LintIssue {
+                line: record.line,
+                kind: LintKind::FieldCount,
+                message: format!("有 {} 個欄位，標題列有 {} 個", record.fields.len(), header.fields.len()),
+            });
+        }
+        for (i, field) in record.fields.iter().enumerate() {
+            if std::str::from_utf8(&field.value).is_err() {
+                issues.push(LintIssue {
+                    line: record.line,
+                    kind: LintKind::InvalidUtf8,
+                    message: format!("第 {} 欄含有非 UTF-8 位元組", i + 1),
+                });
+            }
+            let padded = |b: Option<&u8>| matches!(b, Some(b' ' | b'\t'));
+            if !field.quoted && (padded(field.value.first()) || padded(field.value.last())) {
+                issues.push(LintIssue {
+                    line: record.line,
+                    kind: LintKind::TrailingWhitespace,
+                    message: format!("第 {} 欄的值前後有空白", i + 1),
+                });
+            }
+        }
+    }
+}
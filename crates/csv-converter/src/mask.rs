@@ -0,0 +1,223 @@
+//! 個資遮罩：在轉換前依欄位規則雜湊、遮蔽、概括化或移除欄位
+
+use anyhow::{bail, Context};
+use chrono::{Datelike, NaiveDate};
+use csv::StringRecord;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::str::FromStr;
+
+use crate::converter::CsvConverter;
+
+/// 讀取鹽值的環境變數，避免鹽值出現在指令歷史與行程列表中
+pub const MASK_SALT_ENV: &str = "CSV_MASK_SALT";
+/// redact 規則的替代文字
+pub const REDACTED: &str = "[REDACTED]";
+
+/// 概括化方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Generalization {
+    /// 數字依區間分組，例如區間大小 10 時 `37` → `30-39`
+    Bucket(u64),
+    /// 日期只保留年月
+    Month,
+    /// 日期只保留年份
+    Year,
+}
+
+/// 單一欄位的遮罩規則
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskRule {
+    /// 加鹽雜湊（HMAC-SHA256），相同輸入得到相同代號
+    Hash,
+    /// 以 [`REDACTED`] 取代
+    Redact,
+    /// 只保留最後 N 個字元，例如 `****1234`
+    Partial(usize),
+    Generalize(Generalization),
+    /// 從輸出中移除整個欄位
+    Drop,
+}
+
+impl MaskRule {
+    /// 由規則名稱與參數建立，例如 (`partial`, `4`)、(`generalize`, `month`)
+    pub fn parse(rule: &str, param: &str) -> anyhow::Result<Self> {
+        let param = param.trim();
+        Ok(match rule.trim() {
+            "hash" => MaskRule::Hash,
+            "redact" => MaskRule::Redact,
+            "partial" if param.is_empty() => MaskRule::Partial(4),
+            "partial" => MaskRule::Partial(param.parse().with_context(|| format!("無效的保留字元數: {}", param))?),
+            "generalize" => MaskRule::Generalize(match param {
+                "" | "month" => Generalization::Month,
+                "year" => Generalization::Year,
+                size => match size.parse::<u64>() {
+                    Ok(size) if size > i64::MAX as u64 => bail!("區間大小超出範圍: {}", size),
+                    Ok(size) if size > 0 => Generalization::Bucket(size),
+                    _ => bail!("未知的概括化參數: {}（可用: month, year, 正整數區間大小）", size),
+                },
+            }),
+            "drop" => MaskRule::Drop,
+            other => bail!("未知的遮罩規則: {}（可用: hash, redact, partial, generalize, drop）", other),
+        })
+    }
+}
+
+impl FromStr for MaskRule {
+    type Err = anyhow::Error;
+
+    /// 解析 `規則` 或 `規則:參數`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (rule, param) = s.split_once(':').unwrap_or((s, ""));
+        MaskRule::parse(rule, param)
+    }
+}
+
+/// 欄位名稱與其遮罩規則
+#[derive(Debug, Clone)]
+pub struct ColumnMask {
+    pub column: String,
+    pub rule: MaskRule,
+}
+
+/// 遮罩設定檔的內容
+#[derive(Debug, Clone, Default)]
+pub struct MaskingRules {
+    pub columns: Vec<ColumnMask>,
+}
+
+impl MaskingRules {
+    /// 從 CSV 設定檔讀取規則，需包含 column、rule 欄位，可選 param 欄位
+    pub fn from_csv_file(path: &str) -> anyhow::Result<Self> {
+        let (headers, records) =
+            CsvConverter::read_csv_file(path).with_context(|| format!("無法讀取遮罩設定檔: {}", path))?;
+
+        let position = |name: &str| headers.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+        let (Some(column_idx), Some(rule_idx)) = (position("column"), position("rule")) else {
+            bail!("遮罩設定檔必須包含 column、rule 欄位: {}", path);
+        };
+        let param_idx = position("param");
+
+        let mut columns = Vec::with_capacity(records.len());
+        for (i, record) in records.iter().enumerate() {
+            let field = |idx: usize| record.get(idx).unwrap_or("").trim();
+            let column = field(column_idx).to_string();
+            if columns.iter().any(|c: &ColumnMask| c.column == column) {
+                bail!("遮罩設定第 {} 列重複指定欄位: {}", i + 2, column);
+            }
+            let rule = MaskRule::parse(field(rule_idx), param_idx.map(field).unwrap_or(""))
+                .with_context(|| format!("遮罩設定第 {} 列無效", i + 2))?;
+            columns.push(ColumnMask { column, rule });
+        }
+        Ok(MaskingRules { columns })
+    }
+
+    fn uses_hash(&self) -> bool {
+        self.columns.iter().any(|c| c.rule == MaskRule::Hash)
+    }
+}
+
+/// 遮罩結果
+#[derive(Debug, Clone, Default)]
+pub struct MaskedData {
+    pub headers: Vec<String>,
+    pub records: Vec<StringRecord>,
+    /// 保留的欄位在原始標題中的位置，用來重新對應 [`NullCells`](crate::NullCells) 等以欄位位置記錄的資料
+    pub kept_columns: Vec<usize>,
+}
+
+impl CsvConverter {
+    /// 套用遮罩規則並回傳新的標題與記錄；鹽值只用於計算雜湊，不會寫入輸出
+    ///
+    /// 空欄位保持空白。錯誤訊息不包含欄位值，以免個資出現在日誌中。
+    pub fn mask_records(
+        headers: &[String],
+        records: &[StringRecord],
+        rules: &MaskingRules,
+        salt: Option<&str>,
+    ) -> anyhow::Result<MaskedData> {
+        let mac = match salt {
+            Some(salt) if !salt.is_empty() => Some(Hmac::<Sha256>::new_from_slice(salt.as_bytes())?),
+            _ if rules.uses_hash() => bail!("hash 規則需要鹽值（可設定環境變數 {}）", MASK_SALT_ENV),
+            _ => None,
+        };
+
+        let mut column_rules: Vec<Option<MaskRule>> = vec![None; headers.len()];
+        for mask in &rules.columns {
+            let idx = headers
+                .iter()
+                .position(|h| *h == mask.column)
+                .with_context(|| format!("遮罩規則指定的欄位不存在: {}", mask.column))?;
+            column_rules[idx] = Some(mask.rule);
+        }
+
+        let kept: Vec<usize> = (0..headers.len())
+            .filter(|&i| column_rules[i] != Some(MaskRule::Drop))
+            .collect();
+        let masked_headers = kept.iter().map(|&i| headers[i].clone()).collect();
+
+        let mut masked_records = Vec::with_capacity(records.len());
+        for (row, record) in records.iter().enumerate() {
+            let mut masked = StringRecord::with_capacity(record.as_slice().len(), kept.len());
+            for &i in &kept {
+                let field = record.get(i).unwrap_or("");
+                let value = match column_rules[i] {
+                    _ if field.is_empty() => String::new(),
+                    None | Some(MaskRule::Drop) => field.to_string(),
+                    Some(MaskRule::Hash) => pseudonym(mac.as_ref().expect("已檢查鹽值"), field),
+                    Some(MaskRule::Redact) => REDACTED.to_string(),
+                    Some(MaskRule::Partial(visible)) => partial_mask(field, visible),
+                    Some(MaskRule::Generalize(how)) => generalize(field, how).with_context(|| {
+                        format!("第 {} 列的欄位 {} 無法以 {:?} 概括化", row + 2, headers[i], how)
+                    })?,
+                };
+                masked.push_field(&value);
+            }
+            masked_records.push(masked);
+        }
+
+        Ok(MaskedData { headers: masked_headers, records: masked_records, kept_columns: kept })
+    }
+}
+
+/// HMAC-SHA256 的前 16 個十六進位字元
+fn pseudonym(mac: &Hmac<Sha256>, field: &str) -> String {
+    let mut mac = mac.clone();
+    mac.update(field.as_bytes());
+    mac.finalize().into_bytes()[..8].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 固定以 `****` 開頭，不洩漏原始長度
+fn partial_mask(field: &str, visible: usize) -> String {
+    let chars: Vec<char> = field.chars().collect();
+    if chars.len() <= visible {
+        return "****".to_string();
+    }
+    let tail: String = chars[chars.len() - visible..].iter().collect();
+    format!("****{}", tail)
+}
+
+fn generalize(field: &str, how: Generalization) -> Option<String> {
+    let field = field.trim();
+    match how {
+        Generalization::Bucket(size) => {
+            let value = field.parse::<f64>().ok().filter(|v| v.is_finite())?;
+            // 區間上下限超出 i64 範圍的值無法概括化
+            let size = i64::try_from(size).ok()?;
+            let bucket = (value / size as f64).floor();
+            if !(i64::MIN as f64..i64::MAX as f64).contains(&bucket) {
+                return None;
+            }
+            let lower = (bucket as i64).checked_mul(size)?;
+            Some(format!("{}-{}", lower, lower.checked_add(size - 1)?))
+        }
+        Generalization::Month | Generalization::Year => {
+            // 只看日期部分，允許 `2024-03-05T10:00:00` 之類的時間戳記
+            let date = NaiveDate::parse_from_str(field.get(..10)?, "%Y-%m-%d").ok()?;
+            Some(match how {
+                Generalization::Month => format!("{:04}-{:02}", date.year(), date.month()),
+                _ => format!("{:04}", date.year()),
+            })
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! 依鍵欄位分組，輸出階層式 JSON
+
+use anyhow::{bail, Context};
+use csv::StringRecord;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::converter::CsvConverter;
+use crate::report::{ConversionReport, ReportBuilder};
+use crate::reshape::{Accumulator, Aggregate};
+use crate::value::ValueOptions;
+
+/// 分組節點的輸出形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NestShape {
+    /// 以鍵值為物件的鍵：`{ "Tokyo": [...] }`
+    #[default]
+    Object,
+    /// 每組為陣列中的一個物件：`[{ "city": "Tokyo", "rows": [...] }]`
+    Array,
+}
+
+impl FromStr for NestShape {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "object" => Ok(NestShape::Object),
+            "array" => Ok(NestShape::Array),
+            _ => bail!("未知的分組形式: {}（可用: object, array）", s),
+        }
+    }
+}
+
+/// 附加在每個分組節點上的彙總值
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupAggregate {
+    /// 組內列數，輸出為 `count`
+    Count,
+    /// 欄位加總，輸出為 `sum_<欄位>`
+    Sum(String),
+}
+
+impl GroupAggregate {
+    fn key(&self) -> String {
+        match self {
+            GroupAggregate::Count => "count".to_string(),
+            GroupAggregate::Sum(column) => format!("sum_{}", column),
+        }
+    }
+}
+
+impl FromStr for GroupAggregate {
+    type Err = anyhow::Error;
+
+    /// 解析 `count` 或 `sum:欄位`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "count" => Ok(GroupAggregate::Count),
+            Some(("sum", column)) if !column.is_empty() => Ok(GroupAggregate::Sum(column.to_string())),
+            _ => bail!("未知的分組彙總: {}（可用: count, sum:欄位）", s),
+        }
+    }
+}
+
+/// 階層式 JSON 匯出設定
+#[derive(Debug, Clone, Default)]
+pub struct NestedOptions {
+    /// 依序分組的鍵欄位，每個欄位為一層
+    pub group_by: Vec<String>,
+    pub shape: NestShape,
+    /// 每個分組節點的彙總值；有彙總時，子節點放在 `groups`（中間層）或 `rows`（最底層）
+    pub aggregates: Vec<GroupAggregate>,
+    /// 在最底層的記錄中保留鍵欄位
+    pub keep_keys: bool,
+    pub values: ValueOptions,
+}
+
+impl CsvConverter {
+    /// 將記錄依鍵欄位分組後寫成階層式 JSON，分組順序為首次出現的順序
+    pub fn write_nested_json_file(
+        headers: &[String],
+        records: &[StringRecord],
+        json_path: &str,
+        options: &NestedOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        if options.group_by.is_empty() {
+            bail!("至少需要一個分組欄位");
+        }
+        let report = ReportBuilder::new(headers, records, &options.values);
+        let nesting = Nesting::new(headers, options)?;
+
+        let records: Vec<(usize, &StringRecord)> = records.iter().enumerate().collect();
+        let tree = nesting.node(&records, 0)?;
+
+        std::fs::write(json_path, serde_json::to_string_pretty(&tree)?)?;
+        Ok(report.finish(records.len(), 0, json_path))
+    }
+}
+
+/// 分組時需要的欄位位置
+struct Nesting<'a> {
+    headers: &'a [String],
+    options: &'a NestedOptions,
+    key_idx: Vec<usize>,
+    sum_idx: HashMap<String, usize>,
+}
+
+impl<'a> Nesting<'a> {
+    fn new(headers: &'a [String], options: &'a NestedOptions) -> anyhow::Result<Self> {
+        let index_of = |name: &str| {
+            headers
+                .iter()
+                .position(|h| h == name)
+                .with_context(|| format!("找不到欄位: {}", name))
+        };
+        let key_idx = options
+            .group_by
+            .iter()
+            .map(|name| index_of(name))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let mut sum_idx = HashMap::new();
+        for aggregate in &options.aggregates {
+            if let GroupAggregate::Sum(column) = aggregate {
+                sum_idx.insert(column.clone(), index_of(column)?);
+            }
+        }
+        Ok(Self { headers, options, key_idx, sum_idx })
+    }
+
+    /// 產生第 `level` 層的節點；超過分組層數時為記錄陣列（記錄附帶原始列號）
+    fn node(&self, records: &[(usize, &StringRecord)], level: usize) -> anyhow::Result<Value> {
+        if level == self.key_idx.len() {
+            return records.iter().map(|&(row, record)| self.leaf(row, record)).collect::<anyhow::Result<_>>();
+        }
+
+        // 依首次出現的順序分組
+        let key_idx = self.key_idx[level];
+        let mut groups: Vec<(&str, Vec<(usize, &StringRecord)>)> = Vec::new();
+        let mut positions: HashMap<&str, usize> = HashMap::new();
+        for &(row, record) in records {
+            let key = record.get(key_idx).unwrap_or("");
+            let position = *positions.entry(key).or_insert_with(|| {
+                groups.push((key, Vec::new()));
+                groups.len() - 1
+            });
+            groups[position].1.push((row, record));
+        }
+
+        let children_key = if level + 1 == self.key_idx.len() { "rows" } else { "groups" };
+        match self.options.shape {
+            NestShape::Object => {
+                let mut node = Map::new();
+                for (key, members) in groups {
+                    let child = self.node(&members, level + 1)?;
+                    let child = if self.options.aggregates.is_empty() {
+                        child
+                    } else {
+                        let mut wrapper = self.aggregates(&members)?;
+                        wrapper.insert(children_key.to_string(), child);
+                        Value::Object(wrapper)
+                    };
+                    node.insert(key.to_string(), child);
+                }
+                Ok(Value::Object(node))
+            }
+            NestShape::Array => {
+                let column = &self.headers[key_idx];
+                let mut nodes = Vec::with_capacity(groups.len());
+                for (key, members) in groups {
+                    let mut node = Map::new();
+                    node.insert(column.clone(), self.options.values.parse(column, key)?);
+                    node.extend(self.aggregates(&members)?);
+                    node.insert(children_key.to_string(), self.node(&members, level + 1)?);
+                    nodes.push(Value::Object(node));
+                }
+                Ok(Value::Array(nodes))
+            }
+        }
+    }
+
+    fn aggregates(&self, members: &[(usize, &StringRecord)]) -> anyhow::Result<Map<String, Value>> {
+        let mut values = Map::new();
+        for aggregate in &self.options.aggregates {
+            let value = match aggregate {
+                GroupAggregate::Count => Value::from(members.len()),
+                GroupAggregate::Sum(column) => {
+                    let idx = self.sum_idx[column];
+                    let mut sum = Accumulator::new();
+                    for (_, record) in members {
+                        sum.add(record.get(idx).unwrap_or(""), Aggregate::Sum, &self.options.values, column)?;
+                    }
+                    sum.sum_value()
+                }
+            };
+            values.insert(aggregate.key(), value);
+        }
+        Ok(values)
+    }
+
+    /// 最底層的記錄，預設移除已作為分組鍵的欄位
+    fn leaf(&self, row: usize, record: &StringRecord) -> anyhow::Result<Value> {
+        let mut object = Map::new();
+        for (i, (header, field)) in self.headers.iter().zip(record.iter()).enumerate() {
+            if self.options.keep_keys || !self.key_idx.contains(&i) {
+                object.insert(header.clone(), self.options.values.parse_cell(row, i, header, field)?);
+            }
+        }
+        Ok(Value::Object(object))
+    }
+}
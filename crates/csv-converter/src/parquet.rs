@@ -0,0 +1,131 @@
+//! Parquet 輸出（需啟用 `parquet` feature，使用 Polars 寫檔）
+
+use anyhow::Context;
+use csv::StringRecord;
+use polars::prelude::{Column, DataFrame, ParquetWriter};
+use serde_json::Value;
+use std::fs::File;
+use std::io::Write;
+
+use crate::converter::CsvConverter;
+use crate::report::{ConversionReport, ReportBuilder};
+use crate::schema::{ColumnType, Schema};
+use crate::value::ValueOptions;
+
+impl CsvConverter {
+    /// 將記錄寫成 Parquet 檔案，欄位型別由 [`Schema::infer_with`](crate::Schema::infer_with) 推斷
+    pub fn write_parquet_file(
+        headers: &[String],
+        records: &[StringRecord],
+        parquet_path: &str,
+        options: &ValueOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let report = ReportBuilder::new(headers, records, options);
+        let types: Vec<ColumnType> = report.schema().columns.iter().map(|c| c.column_type).collect();
+        Self::write_parquet_file_with_types(headers, records, &types, parquet_path, options)?;
+        Ok(report.finish(records.len(), 0, parquet_path))
+    }
+
+    /// 以指定的欄位型別寫成 Parquet 檔案；值不符合型別時回傳錯誤
+    pub(crate) fn write_parquet_file_with_types(
+        headers: &[String],
+        records: &[StringRecord],
+        types: &[ColumnType],
+        parquet_path: &str,
+        options: &ValueOptions,
+    ) -> anyhow::Result<()> {
+        let file = File::create(parquet_path).with_context(|| format!("無法建立 Parquet 檔案: {}", parquet_path))?;
+        Self::write_parquet_with_types(headers, records, types, file, options)
+    }
+
+    /// 將記錄以 Parquet 格式寫入 `writer`，欄位型別由 [`Schema::infer_with`](crate::Schema::infer_with) 推斷
+    pub fn write_parquet<W: Write>(
+        headers: &[String],
+        records: &[StringRecord],
+        writer: W,
+        options: &ValueOptions,
+    ) -> anyhow::Result<()> {
+        let schema = Schema::infer_with(headers, records, options);
+        let types: Vec<ColumnType> = schema.columns.iter().map(|c| c.column_type).collect();
+        Self::write_parquet_with_types(headers, records, &types, writer, options)
+    }
+
+    fn write_parquet_with_types<W: Write>(
+        headers: &[String],
+        records: &[StringRecord],
+        types: &[ColumnType],
+        writer: W,
+        options: &ValueOptions,
+    ) -> anyhow::Result<()> {
+        let mut frame = Self::data_frame(headers, records, types, options)?;
+        ParquetWriter::new(writer).finish(&mut frame)?;
+        Ok(())
+    }
+
+    /// 以指定的欄位型別將記錄轉為 Polars `DataFrame`
+    pub(crate) fn data_frame(
+        headers: &[String],
+        records: &[StringRecord],
+        types: &[ColumnType],
+        options: &ValueOptions,
+    ) -> anyhow::Result<DataFrame> {
+        let columns = headers
+            .iter()
+            .zip(types)
+            .enumerate()
+            .map(|(i, (header, column_type))| {
+                let fields: Vec<&str> = records.iter().map(|record| record.get(i).unwrap_or("")).collect();
+                parquet_column(header, *column_type, &fields, options)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(DataFrame::new(columns)?)
+    }
+}
+
+/// 將同一欄的值轉為 Polars 欄位；字串欄位保留原始文字
+fn parquet_column(name: &str, column_type: ColumnType, fields: &[&str], options: &ValueOptions) -> anyhow::Result<Column> {
+    if column_type == ColumnType::String {
+        let cells: Vec<Option<&str>> = fields.iter().map(|f| (!f.is_empty()).then_some(*f)).collect();
+        return Ok(Column::new(name.into(), cells));
+    }
+
+    // 空欄位一律為 null
+    let values = fields
+        .iter()
+        .map(|field| if field.is_empty() { Ok(Value::Null) } else { options.parse(name, field) })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let mismatch = |value: &Value| anyhow::anyhow!("欄位 {} 的值 {} 不符合型別 {}", name, value, column_type);
+    Ok(match column_type {
+        ColumnType::Integer => {
+            let cells = values
+                .iter()
+                .map(|v| match v {
+                    Value::Null => Ok(None),
+                    v => v.as_i64().map(Some).ok_or_else(|| mismatch(v)),
+                })
+                .collect::<anyhow::Result<Vec<Option<i64>>>>()?;
+            Column::new(name.into(), cells)
+        }
+        ColumnType::Float => {
+            let cells = values
+                .iter()
+                .map(|v| match v {
+                    Value::Null => Ok(None),
+                    v => v.as_f64().map(Some).ok_or_else(|| mismatch(v)),
+                })
+                .collect::<anyhow::Result<Vec<Option<f64>>>>()?;
+            Column::new(name.into(), cells)
+        }
+        ColumnType::Boolean => {
+            let cells = values
+                .iter()
+                .map(|v| match v {
+                    Value::Null => Ok(None),
+                    v => v.as_bool().map(Some).ok_or_else(|| mismatch(v)),
+                })
+                .collect::<anyhow::Result<Vec<Option<bool>>>>()?;
+            Column::new(name.into(), cells)
+        }
+        ColumnType::String => unreachable!("字串欄位已在上方處理"),
+    })
+}
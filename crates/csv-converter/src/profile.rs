@@ -0,0 +1,105 @@
+//! 欄位概況：型別、空值數、相異值數、數值範圍與最常見的值
+
+use csv::StringRecord;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::converter::CsvConverter;
+use crate::schema::{ColumnType, Schema};
+use crate::value::ValueOptions;
+
+/// 單一欄位的統計概況
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnProfile {
+    pub name: String,
+    pub column_type: ColumnType,
+    /// 有值的列數
+    pub count: usize,
+    /// 空字串的列數
+    pub empty: usize,
+    /// 相異值的數量（不含空字串）
+    pub distinct: usize,
+    /// 數值欄位的最小值、最大值與平均值
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub mean: Option<f64>,
+    /// 值的最短與最長字元數
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    /// 出現次數最多的值，次數相同時取排序在前者
+    pub most_common: Option<String>,
+    pub most_common_count: usize,
+}
+
+impl CsvConverter {
+    /// 計算每個欄位的統計概況，型別依 [`Schema::infer_with`] 推斷
+    pub fn profile_records(
+        headers: &[String],
+        records: &[StringRecord],
+        options: &ValueOptions,
+    ) -> Vec<ColumnProfile> {
+        let schema = Schema::infer_with(headers, records, options);
+
+        schema
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let numeric = matches!(column.column_type, ColumnType::Integer | ColumnType::Float);
+                let mut profile = ColumnProfile {
+                    name: column.name.clone(),
+                    column_type: column.column_type,
+                    count: 0,
+                    empty: 0,
+                    distinct: 0,
+                    min: None,
+                    max: None,
+                    mean: None,
+                    min_length: None,
+                    max_length: None,
+                    most_common: None,
+                    most_common_count: 0,
+                };
+                let mut counts: HashMap<&str, usize> = HashMap::new();
+                let mut sum = 0.0;
+                let mut numbers = 0;
+
+                for record in records {
+                    let field = record.get(i).unwrap_or("");
+                    if field.is_empty() {
+                        profile.empty += 1;
+                        continue;
+                    }
+                    profile.count += 1;
+                    *counts.entry(field).or_default() += 1;
+
+                    let length = field.chars().count();
+                    profile.min_length = Some(profile.min_length.map_or(length, |l| l.min(length)));
+                    profile.max_length = Some(profile.max_length.map_or(length, |l| l.max(length)));
+
+                    if numeric {
+                        if let Some(n) = options.parse(&column.name, field).ok().and_then(|v| v.as_f64()) {
+                            profile.min = Some(profile.min.map_or(n, |m| m.min(n)));
+                            profile.max = Some(profile.max.map_or(n, |m| m.max(n)));
+                            sum += n;
+                            numbers += 1;
+                        }
+                    }
+                }
+
+                if numbers > 0 {
+                    profile.mean = Some(sum / numbers as f64);
+                }
+                profile.distinct = counts.len();
+                if let Some((value, count)) = counts
+                    .into_iter()
+                    .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then_with(|| b.cmp(a)))
+                {
+                    profile.most_common = Some(value.to_string());
+                    profile.most_common_count = count;
+                }
+                profile
+            })
+            .collect()
+    }
+}
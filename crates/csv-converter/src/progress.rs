@@ -0,0 +1,216 @@
+//! 長時間轉換的進度回報與取消
+
+use anyhow::Context;
+use csv::{Reader, StringRecord};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::converter::CsvConverter;
+use crate::report::{ConversionReport, ReportBuilder};
+use crate::value::ValueOptions;
+
+/// 目前的處理進度
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+    pub rows: usize,
+    pub bytes_read: u64,
+    /// 輸入檔大小，無法取得時為 None
+    pub total_bytes: Option<u64>,
+}
+
+impl Progress {
+    /// 依已讀取的位元組數估計完成百分比
+    pub fn percent(&self) -> Option<f64> {
+        match self.total_bytes {
+            Some(0) => Some(100.0),
+            Some(total) => Some((self.bytes_read as f64 / total as f64 * 100.0).min(100.0)),
+            None => None,
+        }
+    }
+}
+
+/// 接收進度通知；閉包 `Fn(&Progress)` 可直接使用
+pub trait ProgressObserver: Send + Sync {
+    fn on_progress(&self, progress: &Progress);
+}
+
+impl<F: Fn(&Progress) + Send + Sync> ProgressObserver for F {
+    fn on_progress(&self, progress: &Progress) {
+        self(progress)
+    }
+}
+
+/// 可在其他執行緒呼叫 [`cancel`](Self::cancel) 的取消旗標，複製後共用同一個狀態
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// 轉換被取消時回傳的錯誤，可用 `error.is::<Cancelled>()` 判斷
+#[derive(Debug, Clone, Copy)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "轉換已取消")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// 進度回報與取消設定，在每筆記錄之間檢查
+#[derive(Clone)]
+pub struct ProgressMonitor {
+    pub observer: Option<Arc<dyn ProgressObserver>>,
+    pub cancel: CancellationToken,
+    /// 每處理幾列通知一次；開始與結束時一定會通知
+    pub every_rows: usize,
+}
+
+impl Default for ProgressMonitor {
+    fn default() -> Self {
+        Self {
+            observer: None,
+            cancel: CancellationToken::default(),
+            every_rows: 10_000,
+        }
+    }
+}
+
+impl ProgressMonitor {
+    /// 取消時回傳 [`Cancelled`]，否則依間隔通知進度
+    fn tick(&self, progress: &Progress, force: bool) -> anyhow::Result<()> {
+        if self.cancel.is_cancelled() {
+            return Err(Cancelled.into());
+        }
+        if let Some(observer) = &self.observer {
+            if force || progress.rows.is_multiple_of(self.every_rows.max(1)) {
+                observer.on_progress(progress);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CsvConverter {
+    /// 讀取整個 CSV 檔案並回報進度；取消時回傳 [`Cancelled`]
+    pub fn read_csv_file_with_progress(
+        csv_path: &str,
+        monitor: &ProgressMonitor,
+    ) -> anyhow::Result<(Vec<String>, Vec<StringRecord>)> {
+        let mut records = Vec::new();
+        let headers = Self::for_each_record(csv_path, monitor, |_, record| {
+            records.push(record.clone());
+            Ok(())
+        })?;
+        Ok((headers, records))
+    }
+
+    /// 以串流方式將 CSV 轉換為 JSON 並回報進度
+    ///
+    /// 輸出經由 [`CsvConverter::write_atomically`] 寫出，取消或失敗時既有的輸出檔不會被改動。
+    pub fn convert_csv_to_json_file_with_progress(
+        csv_path: &str,
+        json_path: &str,
+        options: &ValueOptions,
+        monitor: &ProgressMonitor,
+    ) -> anyhow::Result<ConversionReport> {
+        let started = Instant::now();
+        let rows = Self::write_atomically(json_path, &monitor.cancel, |partial_path| {
+            Self::write_json_stream(csv_path, partial_path, options, monitor)
+        })?;
+        let mut report = ReportBuilder::streaming();
+        report.rows_read(rows);
+        report.finish(rows, 0, json_path).with_input(csv_path, started)
+    }
+
+    /// 由 `write` 寫到同目錄的 `.partial` 暫存檔（參數為暫存檔路徑），成功且未取消時才改名為輸出檔
+    ///
+    /// 失敗或取消時刪除暫存檔並回傳錯誤（取消為 [`Cancelled`]），既有的輸出檔不會被改動。
+    /// 不會在寫入途中檢查取消的寫入函式也適用，只是要等寫完才會停止。
+    pub fn write_atomically<T>(
+        output_path: &str,
+        cancel: &CancellationToken,
+        write: impl FnOnce(&str) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let partial_path = format!("{}.partial", output_path);
+        let result = write(&partial_path).and_then(|value| {
+            if cancel.is_cancelled() {
+                return Err(Cancelled.into());
+            }
+            std::fs::rename(&partial_path, output_path)
+                .with_context(|| format!("無法建立輸出檔: {}", output_path))?;
+            Ok(value)
+        });
+        if result.is_err() {
+            let _ = std::fs::remove_file(&partial_path);
+        }
+        result
+    }
+
+    /// 逐筆寫出 JSON 陣列，回傳寫出的列數
+    fn write_json_stream(
+        csv_path: &str,
+        json_path: &str,
+        options: &ValueOptions,
+        monitor: &ProgressMonitor,
+    ) -> anyhow::Result<usize> {
+        let mut writer = BufWriter::new(File::create(json_path)?);
+        let mut rows = 0;
+
+        writer.write_all(b"[")?;
+        Self::for_each_record(csv_path, monitor, |headers, record| {
+            let row = Self::typed_records(headers, std::slice::from_ref(record), options)?;
+            writer.write_all(if rows == 0 { b"\n  " } else { b",\n  " })?;
+            serde_json::to_writer(&mut writer, &row[0])?;
+            rows += 1;
+            Ok(())
+        })?;
+        writer.write_all(if rows == 0 { b"]\n" } else { b"\n]\n" })?;
+        writer.flush()?;
+        Ok(rows)
+    }
+
+    /// 逐筆讀取 CSV，每筆記錄之間檢查取消並回報進度，回傳標題列
+    fn for_each_record(
+        csv_path: &str,
+        monitor: &ProgressMonitor,
+        mut on_record: impl FnMut(&[String], &StringRecord) -> anyhow::Result<()>,
+    ) -> anyhow::Result<Vec<String>> {
+        let file = File::open(csv_path)?;
+        let total_bytes = file.metadata().ok().map(|m| m.len());
+        let mut reader = Reader::from_reader(file);
+        let headers: Vec<String> = reader.headers()?.iter().map(|h| h.to_string()).collect();
+
+        let mut progress = Progress { rows: 0, bytes_read: 0, total_bytes };
+        monitor.tick(&progress, true)?;
+
+        let mut record = StringRecord::new();
+        while reader.read_record(&mut record)? {
+            progress.rows += 1;
+            progress.bytes_read = reader.position().byte();
+            on_record(&headers, &record)?;
+            monitor.tick(&progress, false)?;
+        }
+
+        progress.bytes_read = reader.position().byte();
+        monitor.tick(&progress, true)?;
+        Ok(headers)
+    }
+}
@@ -0,0 +1,236 @@
+//! 以 SQL 查詢 CSV、Parquet 與試算表檔案（需啟用 `query` feature，使用 Polars SQL）
+//!
+//! `FROM` 或 `JOIN` 後面以單引號包住的路徑（例如 `FROM 'demo.csv'`）會自動註冊為資料表，
+//! 也可以用 [`QueryOptions::tables`] 指定資料表名稱。
+
+use anyhow::{bail, Context};
+use csv::StringRecord;
+use polars::prelude::{AnyValue, DataFrame, IntoLazy, LazyCsvReader, LazyFileListReader, LazyFrame, PlPath, ScanArgsParquet};
+use polars::sql::SQLContext;
+use std::path::Path;
+
+use crate::converter::CsvConverter;
+use crate::dialect::Dialect;
+use crate::schema::Schema;
+use crate::spreadsheet::{is_spreadsheet_path, SpreadsheetOptions};
+use crate::value::{NullCells, ValueOptions};
+
+/// SQL 查詢設定
+#[derive(Debug, Clone, Default)]
+pub struct QueryOptions {
+    /// 額外註冊的資料表 (名稱, 檔案路徑)
+    pub tables: Vec<(String, String)>,
+    /// CSV 資料表的方言；未指定時依各檔案的副檔名決定
+    pub dialect: Option<Dialect>,
+    /// 試算表與 Avro 資料表的欄位值轉換設定
+    pub values: ValueOptions,
+}
+
+/// 查詢結果；記錄為文字，型別另外記錄，寫出前以 [`QueryData::value_options`] 套用
+#[derive(Debug, Clone, Default)]
+pub struct QueryData {
+    pub headers: Vec<String>,
+    pub records: Vec<StringRecord>,
+    /// 結果型別不是數字或布林值的欄位，例如 `'00501'` 需保持為字串
+    pub text_columns: Vec<String>,
+    /// 結果中的 null，記錄中為空字串
+    pub nulls: NullCells,
+}
+
+impl QueryData {
+    /// 在 `values` 上加入結果的欄位型別與 null，交給任何輸出格式時保留查詢結果的型別
+    pub fn value_options(&self, values: &ValueOptions) -> ValueOptions {
+        let mut values = values.clone();
+        values.text_columns.extend(self.text_columns.iter().cloned());
+        values.null_cells = self.nulls.clone();
+        values
+    }
+}
+
+impl CsvConverter {
+    /// 執行 SQL 查詢，結果以標題列與記錄回傳，可交給任何輸出格式寫出
+    ///
+    /// CSV 與 Parquet 由 Polars 直接掃描，試算表與 Avro 先讀入再依推斷的 schema 建立資料表。
+    pub fn query_files(sql: &str, options: &QueryOptions) -> anyhow::Result<QueryData> {
+        let (sql, paths) = rewrite_file_tables(sql);
+
+        let mut context = SQLContext::new();
+        for path in &paths {
+            context.register(path, Self::scan_table(path, options)?);
+        }
+        for (name, path) in &options.tables {
+            context.register(name, Self::scan_table(path, options)?);
+        }
+
+        let frame = context
+            .execute(&sql)
+            .and_then(|lazy| lazy.collect())
+            .context("SQL 查詢失敗")?;
+        frame_records(&frame)
+    }
+
+    /// 依副檔名建立資料表
+    fn scan_table(path: &str, options: &QueryOptions) -> anyhow::Result<LazyFrame> {
+        if !Path::new(path).is_file() {
+            bail!("找不到資料表檔案: {}", path);
+        }
+        let lowercase = path.to_ascii_lowercase();
+        if lowercase.ends_with(".parquet") {
+            return LazyFrame::scan_parquet(PlPath::new(path), ScanArgsParquet::default())
+                .with_context(|| format!("無法讀取 {}", path));
+        }
+        if is_spreadsheet_path(path) || lowercase.ends_with(".avro") {
+            let (headers, records) = Self::read_input_file(path, &SpreadsheetOptions::default())?;
+            let schema = Schema::infer_with(&headers, &records, &options.values);
+            let types: Vec<_> = schema.columns.iter().map(|c| c.column_type).collect();
+            return Ok(Self::data_frame(&headers, &records, &types, &options.values)?.lazy());
+        }
+
+        let dialect = options.dialect.unwrap_or_else(|| Dialect::for_path(path));
+        LazyCsvReader::new(PlPath::new(path))
+            .with_separator(dialect.delimiter)
+            .with_quote_char(Some(dialect.quote))
+            .with_comment_prefix(dialect.comment.map(|c| (c as char).to_string().into()))
+            // 掃描整個檔案推斷型別，避免後段出現不同型別的值時查詢失敗
+            .with_infer_schema_length(None)
+            .with_missing_is_null(true)
+            .finish()
+            .with_context(|| format!("無法讀取 {}", path))
+    }
+}
+
+/// 將 `FROM`／`JOIN` 後面以單引號包住的路徑改為雙引號識別字，回傳改寫後的 SQL 與這些路徑
+///
+/// 雙引號識別字與註解原樣保留，其中的單引號不視為字串
+fn rewrite_file_tables(sql: &str) -> (String, Vec<String>) {
+    let mut output = String::with_capacity(sql.len());
+    let mut paths: Vec<String> = Vec::new();
+    // 最近一個關鍵字或識別字，用來判斷字串是否位於資料表的位置
+    let mut last_word = String::new();
+    let mut chars = sql.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                let mut literal = String::new();
+                while let Some(c) = chars.next() {
+                    if c == '\'' {
+                        // 兩個單引號表示字串中的單引號
+                        if chars.peek() == Some(&'\'') {
+                            chars.next();
+                            literal.push('\'');
+                            continue;
+                        }
+                        break;
+                    }
+                    literal.push(c);
+                }
+                if last_word.eq_ignore_ascii_case("from") || last_word.eq_ignore_ascii_case("join") {
+                    output.push('"');
+                    output.push_str(&literal.replace('"', "\"\""));
+                    output.push('"');
+                    if !paths.contains(&literal) {
+                        paths.push(literal);
+                    }
+                    // `FROM 'a.csv', 'b.csv'` 的下一個字串同樣是資料表
+                } else {
+                    output.push('\'');
+                    output.push_str(&literal.replace('\'', "''"));
+                    output.push('\'');
+                    last_word.clear();
+                }
+            }
+            '"' => {
+                let mut identifier = String::new();
+                output.push('"');
+                while let Some(c) = chars.next() {
+                    output.push(c);
+                    if c == '"' {
+                        // 兩個雙引號表示識別字中的雙引號
+                        if chars.peek() == Some(&'"') {
+                            output.extend(chars.next());
+                            identifier.push('"');
+                            continue;
+                        }
+                        break;
+                    }
+                    identifier.push(c);
+                }
+                last_word = identifier;
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                output.push(c);
+                for c in chars.by_ref() {
+                    output.push(c);
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                output.push(c);
+                output.extend(chars.next());
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    output.push(c);
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                output.push_str(&word);
+                last_word = word;
+            }
+            c if c.is_whitespace() || c == ',' => output.push(c),
+            c => {
+                output.push(c);
+                last_word.clear();
+            }
+        }
+    }
+
+    (output, paths)
+}
+
+/// 將查詢結果轉為標題列與文字記錄；null 為空字串並記錄在 [`QueryData::nulls`]
+fn frame_records(frame: &DataFrame) -> anyhow::Result<QueryData> {
+    let headers: Vec<String> = frame.get_column_names().iter().map(|name| name.to_string()).collect();
+    let columns = frame.get_columns();
+    let text_columns = columns
+        .iter()
+        .filter(|column| !(column.dtype().is_primitive_numeric() || column.dtype().is_bool()))
+        .map(|column| column.name().to_string())
+        .collect();
+
+    let mut records = Vec::with_capacity(frame.height());
+    let mut nulls = NullCells::default();
+    for row in 0..frame.height() {
+        let mut record = StringRecord::with_capacity(0, columns.len());
+        let mut null_columns = Vec::new();
+        for (i, column) in columns.iter().enumerate() {
+            let text = match column.get(row)? {
+                AnyValue::Null => {
+                    null_columns.push(i);
+                    String::new()
+                }
+                AnyValue::String(s) => s.to_string(),
+                AnyValue::StringOwned(s) => s.to_string(),
+                value => value.to_string(),
+            };
+            record.push_field(&text);
+        }
+        nulls.push(row..row + 1, null_columns);
+        records.push(record);
+    }
+    Ok(QueryData { headers, records, text_columns, nulls })
+}
@@ -0,0 +1,52 @@
+use csv::{StringRecord, Writer, WriterBuilder};
+use std::fs::File;
+
+/// 被拒絕記錄輸出檔中，記錄原因的欄位名稱
+pub const REJECT_REASON_COLUMN: &str = "_reject_reason";
+
+/// 將被拒絕的記錄連同原因寫到 CSV 檔案；未指定路徑時只計數
+pub struct RejectWriter {
+    writer: Option<Writer<File>>,
+    count: usize,
+}
+
+impl RejectWriter {
+    /// 建立輸出檔並寫入原始標題列加上原因欄位；被拒絕的記錄欄位數可能與標題不同
+    pub fn create(path: Option<&str>, headers: &[String]) -> anyhow::Result<Self> {
+        let writer = match path {
+            Some(path) => {
+                let mut writer = WriterBuilder::new().flexible(true).from_path(path)?;
+                let mut header_row: Vec<&str> = headers.iter().map(String::as_str).collect();
+                header_row.push(REJECT_REASON_COLUMN);
+                writer.write_record(&header_row)?;
+                Some(writer)
+            }
+            None => None,
+        };
+        Ok(Self { writer, count: 0 })
+    }
+
+    /// 寫入一筆被拒絕的記錄
+    pub fn reject(&mut self, record: &StringRecord, reason: &str) -> anyhow::Result<()> {
+        self.count += 1;
+        if let Some(writer) = &mut self.writer {
+            let mut row: Vec<&str> = record.iter().collect();
+            row.push(reason);
+            writer.write_record(&row)?;
+        }
+        Ok(())
+    }
+
+    /// 目前被拒絕的記錄數
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// 寫出緩衝區內容
+    pub fn finish(mut self) -> anyhow::Result<usize> {
+        if let Some(writer) = &mut self.writer {
+            writer.flush()?;
+        }
+        Ok(self.count)
+    }
+}
@@ -0,0 +1,123 @@
+//! 轉換報告：每次轉換的列數、位元組數、耗時、最終 schema 與警告
+
+use csv::StringRecord;
+use serde::Serialize;
+use std::path::Path;
+use std::time::Instant;
+
+use crate::schema::Schema;
+use crate::value::ValueOptions;
+
+/// 一次轉換的結果摘要，可序列化為 JSON 供監控儀表板使用
+#[derive(Debug, Clone, Serialize)]
+pub struct ConversionReport {
+    pub rows_read: usize,
+    pub rows_written: usize,
+    /// 因資料無效而未輸出的列數
+    pub rows_rejected: usize,
+    /// 輸入檔大小；直接轉換已讀取的記錄時為 0
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+    pub elapsed_ms: u64,
+    /// 輸出使用的欄位 schema；串流處理的轉換不推斷 schema，此時為空
+    pub schema: Schema,
+    pub warnings: Vec<String>,
+}
+
+impl ConversionReport {
+    /// 序列化為排版後的 JSON
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// 補上輸入檔大小，並將耗時改為從讀取輸入開始計算
+    pub(crate) fn with_input(mut self, input_path: &str, started: Instant) -> anyhow::Result<Self> {
+        self.bytes_in = std::fs::metadata(input_path)?.len();
+        self.elapsed_ms = started.elapsed().as_millis() as u64;
+        Ok(self)
+    }
+}
+
+/// 在轉換過程中累積報告內容
+pub(crate) struct ReportBuilder {
+    started: Instant,
+    rows_read: usize,
+    schema: Schema,
+    warnings: Vec<String>,
+}
+
+impl ReportBuilder {
+    /// 開始計時並推斷 schema
+    pub(crate) fn new(headers: &[String], records: &[StringRecord], options: &ValueOptions) -> Self {
+        let mut builder = Self::streaming();
+        builder.rows_read = records.len();
+        builder.schema = Schema::infer_with(headers, records, options);
+
+        // 以欄位名稱為鍵的格式（JSON、YAML 等）只會保留重複欄位的最後一個值
+        for (i, header) in headers.iter().enumerate() {
+            // 只在第二次出現時警告一次
+            if headers[..i].iter().filter(|h| *h == header).count() == 1 {
+                builder.warn(format!("欄位名稱 {} 重複，以欄位名稱為鍵的格式只會保留最後一個值", header));
+            }
+        }
+        builder
+    }
+
+    /// 只計時，不推斷 schema；用於串流處理的轉換
+    pub(crate) fn streaming() -> Self {
+        Self {
+            started: Instant::now(),
+            rows_read: 0,
+            schema: Schema { columns: Vec::new() },
+            warnings: Vec::new(),
+        }
+    }
+
+    pub(crate) fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    pub(crate) fn rows_read(&mut self, rows: usize) {
+        self.rows_read = rows;
+    }
+
+    pub(crate) fn warn(&mut self, message: impl Into<String>) {
+        self.warnings.push(message.into());
+    }
+
+    /// 完成報告，輸出大小取自輸出檔（目錄則加總其中的檔案）
+    pub(crate) fn finish(self, rows_written: usize, rows_rejected: usize, output_path: &str) -> ConversionReport {
+        let bytes_out = output_size(Path::new(output_path));
+        self.finish_with_bytes(rows_written, rows_rejected, 0, bytes_out)
+    }
+
+    /// 完成報告，輸入與輸出大小由呼叫端計算；用於沒有檔案路徑的串流轉換
+    pub(crate) fn finish_with_bytes(
+        self,
+        rows_written: usize,
+        rows_rejected: usize,
+        bytes_in: u64,
+        bytes_out: u64,
+    ) -> ConversionReport {
+        ConversionReport {
+            rows_read: self.rows_read,
+            rows_written,
+            rows_rejected,
+            bytes_in,
+            bytes_out,
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            schema: self.schema,
+            warnings: self.warnings,
+        }
+    }
+}
+
+pub(crate) fn output_size(path: &Path) -> u64 {
+    match std::fs::metadata(path) {
+        Ok(metadata) if metadata.is_dir() => std::fs::read_dir(path)
+            .map(|entries| entries.flatten().map(|entry| output_size(&entry.path())).sum())
+            .unwrap_or(0),
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    }
+}
@@ -0,0 +1,348 @@
+//! 長表與寬表之間的轉換（pivot / unpivot）
+
+use anyhow::{bail, Context};
+use csv::{StringRecord, Writer};
+use serde_json::{Number, Value};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::converter::CsvConverter;
+use crate::dialect::Dialect;
+use crate::report::{ConversionReport, ReportBuilder};
+use crate::value::ValueOptions;
+
+/// 同一格有多筆值時的彙總方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Aggregate {
+    #[default]
+    Sum,
+    Mean,
+    First,
+    Count,
+}
+
+impl FromStr for Aggregate {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sum" => Ok(Aggregate::Sum),
+            "mean" => Ok(Aggregate::Mean),
+            "first" => Ok(Aggregate::First),
+            "count" => Ok(Aggregate::Count),
+            _ => bail!("未知的彙總方式: {}（可用: sum, mean, first, count）", s),
+        }
+    }
+}
+
+/// pivot 設定：將 (index..., columns, values) 長表轉為寬表
+#[derive(Debug, Clone, Default)]
+pub struct PivotOptions {
+    /// 作為列索引的欄位
+    pub index: Vec<String>,
+    /// 其值會成為新欄位名稱的欄位
+    pub columns: String,
+    /// 填入儲存格的值欄位
+    pub values: String,
+    pub aggregate: Aggregate,
+    /// 輸入已依索引欄位排序時設為 true，逐組輸出而不必將所有分組保留在記憶體；
+    /// 排序方式同輸出列（數字依數值大小並排在文字之前，其餘依位元組順序），
+    /// 與一般模式相同會讀取輸入兩次（第一次收集新欄位名稱）
+    pub sorted: bool,
+    pub value_options: ValueOptions,
+    /// 輸入檔的方言；未指定時依副檔名決定
+    pub dialect: Option<Dialect>,
+}
+
+/// unpivot 設定：將寬表的多個欄位轉為 (variable, value) 長表
+#[derive(Debug, Clone)]
+pub struct UnpivotOptions {
+    /// 保留不動的識別欄位
+    pub id_columns: Vec<String>,
+    /// 要轉為長表的欄位；空白時為所有非識別欄位
+    pub value_columns: Vec<String>,
+    pub variable_name: String,
+    pub value_name: String,
+    /// 略過空值
+    pub skip_empty: bool,
+    /// 輸入檔的方言；未指定時依副檔名決定
+    pub dialect: Option<Dialect>,
+}
+
+impl Default for UnpivotOptions {
+    fn default() -> Self {
+        Self {
+            id_columns: Vec::new(),
+            value_columns: Vec::new(),
+            variable_name: "variable".to_string(),
+            value_name: "value".to_string(),
+            skip_empty: false,
+            dialect: None,
+        }
+    }
+}
+
+/// 單一儲存格的彙總狀態
+#[derive(Debug, Default)]
+pub(crate) struct Accumulator {
+    count: usize,
+    float_sum: f64,
+    int_sum: i128,
+    all_integers: bool,
+    first: Option<String>,
+}
+
+impl Accumulator {
+    pub(crate) fn new() -> Self {
+        Self { all_integers: true, ..Default::default() }
+    }
+
+    pub(crate) fn add(&mut self, field: &str, aggregate: Aggregate, options: &ValueOptions, column: &str) -> anyhow::Result<()> {
+        if field.is_empty() {
+            return Ok(());
+        }
+        self.count += 1;
+        if self.first.is_none() {
+            self.first = Some(field.to_string());
+        }
+
+        if matches!(aggregate, Aggregate::Sum | Aggregate::Mean) {
+            let value = options.parse(column, field)?;
+            match (value.as_i64(), value.as_f64()) {
+                (Some(i), _) => self.int_sum += i as i128,
+                (None, Some(f)) => {
+                    self.all_integers = false;
+                    self.float_sum += f;
+                }
+                _ => bail!("欄位 {} 的值 {:?} 不是數字，無法彙總", column, field),
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn result(&self, aggregate: Aggregate) -> String {
+        if self.count == 0 {
+            return if aggregate == Aggregate::Count { "0".to_string() } else { String::new() };
+        }
+        match aggregate {
+            Aggregate::Sum if self.all_integers => self.int_sum.to_string(),
+            Aggregate::Sum => (self.int_sum as f64 + self.float_sum).to_string(),
+            Aggregate::Mean => ((self.int_sum as f64 + self.float_sum) / self.count as f64).to_string(),
+            Aggregate::First => self.first.clone().unwrap_or_default(),
+            Aggregate::Count => self.count.to_string(),
+        }
+    }
+
+    /// 加總結果的 JSON 數值，不經過字串再解析（避免地區格式把小數點當成千分位）；沒有值時為 null
+    pub(crate) fn sum_value(&self) -> Value {
+        if self.count == 0 {
+            return Value::Null;
+        }
+        if self.all_integers {
+            if let Ok(sum) = i64::try_from(self.int_sum) {
+                return Value::from(sum);
+            }
+        }
+        Number::from_f64(self.int_sum as f64 + self.float_sum).map_or(Value::Null, Value::Number)
+    }
+}
+
+impl CsvConverter {
+    /// 將長表 CSV 轉為寬表 CSV；新欄位與輸出列都依值排序
+    /// （數字依數值大小並排在文字之前，其餘依位元組順序）
+    pub fn pivot_csv_file(
+        input_path: &str,
+        output_path: &str,
+        options: &PivotOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let started = Instant::now();
+        let mut report = ReportBuilder::streaming();
+        let dialect = options.dialect.unwrap_or_else(|| Dialect::for_path(input_path));
+        let mut reader = dialect.reader_builder().from_path(input_path)?;
+        let headers = reader.headers()?.clone();
+        let index_idx = options
+            .index
+            .iter()
+            .map(|name| column_index(&headers, name))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let columns_idx = column_index(&headers, &options.columns)?;
+        let values_idx = column_index(&headers, &options.values)?;
+
+        // 第一遍只收集新欄位名稱，讓輸出欄位順序固定
+        let mut pivot_columns = HashSet::new();
+        let mut rows_read = 0;
+        for record in reader.records() {
+            pivot_columns.insert(record?.get(columns_idx).unwrap_or("").to_string());
+            rows_read += 1;
+        }
+        report.rows_read(rows_read);
+        let mut pivot_columns: Vec<String> = pivot_columns.into_iter().collect();
+        pivot_columns.sort_by(|a, b| compare_key_values(a, b));
+
+        let mut writer = Writer::from_path(output_path)?;
+        let mut header_row: Vec<&str> = options.index.iter().map(String::as_str).collect();
+        header_row.extend(pivot_columns.iter().map(String::as_str));
+        writer.write_record(&header_row)?;
+
+        let mut reader = dialect.reader_builder().from_path(input_path)?;
+        let key_of = |record: &StringRecord| -> Vec<String> {
+            index_idx.iter().map(|&i| record.get(i).unwrap_or("").to_string()).collect()
+        };
+        let add = |cells: &mut Vec<Accumulator>, record: &StringRecord| -> anyhow::Result<()> {
+            let column = record.get(columns_idx).unwrap_or("");
+            let Ok(slot) = pivot_columns.binary_search_by(|c| compare_key_values(c, column)) else {
+                bail!("輸入檔在讀取期間被修改: 出現新的欄位值 {:?}", column);
+            };
+            cells[slot].add(record.get(values_idx).unwrap_or(""), options.aggregate, &options.value_options, &options.values)
+        };
+        let new_cells = || (0..pivot_columns.len()).map(|_| Accumulator::new()).collect::<Vec<_>>();
+        let mut rows_written = 0;
+
+        if options.sorted {
+            // 串流處理：索引改變時輸出上一組
+            let mut current: Option<(Vec<String>, Vec<Accumulator>)> = None;
+            for (line, record) in reader.records().enumerate() {
+                let record = record?;
+                let key = key_of(&record);
+                match &mut current {
+                    Some((current_key, cells)) if *current_key == key => add(cells, &record)?,
+                    _ => {
+                        if let Some((previous_key, cells)) = current.take() {
+                            if compare_keys(&previous_key, &key) == Ordering::Greater {
+                                bail!(
+                                    "第 {} 行的索引 {:?} 排在前一組 {:?} 之前，輸入未排序，請移除 sorted 選項",
+                                    line + 2,
+                                    key,
+                                    previous_key
+                                );
+                            }
+                            write_pivot_row(&mut writer, &previous_key, &cells, options.aggregate)?;
+                            rows_written += 1;
+                        }
+                        let mut cells = new_cells();
+                        add(&mut cells, &record)?;
+                        current = Some((key, cells));
+                    }
+                }
+            }
+            if let Some((key, cells)) = current {
+                write_pivot_row(&mut writer, &key, &cells, options.aggregate)?;
+                rows_written += 1;
+            }
+        } else {
+            let mut groups: BTreeMap<Vec<String>, Vec<Accumulator>> = BTreeMap::new();
+            for record in reader.records() {
+                let record = record?;
+                let cells = groups.entry(key_of(&record)).or_insert_with(new_cells);
+                add(cells, &record)?;
+            }
+            let mut groups: Vec<_> = groups.into_iter().collect();
+            groups.sort_by(|(a, _), (b, _)| compare_keys(a, b));
+            for (key, cells) in &groups {
+                write_pivot_row(&mut writer, key, cells, options.aggregate)?;
+            }
+            rows_written = groups.len();
+        }
+
+        writer.flush()?;
+        drop(writer);
+        report.finish(rows_written, 0, output_path).with_input(input_path, started)
+    }
+
+    /// 將寬表 CSV 轉為長表 CSV（逐列串流處理）
+    pub fn unpivot_csv_file(
+        input_path: &str,
+        output_path: &str,
+        options: &UnpivotOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let started = Instant::now();
+        let mut report = ReportBuilder::streaming();
+        let dialect = options.dialect.unwrap_or_else(|| Dialect::for_path(input_path));
+        let mut reader = dialect.reader_builder().from_path(input_path)?;
+        let headers = reader.headers()?.clone();
+        let id_idx = options
+            .id_columns
+            .iter()
+            .map(|name| column_index(&headers, name))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let value_idx: Vec<usize> = if options.value_columns.is_empty() {
+            (0..headers.len()).filter(|i| !id_idx.contains(i)).collect()
+        } else {
+            options
+                .value_columns
+                .iter()
+                .map(|name| column_index(&headers, name))
+                .collect::<anyhow::Result<Vec<_>>>()?
+        };
+
+        let mut writer = Writer::from_path(output_path)?;
+        let mut header_row: Vec<&str> = options.id_columns.iter().map(String::as_str).collect();
+        header_row.push(&options.variable_name);
+        header_row.push(&options.value_name);
+        writer.write_record(&header_row)?;
+
+        let (mut rows_read, mut rows_written) = (0, 0);
+        for record in reader.records() {
+            let record = record?;
+            rows_read += 1;
+            for &i in &value_idx {
+                let value = record.get(i).unwrap_or("");
+                if options.skip_empty && value.is_empty() {
+                    continue;
+                }
+                let mut row: Vec<&str> = id_idx.iter().map(|&j| record.get(j).unwrap_or("")).collect();
+                row.push(&headers[i]);
+                row.push(value);
+                writer.write_record(&row)?;
+                rows_written += 1;
+            }
+        }
+
+        writer.flush()?;
+        drop(writer);
+        report.rows_read(rows_read);
+        report.finish(rows_written, 0, output_path).with_input(input_path, started)
+    }
+}
+
+/// 索引與新欄位的排序方式：逐欄比較，兩邊都是數字時依數值大小，數字排在文字之前，
+/// 其餘依位元組順序（因此 `9` 在 `10` 之前，`1` 與 `1.0` 仍視為不同索引）
+fn compare_keys(a: &[String], b: &[String]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| compare_key_values(a, b))
+        .find(|o| o.is_ne())
+        .unwrap_or_else(|| a.len().cmp(&b.len()))
+}
+
+fn compare_key_values(a: &str, b: &str) -> Ordering {
+    let number = |s: &str| s.trim().parse::<f64>().ok().filter(|f| f.is_finite());
+    match (number(a), number(b)) {
+        (Some(x), Some(y)) => x.total_cmp(&y).then_with(|| a.cmp(b)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
+fn column_index(headers: &StringRecord, name: &str) -> anyhow::Result<usize> {
+    headers
+        .iter()
+        .position(|h| h == name)
+        .with_context(|| format!("找不到欄位: {}", name))
+}
+
+fn write_pivot_row<W: Write>(
+    writer: &mut Writer<W>,
+    key: &[String],
+    cells: &[Accumulator],
+    aggregate: Aggregate,
+) -> anyhow::Result<()> {
+    let mut row: Vec<String> = key.to_vec();
+    row.extend(cells.iter().map(|cell| cell.result(aggregate)));
+    writer.write_record(&row)?;
+    Ok(())
+}
@@ -0,0 +1,241 @@
+//! 列抽樣：head、tail、每 N 列、可重現的蓄水池抽樣與分層抽樣
+
+use anyhow::{bail, Context};
+use csv::{Reader, ReaderBuilder, StringRecord};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::str::FromStr;
+
+use crate::converter::CsvConverter;
+
+/// 從檔尾往回讀取時每次讀取的位元組數
+const TAIL_CHUNK_SIZE: u64 = 64 * 1024;
+
+/// 抽樣方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sampling {
+    /// 前 N 列
+    Head(usize),
+    /// 最後 N 列
+    Tail(usize),
+    /// 每 N 列取一列（從第一列開始）
+    EveryNth(usize),
+    /// 均勻抽出 N 列
+    Reservoir(usize),
+    /// 依欄位值分組，每組均勻抽出 N 列
+    Stratified { column: String, size: usize },
+}
+
+impl FromStr for Sampling {
+    type Err = anyhow::Error;
+
+    /// 解析 `head:N`、`tail:N`、`every:N`、`reservoir:N` 或 `stratified:欄位:N`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (method, rest) = s.split_once(':').with_context(|| format!("抽樣方式缺少數量: {}", s))?;
+        let count = |text: &str| text.trim().parse::<usize>().with_context(|| format!("無效的抽樣數量: {}", text));
+        Ok(match method {
+            "head" => Sampling::Head(count(rest)?),
+            "tail" => Sampling::Tail(count(rest)?),
+            "every" => match count(rest)? {
+                0 => bail!("every 的間隔必須大於 0"),
+                step => Sampling::EveryNth(step),
+            },
+            "reservoir" => Sampling::Reservoir(count(rest)?),
+            "stratified" => {
+                let (column, size) = rest.rsplit_once(':').with_context(|| format!("格式應為 stratified:欄位:N: {}", s))?;
+                Sampling::Stratified { column: column.to_string(), size: count(size)? }
+            }
+            other => bail!("未知的抽樣方式: {}（可用: head, tail, every, reservoir, stratified）", other),
+        })
+    }
+}
+
+/// 抽樣設定
+#[derive(Debug, Clone)]
+pub struct SampleOptions {
+    pub method: Sampling,
+    /// 隨機抽樣的種子，相同種子得到相同結果
+    pub seed: u64,
+}
+
+impl Default for SampleOptions {
+    fn default() -> Self {
+        Self { method: Sampling::Head(10), seed: 0 }
+    }
+}
+
+/// 蓄水池抽樣，保留原始列號以便依輸入順序輸出
+struct Reservoir {
+    size: usize,
+    seen: usize,
+    items: Vec<(usize, StringRecord)>,
+}
+
+impl Reservoir {
+    fn new(size: usize) -> Self {
+        Self { size, seen: 0, items: Vec::new() }
+    }
+
+    fn offer(&mut self, row: usize, record: StringRecord, rng: &mut StdRng) {
+        self.seen += 1;
+        if self.items.len() < self.size {
+            self.items.push((row, record));
+        } else {
+            let slot = rng.random_range(0..self.seen);
+            if slot < self.size {
+                self.items[slot] = (row, record);
+            }
+        }
+    }
+}
+
+impl CsvConverter {
+    /// 以串流方式從 CSV 檔案抽樣，不會將整個檔案載入記憶體；結果依輸入順序排列
+    pub fn sample_csv_file(path: &str, options: &SampleOptions) -> anyhow::Result<(Vec<String>, Vec<StringRecord>)> {
+        let mut reader = Reader::from_path(path)?;
+        let headers: Vec<String> = reader.headers()?.iter().map(|h| h.to_string()).collect();
+
+        if let Sampling::Tail(count) = options.method {
+            if let Some(records) = tail_by_seeking(path, headers.len(), count)? {
+                return Ok((headers, records));
+            }
+        }
+
+        let records = reader.into_records().map(|r| r.map_err(anyhow::Error::from));
+        let records = Self::sample_iter(&headers, records, options)?;
+        Ok((headers, records.into_iter().map(|(_, record)| record).collect()))
+    }
+
+    /// 對已讀入的記錄抽樣（試算表、固定寬度檔等來源）
+    pub fn sample_records(
+        headers: &[String],
+        records: Vec<StringRecord>,
+        options: &SampleOptions,
+    ) -> anyhow::Result<Vec<StringRecord>> {
+        let records = Self::sample_records_with_rows(headers, records, options)?;
+        Ok(records.into_iter().map(|(_, record)| record).collect())
+    }
+
+    /// 同 [`CsvConverter::sample_records`]，另外回傳每筆在原始記錄中的列號（從 0 開始）
+    pub fn sample_records_with_rows(
+        headers: &[String],
+        records: Vec<StringRecord>,
+        options: &SampleOptions,
+    ) -> anyhow::Result<Vec<(usize, StringRecord)>> {
+        Self::sample_iter(headers, records.into_iter().map(Ok), options)
+    }
+
+    fn sample_iter(
+        headers: &[String],
+        records: impl Iterator<Item = anyhow::Result<StringRecord>>,
+        options: &SampleOptions,
+    ) -> anyhow::Result<Vec<(usize, StringRecord)>> {
+        // 直接建構的設定不經過 FromStr 的檢查
+        if options.method == Sampling::EveryNth(0) {
+            bail!("every 的間隔必須大於 0");
+        }
+        let mut rng = StdRng::seed_from_u64(options.seed);
+        let records = records.enumerate().map(|(row, record)| record.map(|record| (row, record)));
+
+        match &options.method {
+            Sampling::Head(count) => records.take(*count).collect(),
+            Sampling::Tail(count) => {
+                let mut window = VecDeque::with_capacity(*count + 1);
+                for record in records {
+                    window.push_back(record?);
+                    if window.len() > *count {
+                        window.pop_front();
+                    }
+                }
+                Ok(window.into())
+            }
+            Sampling::EveryNth(step) => records.step_by(*step).collect(),
+            Sampling::Reservoir(size) => {
+                let mut reservoir = Reservoir::new(*size);
+                for record in records {
+                    let (row, record) = record?;
+                    reservoir.offer(row, record, &mut rng);
+                }
+                Ok(in_input_order(reservoir.items))
+            }
+            Sampling::Stratified { column, size } => {
+                let idx = headers
+                    .iter()
+                    .position(|h| h == column)
+                    .with_context(|| format!("找不到分層欄位: {}", column))?;
+                let mut groups: BTreeMap<String, Reservoir> = BTreeMap::new();
+                for record in records {
+                    let (row, record) = record?;
+                    let key = record.get(idx).unwrap_or("").to_string();
+                    groups.entry(key).or_insert_with(|| Reservoir::new(*size)).offer(row, record, &mut rng);
+                }
+                Ok(in_input_order(groups.into_values().flat_map(|g| g.items).collect()))
+            }
+        }
+    }
+}
+
+fn in_input_order(mut items: Vec<(usize, StringRecord)>) -> Vec<(usize, StringRecord)> {
+    items.sort_by_key(|(row, _)| *row);
+    items
+}
+
+/// 從檔尾往回尋找最後 `count` 列的起點後只解析這一段
+///
+/// 以換行字元切列並略過空白列（與 CSV 讀取器相同）；欄位可能含有換行時無法從中間切入，
+/// 因此掃描範圍內出現引號、或解析結果的列數或欄位數不符時回傳 None，改為從頭串流讀取。
+fn tail_by_seeking(path: &str, field_count: usize, count: usize) -> anyhow::Result<Option<Vec<StringRecord>>> {
+    if count == 0 {
+        return Ok(Some(Vec::new()));
+    }
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+
+    // 每遇到一個非空白列前的換行就算一列，第 count 個換行之後即為起點
+    let mut position = len;
+    let mut newlines = 0;
+    let mut line_has_content = false;
+    let mut start = 0;
+    let mut chunk = vec![0u8; TAIL_CHUNK_SIZE as usize];
+    'scan: while position > 0 {
+        let read = TAIL_CHUNK_SIZE.min(position);
+        position -= read;
+        file.seek(SeekFrom::Start(position))?;
+        let buf = &mut chunk[..read as usize];
+        file.read_exact(buf)?;
+        for i in (0..buf.len()).rev() {
+            match buf[i] {
+                b'"' => return Ok(None),
+                b'\n' if line_has_content => {
+                    line_has_content = false;
+                    newlines += 1;
+                    if newlines == count {
+                        start = position + i as u64 + 1;
+                        break 'scan;
+                    }
+                }
+                b'\n' | b'\r' => {}
+                _ => line_has_content = true,
+            }
+        }
+    }
+    if start == 0 {
+        // 檔案列數不足，包含標題列，交給串流讀取
+        return Ok(None);
+    }
+
+    file.seek(SeekFrom::Start(start))?;
+    let mut reader = ReaderBuilder::new().has_headers(false).flexible(true).from_reader(file);
+    let mut records = Vec::with_capacity(count);
+    for record in reader.records() {
+        let record = record?;
+        if record.len() != field_count {
+            return Ok(None);
+        }
+        records.push(record);
+    }
+    Ok((records.len() == count).then_some(records))
+}
@@ -0,0 +1,225 @@
+use anyhow::{bail, Context};
+use csv::StringRecord;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
+
+use crate::value::{ParseRule, ValueOptions};
+
+/// 欄位推斷出的資料型別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnType {
+    Integer,
+    Float,
+    Boolean,
+    String,
+}
+
+impl ColumnType {
+    /// 由轉換後的 JSON 值判斷型別，null 回傳 None
+    pub fn of_value(value: &Value) -> Option<ColumnType> {
+        match value {
+            Value::Null => None,
+            Value::Bool(_) => Some(ColumnType::Boolean),
+            Value::Number(n) if n.is_i64() || n.is_u64() => Some(ColumnType::Integer),
+            Value::Number(_) => Some(ColumnType::Float),
+            _ => Some(ColumnType::String),
+        }
+    }
+
+    /// 合併兩種型別：整數與浮點數合併為浮點數，其餘不一致時退回字串
+    pub fn merge(self, other: ColumnType) -> ColumnType {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (ColumnType::Integer, ColumnType::Float) | (ColumnType::Float, ColumnType::Integer) => {
+                ColumnType::Float
+            }
+            _ => ColumnType::String,
+        }
+    }
+
+    /// 宣告為此型別的欄位是否接受另一種型別的值（浮點數欄位可接受整數，字串欄位接受任何值）
+    pub fn accepts(self, other: ColumnType) -> bool {
+        self == other
+            || self == ColumnType::String
+            || (self == ColumnType::Float && other == ColumnType::Integer)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ColumnType::Integer => "integer",
+            ColumnType::Float => "float",
+            ColumnType::Boolean => "boolean",
+            ColumnType::String => "string",
+        }
+    }
+}
+
+impl std::fmt::Display for ColumnType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ColumnType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "integer" | "int" => Ok(ColumnType::Integer),
+            "float" | "number" => Ok(ColumnType::Float),
+            "boolean" | "bool" => Ok(ColumnType::Boolean),
+            "string" | "text" => Ok(ColumnType::String),
+            _ => bail!("未知的欄位型別: {}（可用: integer, float, boolean, string）", s),
+        }
+    }
+}
+
+/// 單一欄位的 schema
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnSchema {
+    pub name: String,
+    pub column_type: ColumnType,
+    pub nullable: bool,
+    /// 轉換此欄位時套用過的解析規則（千分位、貨幣符號等）
+    #[serde(default)]
+    pub rules: Vec<ParseRule>,
+}
+
+/// 由 CSV 資料推斷出的整體 schema
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schema {
+    pub columns: Vec<ColumnSchema>,
+}
+
+/// 資料不符合 schema 的地方
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaViolation {
+    /// 檔案中的行號；標題列的問題為 1
+    pub line: u64,
+    pub column: String,
+    pub message: String,
+}
+
+impl Schema {
+    /// 掃描所有記錄，以預設的欄位值轉換設定推斷 schema
+    pub fn infer(headers: &[String], records: &[StringRecord]) -> Self {
+        Self::infer_with(headers, records, &ValueOptions::default())
+    }
+
+    /// 掃描所有記錄，推斷每個欄位的型別、是否可為 null 以及套用的解析規則
+    pub fn infer_with(headers: &[String], records: &[StringRecord], options: &ValueOptions) -> Self {
+        let mut types: Vec<Option<ColumnType>> = vec![None; headers.len()];
+        let mut nullable = vec![false; headers.len()];
+        let mut rules: Vec<Vec<ParseRule>> = vec![Vec::new(); headers.len()];
+
+        for record in records {
+            for (i, header) in headers.iter().enumerate() {
+                let field = record.get(i).unwrap_or("");
+                if field.is_empty() {
+                    nullable[i] = true;
+                    continue;
+                }
+
+                // 轉換失敗（例如非有限數值設為錯誤）時在推斷階段視為字串
+                let (value, applied) = options
+                    .parse_with_rules(header, field)
+                    .unwrap_or_else(|_| (Value::String(field.to_string()), Vec::new()));
+
+                match ColumnType::of_value(&value) {
+                    Some(t) => types[i] = Some(types[i].map_or(t, |prev| prev.merge(t))),
+                    None => nullable[i] = true,
+                }
+                for rule in applied {
+                    if !rules[i].contains(&rule) {
+                        rules[i].push(rule);
+                    }
+                }
+            }
+        }
+
+        let columns = headers
+            .iter()
+            .zip(types)
+            .zip(nullable)
+            .zip(rules)
+            .map(|(((name, column_type), nullable), rules)| ColumnSchema {
+                name: name.clone(),
+                // 整欄皆為空值時以字串處理
+                column_type: column_type.unwrap_or(ColumnType::String),
+                nullable,
+                rules,
+            })
+            .collect();
+
+        Schema { columns }
+    }
+
+    /// 依欄位名稱尋找欄位索引
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|c| c.name == name)
+    }
+
+    /// 讀取序列化為 JSON 的 schema，例如 `csv_toolbox infer-schema --json` 的輸出
+    pub fn from_json_file(path: &str) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path).with_context(|| format!("無法讀取 schema: {}", path))?;
+        serde_json::from_str(&text).with_context(|| format!("無效的 schema: {}", path))
+    }
+
+    /// 檢查標題列與每筆記錄是否符合 schema：欄位是否齊全、型別是否相容、不可為 null 的欄位是否有值
+    pub fn validate(
+        &self,
+        headers: &[String],
+        records: &[StringRecord],
+        options: &ValueOptions,
+    ) -> Vec<SchemaViolation> {
+        let mut violations = Vec::new();
+        let header_violation = |column: &str, message: String| SchemaViolation {
+            line: 1,
+            column: column.to_string(),
+            message,
+        };
+
+        for column in &self.columns {
+            if !headers.contains(&column.name) {
+                violations.push(header_violation(&column.name, "缺少 schema 中的欄位".to_string()));
+            }
+        }
+        let mut checked = Vec::new();
+        for (i, header) in headers.iter().enumerate() {
+            match self.columns.iter().find(|c| &c.name == header) {
+                Some(column) => checked.push((i, column)),
+                None => violations.push(header_violation(header, "schema 中沒有此欄位".to_string())),
+            }
+        }
+
+        for (n, record) in records.iter().enumerate() {
+            // 沒有位置資訊（試算表等來源）時以標題列之後的列數計算
+            let line = record.position().map_or(n as u64 + 2, |p| p.line());
+            for &(i, column) in &checked {
+                let field = record.get(i).unwrap_or("");
+                // 與推斷時相同，空字串視為 null
+                let parsed = match field {
+                    "" => Ok(Value::Null),
+                    _ => options.parse(&column.name, field),
+                };
+                let message = match parsed {
+                    Err(error) => Some(error.to_string()),
+                    Ok(value) => match ColumnType::of_value(&value) {
+                        None if !column.nullable => Some("不可為空值".to_string()),
+                        Some(actual) if !column.column_type.accepts(actual) => {
+                            Some(format!("應為 {}，實際為 {}: {}", column.column_type, actual, field))
+                        }
+                        _ => None,
+                    },
+                };
+                if let Some(message) = message {
+                    violations.push(SchemaViolation { line, column: column.name.clone(), message });
+                }
+            }
+        }
+
+        violations
+    }
+}
@@ -0,0 +1,340 @@
+//! 本機 HTTP 轉換服務（需啟用 `serve` feature，使用 axum）
+//!
+//! | 路徑 | 說明 |
+//! |------|------|
+//! | `POST /convert` | CSV 轉為 JSON、NDJSON 或 Parquet |
+//! | `POST /profile` | 欄位概況 |
+//! | `POST /validate` | 依 [`ServeOptions::schema`] 驗證，不符合時回應 422 |
+//! | `GET /health` | 健康檢查 |
+//!
+//! 請求本文為以逗號分隔的 CSV，回應格式依 `Accept` 決定，未指定時為 JSON。
+//! JSON 與 NDJSON 的轉換邊讀邊寫；回應開始送出後才發生的錯誤（例如欄位數不符）會中斷連線，
+//! 不送出結尾的 chunk，客戶端因此能分辨不完整的回應。
+
+use anyhow::Context;
+use axum::body::{Body, Bytes};
+use axum::extract::State;
+use axum::http::header::{ACCEPT, CONTENT_LENGTH, CONTENT_TYPE};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use csv::StringRecord;
+use futures_util::{stream, StreamExt, TryStreamExt};
+use http_body_util::{BodyExt, LengthLimitError, Limited};
+use serde::Serialize;
+use std::future::Future;
+use std::io;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio_util::io::{ReaderStream, StreamReader};
+
+use crate::async_io::{AsyncFormat, AsyncOptions};
+use crate::converter::CsvConverter;
+use crate::dialect::Dialect;
+use crate::schema::Schema;
+use crate::value::ValueOptions;
+
+/// HTTP 服務設定
+#[derive(Debug, Clone)]
+pub struct ServeOptions {
+    /// 請求本文的大小上限（位元組），超過時回應 413
+    pub max_body_bytes: usize,
+    /// `POST /validate` 使用的 schema；未設定時該路徑回應 404
+    pub schema: Option<Schema>,
+    /// 串流轉換的緩衝區大小（位元組）
+    pub buffer_size: usize,
+    pub values: ValueOptions,
+}
+
+impl Default for ServeOptions {
+    fn default() -> Self {
+        Self {
+            max_body_bytes: 64 * 1024 * 1024,
+            schema: None,
+            buffer_size: 64 * 1024,
+            values: ValueOptions::default(),
+        }
+    }
+}
+
+/// 依 `Accept` 選出的回應格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResponseFormat {
+    Json,
+    Ndjson,
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+/// `POST /convert` 可回應的格式，第一個為預設
+const CONVERT_FORMATS: &[ResponseFormat] = &[
+    ResponseFormat::Json,
+    ResponseFormat::Ndjson,
+    #[cfg(feature = "parquet")]
+    ResponseFormat::Parquet,
+];
+
+/// `POST /profile` 與 `POST /validate` 可回應的格式
+const REPORT_FORMATS: &[ResponseFormat] = &[ResponseFormat::Json, ResponseFormat::Ndjson];
+
+impl ResponseFormat {
+    fn content_type(self) -> &'static str {
+        match self {
+            ResponseFormat::Json => "application/json",
+            ResponseFormat::Ndjson => "application/x-ndjson",
+            #[cfg(feature = "parquet")]
+            ResponseFormat::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+
+    /// 是否為此格式的媒體類型（含常見的別名）
+    fn matches(self, media_type: &str) -> bool {
+        match self {
+            ResponseFormat::Json => media_type == "application/json",
+            ResponseFormat::Ndjson => {
+                matches!(media_type, "application/x-ndjson" | "application/ndjson" | "application/jsonl")
+            }
+            #[cfg(feature = "parquet")]
+            ResponseFormat::Parquet => matches!(media_type, "application/vnd.apache.parquet" | "application/x-parquet"),
+        }
+    }
+
+    /// 取 q 值最高的媒體類型；萬用字元對應 `supported` 中第一個未被 `q=0` 排除的格式，
+    /// 沒有 `Accept` 時回傳預設格式，沒有可用格式時回傳 None
+    fn negotiate(headers: &HeaderMap, supported: &[ResponseFormat]) -> Option<ResponseFormat> {
+        let ranges: Vec<(String, f32)> = headers
+            .get_all(ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let media_type = parts.next()?.trim().to_ascii_lowercase();
+                let quality = parts
+                    .filter_map(|param| param.trim().strip_prefix("q="))
+                    .find_map(|q| q.trim().parse().ok())
+                    .unwrap_or(1.0);
+                (!media_type.is_empty()).then_some((media_type, quality))
+            })
+            .collect();
+        if ranges.is_empty() {
+            return supported.first().copied();
+        }
+
+        let excluded = |format: ResponseFormat| ranges.iter().any(|(t, q)| *q <= 0.0 && format.matches(t));
+        let mut best: Option<(f32, ResponseFormat)> = None;
+        for (media_type, quality) in &ranges {
+            if *quality <= 0.0 {
+                continue;
+            }
+            let format = match media_type.as_str() {
+                "*/*" | "application/*" => supported.iter().copied().find(|&f| !excluded(f)),
+                _ => supported.iter().copied().find(|f| f.matches(media_type)),
+            };
+            if let Some(format) = format {
+                if best.is_none_or(|(q, _)| *quality > q) {
+                    best = Some((*quality, format));
+                }
+            }
+        }
+        best.map(|(_, format)| format)
+    }
+}
+
+impl CsvConverter {
+    /// 建立 HTTP 服務的路由，可交給 `axum::serve` 或直接以 tower 的 `Service` 呼叫
+    pub fn http_router(options: ServeOptions) -> Router {
+        Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .route("/convert", post(convert))
+            .route("/profile", post(profile))
+            .route("/validate", post(validate))
+            .fallback(|| async {
+                error_response(StatusCode::NOT_FOUND, "找不到路徑（可用: POST /convert, /profile, /validate）")
+            })
+            .with_state(Arc::new(options))
+    }
+
+    /// 在 `listener` 上提供 HTTP 服務直到 `shutdown` 完成，進行中的請求會先處理完
+    ///
+    /// 綁定 `127.0.0.1:0` 由系統挑選可用的連接埠，實際位址可由 `listener.local_addr()` 取得。
+    pub async fn serve_http<F>(listener: TcpListener, options: ServeOptions, shutdown: F) -> anyhow::Result<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        axum::serve(listener, Self::http_router(options))
+            .with_graceful_shutdown(shutdown)
+            .await
+            .context("HTTP 服務失敗")
+    }
+}
+
+async fn convert(State(options): State<Arc<ServeOptions>>, headers: HeaderMap, body: Body) -> Response {
+    let Some(format) = ResponseFormat::negotiate(&headers, CONVERT_FORMATS) else {
+        return not_acceptable(CONVERT_FORMATS);
+    };
+    if let Some(response) = check_content_length(&headers, &options) {
+        return response;
+    }
+    let async_format = match format {
+        ResponseFormat::Json => AsyncFormat::Json,
+        ResponseFormat::Ndjson => AsyncFormat::Ndjson,
+        // Parquet 需要整份資料才能寫出
+        #[cfg(feature = "parquet")]
+        ResponseFormat::Parquet => {
+            let (headers, records) = match read_body_records(body, &options).await {
+                Ok(data) => data,
+                Err(response) => return response,
+            };
+            let mut output = Vec::new();
+            return match CsvConverter::write_parquet(&headers, &records, &mut output, &options.values) {
+                Ok(()) => ([(CONTENT_TYPE, format.content_type())], output).into_response(),
+                Err(error) => error_response(StatusCode::BAD_REQUEST, format!("{:#}", error)),
+            };
+        }
+    };
+
+    let async_options = AsyncOptions {
+        format: async_format,
+        buffer_size: options.buffer_size,
+        values: options.values.clone(),
+    };
+    let reader = StreamReader::new(
+        Limited::new(body, options.max_body_bytes)
+            .into_data_stream()
+            .map_err(io::Error::other),
+    );
+    let (writer, output) = tokio::io::duplex(options.buffer_size.max(1));
+    let task = tokio::spawn(async move { CsvConverter::convert_csv_async(reader, writer, &async_options).await });
+
+    // 送出第一塊輸出前就結束的轉換（開頭即有錯誤）仍能回應對應的狀態碼
+    let mut chunks = ReaderStream::new(output);
+    let first = match chunks.next().await {
+        Some(chunk) => chunk,
+        None => {
+            return match task.await {
+                Ok(Ok(_)) => ([(CONTENT_TYPE, format.content_type())], Bytes::new()).into_response(),
+                Ok(Err(error)) => conversion_error(&error, &options),
+                Err(error) => error_response(StatusCode::INTERNAL_SERVER_ERROR, error),
+            };
+        }
+    };
+
+    // 轉換失敗時讓回應本文以錯誤結束，連線會被中斷而不是正常結束
+    let outcome = stream::once(task).filter_map(|result| async move {
+        let error = match result {
+            Ok(Ok(_)) => return None,
+            Ok(Err(error)) => error,
+            Err(error) => error.into(),
+        };
+        Some(Err(io::Error::other(format!("{:#}", error))))
+    });
+    let body = Body::from_stream(stream::iter([first]).chain(chunks).chain(outcome));
+    ([(CONTENT_TYPE, format.content_type())], body).into_response()
+}
+
+async fn profile(State(options): State<Arc<ServeOptions>>, headers: HeaderMap, body: Body) -> Response {
+    let Some(format) = ResponseFormat::negotiate(&headers, REPORT_FORMATS) else {
+        return not_acceptable(REPORT_FORMATS);
+    };
+    if let Some(response) = check_content_length(&headers, &options) {
+        return response;
+    }
+    match read_body_records(body, &options).await {
+        Ok((headers, records)) => {
+            let profiles = CsvConverter::profile_records(&headers, &records, &options.values);
+            serialized_response(StatusCode::OK, format, &profiles)
+        }
+        Err(response) => response,
+    }
+}
+
+async fn validate(State(options): State<Arc<ServeOptions>>, headers: HeaderMap, body: Body) -> Response {
+    let Some(schema) = &options.schema else {
+        return error_response(StatusCode::NOT_FOUND, "服務未設定 schema，無法驗證");
+    };
+    let Some(format) = ResponseFormat::negotiate(&headers, REPORT_FORMATS) else {
+        return not_acceptable(REPORT_FORMATS);
+    };
+    if let Some(response) = check_content_length(&headers, &options) {
+        return response;
+    }
+    match read_body_records(body, &options).await {
+        Ok((headers, records)) => {
+            let violations = schema.validate(&headers, &records, &options.values);
+            let status = if violations.is_empty() { StatusCode::OK } else { StatusCode::UNPROCESSABLE_ENTITY };
+            serialized_response(status, format, &violations)
+        }
+        Err(response) => response,
+    }
+}
+
+/// 讀入整個請求本文並解析為 CSV
+async fn read_body_records(
+    body: Body,
+    options: &ServeOptions,
+) -> Result<(Vec<String>, Vec<StringRecord>), Response> {
+    let bytes = match Limited::new(body, options.max_body_bytes).collect().await {
+        Ok(collected) => collected.to_bytes(),
+        Err(error) if error.is::<LengthLimitError>() => return Err(too_large(options)),
+        Err(error) => return Err(error_response(StatusCode::BAD_REQUEST, format!("讀取請求本文失敗: {}", error))),
+    };
+    CsvConverter::read_csv(&bytes[..], &Dialect::default())
+        .map_err(|error| error_response(StatusCode::BAD_REQUEST, format!("無效的 CSV: {}", error)))
+}
+
+/// `Content-Length` 已超過上限時直接回應 413，不讀取本文
+fn check_content_length(headers: &HeaderMap, options: &ServeOptions) -> Option<Response> {
+    let length: u64 = headers.get(CONTENT_LENGTH)?.to_str().ok()?.parse().ok()?;
+    (length > options.max_body_bytes as u64).then(|| too_large(options))
+}
+
+/// 串流轉換的錯誤：本文超過上限為 413，其餘視為輸入有誤
+fn conversion_error(error: &anyhow::Error, options: &ServeOptions) -> Response {
+    let exceeded = error.chain().any(|cause| {
+        cause
+            .downcast_ref::<io::Error>()
+            .and_then(io::Error::get_ref)
+            .is_some_and(|inner| inner.is::<LengthLimitError>())
+    });
+    if exceeded {
+        too_large(options)
+    } else {
+        error_response(StatusCode::BAD_REQUEST, format!("{:#}", error))
+    }
+}
+
+/// JSON 為陣列，NDJSON 每個值一行
+fn serialized_response<T: Serialize>(status: StatusCode, format: ResponseFormat, items: &[T]) -> Response {
+    let body = match format {
+        ResponseFormat::Ndjson => items.iter().try_fold(Vec::new(), |mut body, item| {
+            serde_json::to_writer(&mut body, item)?;
+            body.push(b'\n');
+            Ok(body)
+        }),
+        _ => serde_json::to_vec_pretty(items),
+    };
+    match body {
+        Ok(body) => (status, [(CONTENT_TYPE, format.content_type())], body).into_response(),
+        Err(error) => error_response(StatusCode::INTERNAL_SERVER_ERROR, error),
+    }
+}
+
+fn not_acceptable(supported: &[ResponseFormat]) -> Response {
+    let types: Vec<&str> = supported.iter().map(|f| f.content_type()).collect();
+    error_response(StatusCode::NOT_ACCEPTABLE, format!("不支援要求的格式（可用: {}）", types.join(", ")))
+}
+
+fn too_large(options: &ServeOptions) -> Response {
+    error_response(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        format!("請求本文超過 {} 位元組的上限", options.max_body_bytes),
+    )
+}
+
+/// 錯誤以 `{"error": "..."}` 回應
+fn error_response(status: StatusCode, message: impl std::fmt::Display) -> Response {
+    let body = serde_json::json!({ "error": message.to_string() });
+    (status, [(CONTENT_TYPE, "application/json")], body.to_string()).into_response()
+}
@@ -0,0 +1,313 @@
+use anyhow::{bail, Context};
+use calamine::{open_workbook_auto, Data, Reader};
+use csv::StringRecord;
+use rust_xlsxwriter::{Format, Workbook, Worksheet};
+use serde_json::Value;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::converter::CsvConverter;
+use crate::dialect::Dialect;
+use crate::report::{ConversionReport, ReportBuilder};
+use crate::value::{ValueOptions, MAX_SAFE_INTEGER};
+
+/// 可讀取的試算表副檔名
+pub const SPREADSHEET_EXTENSIONS: &[&str] = &["xlsx", "xlsm", "xlsb", "xls", "ods"];
+
+/// 工作表的選擇方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SheetSelector {
+    /// 依順序選擇（從 0 開始）
+    Index(usize),
+    /// 依名稱選擇
+    Name(String),
+}
+
+impl Default for SheetSelector {
+    fn default() -> Self {
+        SheetSelector::Index(0)
+    }
+}
+
+impl FromStr for SheetSelector {
+    type Err = anyhow::Error;
+
+    /// 純數字視為索引，其餘視為工作表名稱
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.parse::<usize>() {
+            Ok(index) => SheetSelector::Index(index),
+            Err(_) => SheetSelector::Name(s.to_string()),
+        })
+    }
+}
+
+/// 試算表讀取設定
+#[derive(Debug, Clone, Default)]
+pub struct SpreadsheetOptions {
+    pub sheet: SheetSelector,
+    /// 儲存格範圍，例如 `B2:F100`；只給左上角（`B2`）時讀到資料結尾
+    pub range: Option<String>,
+}
+
+/// xlsx 匯出設定
+#[derive(Debug, Clone)]
+pub struct XlsxExportOptions {
+    /// 資料工作表名稱
+    pub sheet_name: String,
+    /// 欄位值轉換設定，決定儲存格型別
+    pub values: ValueOptions,
+    /// 是否另外加入一個列出推斷 schema 的工作表
+    pub include_schema: bool,
+}
+
+impl Default for XlsxExportOptions {
+    fn default() -> Self {
+        Self {
+            sheet_name: "data".to_string(),
+            values: ValueOptions::default(),
+            include_schema: false,
+        }
+    }
+}
+
+impl CsvConverter {
+    /// 讀取 .xlsx / .xls / .ods 工作表，回傳與 CSV 相同的標題列與記錄
+    pub fn read_spreadsheet_file(
+        path: &str,
+        options: &SpreadsheetOptions,
+    ) -> anyhow::Result<(Vec<String>, Vec<StringRecord>)> {
+        let mut workbook = open_workbook_auto(path)
+            .with_context(|| format!("無法開啟試算表: {}", path))?;
+
+        let range = match &options.sheet {
+            SheetSelector::Index(index) => workbook
+                .worksheet_range_at(*index)
+                .with_context(|| format!("找不到第 {} 個工作表", index))??,
+            SheetSelector::Name(name) => workbook
+                .worksheet_range(name)
+                .with_context(|| format!("找不到工作表: {}", name))?,
+        };
+
+        // 依指定的儲存格範圍裁切，終點不超過資料結尾；起點已在資料之外時沒有任何列
+        let range = match &options.range {
+            Some(spec) => {
+                let (start, end) = parse_cell_range(spec)?;
+                let Some(data_end) = range.end() else {
+                    return Ok((Vec::new(), Vec::new()));
+                };
+                if start.0 > data_end.0 || start.1 > data_end.1 {
+                    return Ok((Vec::new(), Vec::new()));
+                }
+                let end = end.unwrap_or(data_end);
+                range.range(start, (end.0.min(data_end.0), end.1.min(data_end.1)))
+            }
+            None => range,
+        };
+
+        let mut rows = range.rows();
+        let headers: Vec<String> = match rows.next() {
+            Some(row) => row
+                .iter()
+                .enumerate()
+                .map(|(i, cell)| match cell_to_string(cell) {
+                    h if h.is_empty() => format!("column_{}", i + 1),
+                    h => h,
+                })
+                .collect(),
+            None => return Ok((Vec::new(), Vec::new())),
+        };
+
+        let records = rows
+            .filter(|row| row.iter().any(|cell| *cell != Data::Empty))
+            .map(|row| row.iter().map(cell_to_string).collect::<StringRecord>())
+            .collect();
+
+        Ok((headers, records))
+    }
+
+    /// 依副檔名讀取 CSV、試算表或 Avro 檔案；.tsv 檔以 tab 分隔
+    pub fn read_input_file(
+        path: &str,
+        sheet: &SpreadsheetOptions,
+    ) -> anyhow::Result<(Vec<String>, Vec<StringRecord>)> {
+        Self::read_input_file_with_dialect(path, sheet, &Dialect::for_path(path))
+    }
+
+    /// 同 [`read_input_file`](Self::read_input_file)，CSV 輸入以指定方言解析
+    pub fn read_input_file_with_dialect(
+        path: &str,
+        sheet: &SpreadsheetOptions,
+        dialect: &Dialect,
+    ) -> anyhow::Result<(Vec<String>, Vec<StringRecord>)> {
+        if is_spreadsheet_path(path) {
+            Self::read_spreadsheet_file(path, sheet)
+        } else if path.to_ascii_lowercase().ends_with(".avro") {
+            Self::read_avro_file(path)
+        } else {
+            Ok(Self::read_csv_file_with_dialect(path, dialect)?)
+        }
+    }
+
+    /// 將 CSV 檔案轉換為 xlsx 檔案
+    pub fn convert_csv_to_xlsx_file(
+        csv_path: &str,
+        xlsx_path: &str,
+        options: &XlsxExportOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let started = Instant::now();
+        let (headers, records) = Self::read_csv_file(csv_path)?;
+        Self::write_xlsx_file(&headers, &records, xlsx_path, options)?.with_input(csv_path, started)
+    }
+
+    /// 寫出 xlsx 檔案：儲存格依推斷的型別寫入，標題列粗體並凍結
+    pub fn write_xlsx_file(
+        headers: &[String],
+        records: &[StringRecord],
+        xlsx_path: &str,
+        options: &XlsxExportOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let mut report = ReportBuilder::new(headers, records, &options.values);
+        let mut text_numbers = 0;
+        let mut workbook = Workbook::new();
+        let header_format = Format::new().set_bold();
+
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(&options.sheet_name)?;
+        write_header_row(sheet, headers, &header_format)?;
+
+        for (row, record) in records.iter().enumerate() {
+            let row = row as u32 + 1;
+            for (col, (header, field)) in headers.iter().zip(record.iter()).enumerate() {
+                let col = col as u16;
+                if field.is_empty() {
+                    continue;
+                }
+                match options.values.parse(header, field)? {
+                    Value::Null => {}
+                    Value::Bool(b) => {
+                        sheet.write_boolean(row, col, b)?;
+                    }
+                    Value::Number(n) => {
+                        let integer = n.is_i64() || n.is_u64();
+                        match n.as_f64() {
+                            // 超出 f64 精確範圍的整數以文字保存，避免損失精度
+                            Some(f) if !(integer && f.abs() > MAX_SAFE_INTEGER as f64) => {
+                                sheet.write_number(row, col, f)?;
+                            }
+                            _ => {
+                                text_numbers += 1;
+                                sheet.write_string(row, col, n.to_string())?;
+                            }
+                        }
+                    }
+                    Value::String(s) => {
+                        sheet.write_string(row, col, s)?;
+                    }
+                    other => {
+                        sheet.write_string(row, col, other.to_string())?;
+                    }
+                }
+            }
+        }
+        sheet.autofit();
+
+        if text_numbers > 0 {
+            report.warn(format!("{} 個超出 Excel 精確範圍的整數以文字儲存", text_numbers));
+        }
+
+        if options.include_schema {
+            let schema = report.schema();
+            let sheet = workbook.add_worksheet();
+            sheet.set_name("schema")?;
+            let columns = ["name", "type", "nullable", "rules"].map(String::from);
+            write_header_row(sheet, &columns, &header_format)?;
+
+            for (row, column) in schema.columns.iter().enumerate() {
+                let row = row as u32 + 1;
+                let rules = column.rules.iter().map(|r| r.as_str()).collect::<Vec<_>>().join(", ");
+                sheet.write_string(row, 0, &column.name)?;
+                sheet.write_string(row, 1, column.column_type.as_str())?;
+                sheet.write_boolean(row, 2, column.nullable)?;
+                sheet.write_string(row, 3, rules)?;
+            }
+            sheet.autofit();
+        }
+
+        workbook.save(xlsx_path)?;
+        Ok(report.finish(records.len(), 0, xlsx_path))
+    }
+}
+
+/// 依副檔名判斷是否為試算表檔案
+pub fn is_spreadsheet_path(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SPREADSHEET_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// 寫入粗體標題列並凍結第一列
+fn write_header_row(sheet: &mut Worksheet, headers: &[String], format: &Format) -> anyhow::Result<()> {
+    for (col, header) in headers.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, header, format)?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+    Ok(())
+}
+
+/// 將儲存格轉為字串，交由與 CSV 相同的型別推斷流程處理
+fn cell_to_string(cell: &Data) -> String {
+    match cell {
+        Data::Empty | Data::Error(_) => String::new(),
+        Data::DateTime(dt) => match dt.as_datetime() {
+            Some(datetime) if datetime.time() == chrono::NaiveTime::MIN => {
+                datetime.format("%Y-%m-%d").to_string()
+            }
+            Some(datetime) => datetime.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            None => dt.to_string(),
+        },
+        other => other.to_string(),
+    }
+}
+
+/// 從 0 開始的 (列, 欄) 儲存格座標
+type CellPos = (u32, u32);
+
+/// Excel 工作表的最大列數與欄數（欄 `XFD`）
+const MAX_ROWS: u32 = 1_048_576;
+const MAX_COLUMNS: u32 = 16_384;
+
+/// 解析 `A1:D20` 或 `A1` 形式的範圍；起點必須在終點的左上方
+fn parse_cell_range(spec: &str) -> anyhow::Result<(CellPos, Option<CellPos>)> {
+    let Some((start, end)) = spec.split_once(':') else {
+        return Ok((parse_cell_ref(spec)?, None));
+    };
+    let (start, end) = (parse_cell_ref(start)?, parse_cell_ref(end)?);
+    if start.0 > end.0 || start.1 > end.1 {
+        bail!("無效的儲存格範圍: {}（起點必須在終點的左上方，例如 B2:F100）", spec);
+    }
+    Ok((start, Some(end)))
+}
+
+/// 解析 `AB12` 形式的儲存格位置
+fn parse_cell_ref(cell: &str) -> anyhow::Result<CellPos> {
+    let cell = cell.trim().to_ascii_uppercase();
+    let split = cell.find(|c: char| c.is_ascii_digit()).unwrap_or(cell.len());
+    let (letters, digits) = cell.split_at(split);
+
+    if letters.is_empty() || !letters.bytes().all(|b| b.is_ascii_uppercase()) {
+        bail!("無效的儲存格位置: {}", cell);
+    }
+    let row: u32 = digits.parse().with_context(|| format!("無效的儲存格位置: {}", cell))?;
+    if row == 0 || row > MAX_ROWS {
+        bail!("無效的儲存格位置: {}（列號需在 1 到 {} 之間）", cell, MAX_ROWS);
+    }
+
+    let col = letters
+        .bytes()
+        .try_fold(0u32, |acc, b| acc.checked_mul(26)?.checked_add((b - b'A' + 1) as u32))
+        .filter(|&col| col <= MAX_COLUMNS)
+        .with_context(|| format!("無效的儲存格位置: {}（欄位不可超過 XFD）", cell))?;
+    Ok((row - 1, col - 1))
+}
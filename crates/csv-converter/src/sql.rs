@@ -0,0 +1,284 @@
+use anyhow::{bail, Context};
+use csv::StringRecord;
+use rusqlite::{params_from_iter, types::Value as SqlValue, Connection};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::Instant;
+
+use crate::converter::CsvConverter;
+use crate::report::{ConversionReport, ReportBuilder};
+use crate::schema::{ColumnType, Schema};
+use crate::value::ValueOptions;
+
+/// SQL 匯出設定
+#[derive(Debug, Clone)]
+pub struct SqlExportOptions {
+    /// 資料表名稱
+    pub table_name: String,
+    /// 需要建立索引的欄位名稱
+    pub indexes: Vec<String>,
+    /// 產生 SQL 腳本時，每個 INSERT 敘述包含的列數
+    pub batch_size: usize,
+    /// 欄位值轉換設定（數值策略、地區格式等）
+    pub values: ValueOptions,
+}
+
+impl Default for SqlExportOptions {
+    fn default() -> Self {
+        Self {
+            table_name: "data".to_string(),
+            indexes: Vec::new(),
+            batch_size: 500,
+            values: ValueOptions::default(),
+        }
+    }
+}
+
+impl SqlExportOptions {
+    /// 指定資料表名稱，其他欄位使用預設值
+    pub fn new(table_name: impl Into<String>) -> Self {
+        Self {
+            table_name: table_name.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl ColumnType {
+    /// 對應的 SQLite 欄位型別（布林值以 0/1 整數儲存）
+    pub fn sql_type(self) -> &'static str {
+        match self {
+            ColumnType::Integer | ColumnType::Boolean => "INTEGER",
+            ColumnType::Float => "REAL",
+            ColumnType::String => "TEXT",
+        }
+    }
+}
+
+impl CsvConverter {
+    /// 將 CSV 檔案載入 SQLite 資料庫檔案（資料表已存在時會先刪除）
+    pub fn convert_csv_to_sqlite_file(
+        csv_path: &str,
+        db_path: &str,
+        options: &SqlExportOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let started = Instant::now();
+        let (headers, records) = Self::read_csv_file(csv_path)?;
+        Self::write_sqlite_file(&headers, &records, db_path, options)?.with_input(csv_path, started)
+    }
+
+    /// 將已讀取的標題列與記錄寫入 SQLite 資料庫檔案
+    pub fn write_sqlite_file(
+        headers: &[String],
+        records: &[StringRecord],
+        db_path: &str,
+        options: &SqlExportOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let report = ReportBuilder::new(headers, records, &options.values);
+        let schema = report.schema();
+        check_indexes(schema, options)?;
+        let types = sql_types(schema, records, &options.values)?;
+
+        let mut conn = Connection::open(db_path)
+            .with_context(|| format!("無法開啟 SQLite 資料庫: {}", db_path))?;
+        let tx = conn.transaction()?;
+
+        tx.execute(&format!("DROP TABLE IF EXISTS {}", quote_ident(&options.table_name)), [])?;
+        tx.execute(&create_table_sql(schema, &types, &options.table_name), [])?;
+
+        {
+            let placeholders = vec!["?"; schema.columns.len()].join(", ");
+            let mut stmt = tx.prepare(&format!(
+                "INSERT INTO {} VALUES ({})",
+                quote_ident(&options.table_name),
+                placeholders
+            ))?;
+
+            // 使用交易與預先編譯的敘述批次寫入
+            for record in records {
+                stmt.execute(params_from_iter(sql_values(schema, &types, record, &options.values)?))?;
+            }
+        }
+
+        for sql in create_index_sql(options) {
+            tx.execute(&sql, [])?;
+        }
+
+        tx.commit()?;
+        drop(conn);
+        Ok(report.finish(records.len(), 0, db_path))
+    }
+
+    /// 將 CSV 檔案轉換為可攜式的 SQL 腳本（CREATE TABLE 加上批次 INSERT）
+    pub fn convert_csv_to_sql_script(
+        csv_path: &str,
+        sql_path: &str,
+        options: &SqlExportOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let started = Instant::now();
+        let (headers, records) = Self::read_csv_file(csv_path)?;
+        Self::write_sql_script(&headers, &records, sql_path, options)?.with_input(csv_path, started)
+    }
+
+    /// 將已讀取的標題列與記錄寫成 SQL 腳本
+    pub fn write_sql_script(
+        headers: &[String],
+        records: &[StringRecord],
+        sql_path: &str,
+        options: &SqlExportOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let report = ReportBuilder::new(headers, records, &options.values);
+        let schema = report.schema();
+        check_indexes(schema, options)?;
+        let types = sql_types(schema, records, &options.values)?;
+
+        let mut out = BufWriter::new(File::create(sql_path)?);
+        let table = quote_ident(&options.table_name);
+        let columns = schema
+            .columns
+            .iter()
+            .map(|c| quote_ident(&c.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        writeln!(out, "BEGIN TRANSACTION;")?;
+        writeln!(out, "{};", create_table_sql(schema, &types, &options.table_name))?;
+
+        for batch in records.chunks(options.batch_size.max(1)) {
+            writeln!(out, "INSERT INTO {} ({}) VALUES", table, columns)?;
+            for (i, record) in batch.iter().enumerate() {
+                let row = sql_values(schema, &types, record, &options.values)?
+                    .iter()
+                    .map(sql_literal)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let end = if i + 1 == batch.len() { ";" } else { "," };
+                writeln!(out, "  ({}){}", row, end)?;
+            }
+        }
+
+        for sql in create_index_sql(options) {
+            writeln!(out, "{};", sql)?;
+        }
+        writeln!(out, "COMMIT;")?;
+
+        out.flush()?;
+        drop(out);
+        Ok(report.finish(records.len(), 0, sql_path))
+    }
+}
+
+/// 以雙引號包住 SQL 識別字，並跳脫內部的雙引號
+pub fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// 將值轉為 SQL 字面值，字串中的單引號會加倍跳脫
+pub fn sql_literal(value: &SqlValue) -> String {
+    match value {
+        SqlValue::Null => "NULL".to_string(),
+        SqlValue::Integer(n) => n.to_string(),
+        SqlValue::Real(f) => format!("{:?}", f),
+        SqlValue::Text(s) => format!("'{}'", s.replace('\'', "''")),
+        SqlValue::Blob(b) => {
+            let hex: String = b.iter().map(|byte| format!("{:02X}", byte)).collect();
+            format!("X'{}'", hex)
+        }
+    }
+}
+
+/// 依 schema 與各欄位的 SQL 型別產生 CREATE TABLE 敘述
+fn create_table_sql(schema: &Schema, types: &[&str], table_name: &str) -> String {
+    let columns = schema
+        .columns
+        .iter()
+        .zip(types)
+        .map(|(c, sql_type)| {
+            let not_null = if c.nullable { "" } else { " NOT NULL" };
+            format!("{} {}{}", quote_ident(&c.name), sql_type, not_null)
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!("CREATE TABLE {} ({})", quote_ident(table_name), columns)
+}
+
+/// 產生每個索引欄位的 CREATE INDEX 敘述
+fn create_index_sql(options: &SqlExportOptions) -> Vec<String> {
+    options
+        .indexes
+        .iter()
+        .map(|column| {
+            format!(
+                "CREATE INDEX {} ON {} ({})",
+                quote_ident(&format!("idx_{}_{}", options.table_name, column)),
+                quote_ident(&options.table_name),
+                quote_ident(column)
+            )
+        })
+        .collect()
+}
+
+/// 各欄位宣告的 SQL 型別；數值欄位含有需以文字保存的值時宣告為 TEXT，
+/// 否則 SQLite 的型別親和性會把這些文字再轉回浮點數
+fn sql_types(schema: &Schema, records: &[StringRecord], options: &ValueOptions) -> anyhow::Result<Vec<&'static str>> {
+    let inferred: Vec<&'static str> = schema.columns.iter().map(|c| c.column_type.sql_type()).collect();
+    let mut types = inferred.clone();
+    for record in records {
+        for (i, value) in sql_values(schema, &inferred, record, options)?.iter().enumerate() {
+            if matches!(value, SqlValue::Text(_)) && schema.columns[i].column_type != ColumnType::String {
+                types[i] = "TEXT";
+            }
+        }
+    }
+    Ok(types)
+}
+
+/// 確認索引欄位都存在於 schema 中
+fn check_indexes(schema: &Schema, options: &SqlExportOptions) -> anyhow::Result<()> {
+    for column in &options.indexes {
+        if schema.index_of(column).is_none() {
+            bail!("索引欄位不存在: {}", column);
+        }
+    }
+    Ok(())
+}
+
+/// 依欄位型別將一筆記錄轉為 SQL 值，空字串與 null 皆為 NULL；宣告為 TEXT 的數值欄位保留數字的原始字面
+fn sql_values(
+    schema: &Schema,
+    types: &[&str],
+    record: &StringRecord,
+    options: &ValueOptions,
+) -> anyhow::Result<Vec<SqlValue>> {
+    schema
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let field = record.get(i).unwrap_or("");
+            if field.is_empty() {
+                return Ok(SqlValue::Null);
+            }
+            let value = match (column.column_type, options.parse(&column.name, field)?) {
+                (_, Value::Null) => SqlValue::Null,
+                (ColumnType::String, _) => SqlValue::Text(field.to_string()),
+                (_, Value::Bool(b)) => SqlValue::Integer(b as i64),
+                (_, Value::Number(n)) if types[i] == "TEXT" => SqlValue::Text(n.to_string()),
+                (_, Value::Number(n)) => match (n.as_i64(), n.as_f64()) {
+                    (Some(i), _) if column.column_type == ColumnType::Integer => SqlValue::Integer(i),
+                    // 超出 i64 範圍的整數（保留原始字面時）以文字保存，避免損失精度
+                    (None, _) if n.to_string().trim_start_matches('-').bytes().all(|b| b.is_ascii_digit()) => {
+                        SqlValue::Text(n.to_string())
+                    }
+                    (_, Some(f)) if column.column_type == ColumnType::Float => SqlValue::Real(f),
+                    _ => SqlValue::Text(n.to_string()),
+                },
+                (_, Value::String(s)) => SqlValue::Text(s),
+                (_, other) => SqlValue::Text(other.to_string()),
+            };
+            Ok(value)
+        })
+        .collect()
+}
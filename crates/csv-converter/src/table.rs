@@ -0,0 +1,246 @@
+//! 將 CSV 資料渲染為 Markdown、HTML 或終端機表格
+
+use csv::StringRecord;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::converter::CsvConverter;
+use crate::schema::{ColumnType, Schema};
+use crate::value::ValueOptions;
+
+/// 表格渲染設定
+#[derive(Debug, Clone, Default)]
+pub struct TableOptions {
+    /// 只渲染前 N 列
+    pub max_rows: Option<usize>,
+    /// 儲存格最大顯示寬度，超過時截斷並加上 `…`
+    pub max_width: Option<usize>,
+    /// HTML 文件標題
+    pub title: Option<String>,
+    /// 用於推斷欄位型別（決定對齊方式）
+    pub values: ValueOptions,
+}
+
+/// 欄位對齊方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    Left,
+    Center,
+    Right,
+}
+
+impl Alignment {
+    /// 數字靠右、布林值置中、其餘靠左
+    pub fn for_type(column_type: ColumnType) -> Self {
+        match column_type {
+            ColumnType::Integer | ColumnType::Float => Alignment::Right,
+            ColumnType::Boolean => Alignment::Center,
+            ColumnType::String => Alignment::Left,
+        }
+    }
+
+    fn css(self) -> &'static str {
+        match self {
+            Alignment::Left => "left",
+            Alignment::Center => "center",
+            Alignment::Right => "right",
+        }
+    }
+}
+
+/// 準備好要渲染的表格：已截斷的儲存格與每欄的對齊方式
+struct PreparedTable {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    alignments: Vec<Alignment>,
+}
+
+impl PreparedTable {
+    /// `single_line` 為 true 時先將換行換成空白再截斷，截斷後的寬度才不會超過上限
+    fn new(headers: &[String], records: &[StringRecord], options: &TableOptions, single_line: bool) -> Self {
+        let records = &records[..options.max_rows.unwrap_or(records.len()).min(records.len())];
+        let schema = Schema::infer_with(headers, records, &options.values);
+        let truncate = |text: &str| {
+            let text = if single_line { text.replace(['\r', '\n'], " ") } else { text.to_string() };
+            match options.max_width {
+                Some(width) => truncate_to_width(&text, width),
+                None => text,
+            }
+        };
+
+        PreparedTable {
+            headers: headers.iter().map(|h| truncate(h)).collect(),
+            rows: records
+                .iter()
+                .map(|record| (0..headers.len()).map(|i| truncate(record.get(i).unwrap_or(""))).collect())
+                .collect(),
+            alignments: schema.columns.iter().map(|c| Alignment::for_type(c.column_type)).collect(),
+        }
+    }
+
+    /// 每一欄的最大顯示寬度
+    fn column_widths(&self) -> Vec<usize> {
+        self.headers
+            .iter()
+            .enumerate()
+            .map(|(i, header)| {
+                self.rows
+                    .iter()
+                    .map(|row| row[i].width())
+                    .chain(std::iter::once(header.width()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+}
+
+impl CsvConverter {
+    /// 渲染為 GitHub 風格的 Markdown 表格
+    pub fn render_markdown_table(headers: &[String], records: &[StringRecord], options: &TableOptions) -> String {
+        let table = PreparedTable::new(headers, records, options, false);
+        let row_line = |cells: &[String]| {
+            let cells: Vec<String> = cells.iter().map(|c| escape_markdown(c)).collect();
+            format!("| {} |\n", cells.join(" | "))
+        };
+
+        let mut out = row_line(&table.headers);
+        let separators: Vec<&str> = table
+            .alignments
+            .iter()
+            .map(|a| match a {
+                Alignment::Left => ":---",
+                Alignment::Center => ":---:",
+                Alignment::Right => "---:",
+            })
+            .collect();
+        out.push_str(&format!("| {} |\n", separators.join(" | ")));
+
+        for row in &table.rows {
+            out.push_str(&row_line(row));
+        }
+        out
+    }
+
+    /// 渲染為獨立的 HTML 文件，內容皆經過跳脫
+    pub fn render_html_table(headers: &[String], records: &[StringRecord], options: &TableOptions) -> String {
+        let table = PreparedTable::new(headers, records, options, false);
+        let title = escape_html(options.title.as_deref().unwrap_or("CSV"));
+
+        let mut out = String::new();
+        out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        out.push_str(&format!("<title>{}</title>\n", title));
+        out.push_str("<style>\ntable { border-collapse: collapse; font-family: sans-serif; }\n");
+        out.push_str("th, td { border: 1px solid #ccc; padding: 4px 8px; }\nth { background: #f4f4f4; }\n");
+        out.push_str("</style>\n</head>\n<body>\n<table>\n<thead>\n<tr>");
+        for (header, align) in table.headers.iter().zip(&table.alignments) {
+            out.push_str(&format!("<th style=\"text-align: {}\">{}</th>", align.css(), escape_html(header)));
+        }
+        out.push_str("</tr>\n</thead>\n<tbody>\n");
+
+        for row in &table.rows {
+            out.push_str("<tr>");
+            for (cell, align) in row.iter().zip(&table.alignments) {
+                out.push_str(&format!("<td style=\"text-align: {}\">{}</td>", align.css(), escape_html(cell)));
+            }
+            out.push_str("</tr>\n");
+        }
+
+        out.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+        out
+    }
+
+    /// 以 Unicode 框線字元渲染終端機表格（依顯示寬度對齊，支援全形字）
+    pub fn render_terminal_table(headers: &[String], records: &[StringRecord], options: &TableOptions) -> String {
+        // 換行字元會破壞框線，以空白取代
+        let table = PreparedTable::new(headers, records, options, true);
+        let widths = table.column_widths();
+
+        let border = |left: &str, middle: &str, right: &str| {
+            let segments: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+            format!("{}{}{}\n", left, segments.join(middle), right)
+        };
+        let row_line = |cells: &[String], alignments: Option<&[Alignment]>| {
+            let cells: Vec<String> = cells
+                .iter()
+                .enumerate()
+                .map(|(i, cell)| {
+                    let align = alignments.map_or(Alignment::Left, |a| a[i]);
+                    format!(" {} ", pad(cell, widths[i], align))
+                })
+                .collect();
+            format!("│{}│\n", cells.join("│"))
+        };
+
+        let mut out = border("┌", "┬", "┐");
+        out.push_str(&row_line(&table.headers, None));
+        out.push_str(&border("├", "┼", "┤"));
+        for row in &table.rows {
+            out.push_str(&row_line(row, Some(&table.alignments)));
+        }
+        out.push_str(&border("└", "┴", "┘"));
+        out
+    }
+}
+
+/// 依顯示寬度截斷文字，超過時以 `…` 結尾；寬度為 0 時連 `…` 也放不下，回傳空字串
+pub fn truncate_to_width(text: &str, max_width: usize) -> String {
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+
+    let mut result = String::new();
+    let mut width = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if width + w + 1 > max_width {
+            break;
+        }
+        result.push(c);
+        width += w;
+    }
+    result.push('…');
+    result
+}
+
+/// 依顯示寬度補齊空白
+fn pad(text: &str, width: usize, align: Alignment) -> String {
+    let space = width.saturating_sub(text.width());
+    match align {
+        Alignment::Left => format!("{}{}", text, " ".repeat(space)),
+        Alignment::Right => format!("{}{}", " ".repeat(space), text),
+        Alignment::Center => {
+            let left = space / 2;
+            format!("{}{}{}", " ".repeat(left), text, " ".repeat(space - left))
+        }
+    }
+}
+
+/// 跳脫 Markdown 表格中的 `|`、HTML 標籤、實體與換行
+fn escape_markdown(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+/// 跳脫 HTML 特殊字元
+pub fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,209 @@
+//! 以範本（Handlebars 語法）逐列或逐組輸出文字，例如設定檔片段或信件
+//!
+//! 範本的 context 為轉換後有型別的記錄，另外提供從 1 開始的列號 `_row`。
+//! 依欄位分組時，context 為分組欄位的值、筆數 `_count` 與該組所有記錄 `_rows`。
+
+use anyhow::{bail, Context};
+use csv::StringRecord;
+use handlebars::Handlebars;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path};
+
+use crate::converter::CsvConverter;
+use crate::report::{ConversionReport, ReportBuilder};
+use crate::value::ValueOptions;
+
+/// 每列 context 中的列號欄位
+pub const ROW_NUMBER_FIELD: &str = "_row";
+/// 分組 context 中的筆數欄位
+pub const GROUP_COUNT_FIELD: &str = "_count";
+/// 分組 context 中該組所有記錄的欄位
+pub const GROUP_ROWS_FIELD: &str = "_rows";
+
+const BODY_TEMPLATE: &str = "body";
+const NAME_TEMPLATE: &str = "name";
+
+/// 範本輸出設定
+#[derive(Debug, Clone, Default)]
+pub struct TemplateOptions {
+    /// 範本內容
+    pub template: String,
+    /// 輸出路徑；含有 `{{` 時視為檔名範本，每列（或每組）輸出一個檔案，例如 `out/{{id}}.yaml`，
+    /// 產生的檔名不可離開 `{{` 之前的資料夾；否則所有結果依序串接寫入同一個檔案
+    pub output: String,
+    /// 依這些欄位分組，每組輸出一次；空白時每列輸出一次
+    pub group_by: Vec<String>,
+    /// 引用不存在的欄位時回傳錯誤，而不是輸出空字串
+    pub strict: bool,
+    pub values: ValueOptions,
+}
+
+impl CsvConverter {
+    /// 依範本輸出每列或每組的文字；範本語法錯誤會在處理任何記錄前回報
+    pub fn render_template(
+        headers: &[String],
+        records: &[StringRecord],
+        options: &TemplateOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        let per_file = options.output.contains("{{");
+
+        let mut engine = Handlebars::new();
+        // 輸出的是設定檔與純文字，不做 HTML 跳脫
+        engine.register_escape_fn(handlebars::no_escape);
+        engine.set_strict_mode(options.strict);
+        engine
+            .register_template_string(BODY_TEMPLATE, &options.template)
+            .map_err(|e| anyhow::anyhow!("範本語法錯誤: {}", e))?;
+        if per_file {
+            engine
+                .register_template_string(NAME_TEMPLATE, &options.output)
+                .map_err(|e| anyhow::anyhow!("檔名範本語法錯誤: {}", e))?;
+        }
+
+        let report = ReportBuilder::new(headers, records, &options.values);
+        let contexts = if options.group_by.is_empty() {
+            row_contexts(headers, records, &options.values)?
+        } else {
+            group_contexts(headers, records, &options.group_by, &options.values)?
+        };
+
+        if !per_file {
+            let mut output = String::new();
+            for (i, context) in contexts.iter().enumerate() {
+                let rendered = engine
+                    .render(BODY_TEMPLATE, context)
+                    .with_context(|| format!("第 {} 筆無法套用範本", i + 1))?;
+                output.push_str(&rendered);
+            }
+            create_parent_dir(&options.output)?;
+            std::fs::write(&options.output, output)?;
+            return Ok(report.finish(records.len(), 0, &options.output));
+        }
+
+        // 先算出所有檔名，避免寫到一半才發現重複而覆蓋先前的輸出
+        let base_dir = template_base_dir(&options.output);
+        let mut names = Vec::with_capacity(contexts.len());
+        let mut seen = HashSet::new();
+        for (i, context) in contexts.iter().enumerate() {
+            let name = engine
+                .render(NAME_TEMPLATE, context)
+                .with_context(|| format!("第 {} 筆無法產生檔名", i + 1))?;
+            if name.trim().is_empty() || name.ends_with('/') {
+                bail!("第 {} 筆的檔名為空白: {}", i + 1, name);
+            }
+            if !stays_within(&name, base_dir) {
+                bail!("第 {} 筆的檔名 {} 離開了輸出資料夾 {}", i + 1, name, display_dir(base_dir));
+            }
+            if !seen.insert(name.clone()) {
+                bail!("多筆記錄產生相同的檔名 {}，請在檔名範本中加入可區分的欄位", name);
+            }
+            names.push(name);
+        }
+
+        // 同樣先套用所有範本，任何一筆失敗（例如 strict 模式缺少欄位）時不會留下部分檔案
+        let bodies = contexts
+            .iter()
+            .enumerate()
+            .map(|(i, context)| {
+                engine
+                    .render(BODY_TEMPLATE, context)
+                    .with_context(|| format!("第 {} 筆無法套用範本", i + 1))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let mut bytes_out = 0;
+        for (name, body) in names.iter().zip(&bodies) {
+            create_parent_dir(name)?;
+            std::fs::write(name, body).with_context(|| format!("無法寫入 {}", name))?;
+            bytes_out += body.len() as u64;
+        }
+        Ok(report.finish_with_bytes(records.len(), 0, 0, bytes_out))
+    }
+}
+
+/// 每列一個 context：有型別的記錄加上列號
+fn row_contexts(headers: &[String], records: &[StringRecord], options: &ValueOptions) -> anyhow::Result<Vec<Value>> {
+    let rows = CsvConverter::typed_records(headers, records, options)?;
+    Ok(rows
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut row)| {
+            row.entry(ROW_NUMBER_FIELD).or_insert_with(|| Value::from(i + 1));
+            Value::Object(row)
+        })
+        .collect())
+}
+
+/// 每組一個 context，分組順序為首次出現的順序
+fn group_contexts(
+    headers: &[String],
+    records: &[StringRecord],
+    group_by: &[String],
+    options: &ValueOptions,
+) -> anyhow::Result<Vec<Value>> {
+    let key_idx = group_by
+        .iter()
+        .map(|name| headers.iter().position(|h| h == name).with_context(|| format!("找不到欄位: {}", name)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    if let Some(name) = group_by.iter().find(|name| [GROUP_COUNT_FIELD, GROUP_ROWS_FIELD].contains(&name.as_str())) {
+        bail!("分組欄位 {} 與範本保留的名稱衝突，請先重新命名", name);
+    }
+
+    let rows = CsvConverter::typed_records(headers, records, options)?;
+    let mut groups: Vec<(Map<String, Value>, Vec<Value>)> = Vec::new();
+    let mut positions: HashMap<Vec<&str>, usize> = HashMap::new();
+    for (row_index, (record, row)) in records.iter().zip(rows).enumerate() {
+        let key: Vec<&str> = key_idx.iter().map(|&i| record.get(i).unwrap_or("")).collect();
+        let position = match positions.get(&key) {
+            Some(&position) => position,
+            None => {
+                let mut context = Map::new();
+                for (&column, field) in key_idx.iter().zip(&key) {
+                    context.insert(headers[column].clone(), options.parse_cell(row_index, column, &headers[column], field)?);
+                }
+                groups.push((context, Vec::new()));
+                positions.insert(key, groups.len() - 1);
+                groups.len() - 1
+            }
+        };
+        groups[position].1.push(Value::Object(row));
+    }
+
+    Ok(groups
+        .into_iter()
+        .map(|(mut context, rows)| {
+            context.insert(GROUP_COUNT_FIELD.to_string(), Value::from(rows.len()));
+            context.insert(GROUP_ROWS_FIELD.to_string(), Value::Array(rows));
+            Value::Object(context)
+        })
+        .collect())
+}
+
+/// 檔名範本中第一個 `{{` 之前的資料夾部分，例如 `out/{{id}}.yaml` 為 `out/`
+fn template_base_dir(template: &str) -> &str {
+    let literal = &template[..template.find("{{").unwrap_or(template.len())];
+    literal.rfind(['/', '\\']).map_or("", |end| &literal[..=end])
+}
+
+/// 檔名在資料夾之後只能是一般的相對路徑，不可含有 `..` 或絕對路徑
+fn stays_within(name: &str, base_dir: &str) -> bool {
+    name.strip_prefix(base_dir).is_some_and(|rest| {
+        Path::new(rest).components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    })
+}
+
+fn display_dir(base_dir: &str) -> &str {
+    if base_dir.is_empty() {
+        "."
+    } else {
+        base_dir
+    }
+}
+
+fn create_parent_dir(path: &str) -> anyhow::Result<()> {
+    if let Some(parent) = Path::new(path).parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).with_context(|| format!("無法建立資料夾 {}", parent.display()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,454 @@
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+use std::ops::Range;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// JavaScript 可安全表示的最大整數（2^53 - 1）
+pub const MAX_SAFE_INTEGER: i64 = 9_007_199_254_740_991;
+
+/// 數值轉換策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumericPolicy {
+    /// 先解析為 i64，失敗再解析為 f64（原本的行為，可能損失精度）
+    #[default]
+    Lossy,
+    /// 符合 JSON 數字格式的值保留原始字面（serde_json `arbitrary_precision`）
+    Preserve,
+    /// 超出 ±(2^53 - 1) 的整數轉為字串，避免 JavaScript 端損失精度
+    JsSafe,
+}
+
+impl FromStr for NumericPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lossy" => Ok(NumericPolicy::Lossy),
+            "preserve" => Ok(NumericPolicy::Preserve),
+            "js-safe" => Ok(NumericPolicy::JsSafe),
+            _ => bail!("未知的數值策略: {}（可用: lossy, preserve, js-safe）", s),
+        }
+    }
+}
+
+/// NaN、inf 等非有限浮點數的處理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFinitePolicy {
+    /// 保留為原始字串（原本的行為）
+    #[default]
+    String,
+    /// 轉為 null
+    Null,
+    /// 視為錯誤並中止轉換
+    Error,
+}
+
+impl FromStr for NonFinitePolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "string" => Ok(NonFinitePolicy::String),
+            "null" => Ok(NonFinitePolicy::Null),
+            "error" => Ok(NonFinitePolicy::Error),
+            _ => bail!("未知的非有限數處理方式: {}（可用: string, null, error）", s),
+        }
+    }
+}
+
+/// 數字的地區格式（千分位與小數點符號）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberLocale {
+    /// 只接受 Rust `parse` 可解析的格式（原本的行為）
+    #[default]
+    Plain,
+    /// 英語系：`1,234.56`
+    En,
+    /// 德語系：`1.234,56`
+    De,
+    /// 法語系：`1 234,56`（空白或不換行空白作為千分位）
+    Fr,
+    /// 瑞士：`1'234.56`（撇號作為千分位）
+    Ch,
+}
+
+impl NumberLocale {
+    /// 回傳 (千分位符號, 小數點符號)
+    pub(crate) fn separators(self) -> (&'static [char], char) {
+        match self {
+            NumberLocale::Plain => (&[], '.'),
+            NumberLocale::En => (&[','], '.'),
+            NumberLocale::De => (&['.'], ','),
+            NumberLocale::Fr => (&[' ', '\u{a0}', '\u{202f}'], ','),
+            NumberLocale::Ch => (&['\'', '\u{2019}'], '.'),
+        }
+    }
+}
+
+impl FromStr for NumberLocale {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(NumberLocale::Plain),
+            "en" => Ok(NumberLocale::En),
+            "de" => Ok(NumberLocale::De),
+            "fr" => Ok(NumberLocale::Fr),
+            "ch" => Ok(NumberLocale::Ch),
+            _ => bail!("未知的數字地區格式: {}（可用: plain, en, de, fr, ch）", s),
+        }
+    }
+}
+
+/// 解析欄位值時套用的規則，會記錄在推斷出的 schema 中
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParseRule {
+    ThousandsSeparator,
+    DecimalComma,
+    Currency,
+    Percent,
+    BooleanTokens,
+}
+
+impl ParseRule {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ParseRule::ThousandsSeparator => "thousands_separator",
+            ParseRule::DecimalComma => "decimal_comma",
+            ParseRule::Currency => "currency",
+            ParseRule::Percent => "percent",
+            ParseRule::BooleanTokens => "boolean_tokens",
+        }
+    }
+}
+
+impl std::fmt::Display for ParseRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 常見貨幣符號（較長的符號需排在前面）
+pub const DEFAULT_CURRENCY_SYMBOLS: &[&str] = &["US$", "NT$", "HK$", "$", "€", "£", "¥", "₩"];
+/// 擴充的真值字詞
+pub const EXTENDED_TRUE_TOKENS: &[&str] = &["true", "yes", "y", "t", "on", "是"];
+/// 擴充的假值字詞
+pub const EXTENDED_FALSE_TOKENS: &[&str] = &["false", "no", "n", "f", "off", "否"];
+
+/// 欄位值轉換設定
+#[derive(Debug, Clone)]
+pub struct ValueOptions {
+    pub numeric: NumericPolicy,
+    pub non_finite: NonFinitePolicy,
+    /// 需保持精確十進位的欄位（例如金額），一律保留原始字面
+    pub decimal_columns: Vec<String>,
+    /// 一律保留為字串的欄位，例如 SQL 查詢結果中的文字欄位
+    pub text_columns: Vec<String>,
+    pub locale: NumberLocale,
+    /// 數字前後可去除的貨幣符號
+    pub currency_symbols: Vec<String>,
+    /// 是否將 `45%` 轉為 0.45
+    pub percent: bool,
+    /// 視為 true 的字詞（不分大小寫）
+    pub true_tokens: Vec<String>,
+    /// 視為 false 的字詞（不分大小寫）
+    pub false_tokens: Vec<String>,
+    /// 空欄位轉為 null（預設保留為空字串）
+    pub empty_as_null: bool,
+    /// 不論內容一律轉為 null 的儲存格，例如合併檔案時來源檔案缺少的欄位
+    pub null_cells: NullCells,
+}
+
+impl Default for ValueOptions {
+    fn default() -> Self {
+        Self {
+            numeric: NumericPolicy::default(),
+            non_finite: NonFinitePolicy::default(),
+            decimal_columns: Vec::new(),
+            text_columns: Vec::new(),
+            locale: NumberLocale::default(),
+            currency_symbols: Vec::new(),
+            percent: false,
+            true_tokens: vec!["true".to_string()],
+            false_tokens: vec!["false".to_string()],
+            empty_as_null: false,
+            null_cells: NullCells::default(),
+        }
+    }
+}
+
+/// 以列號區段記錄的 null 儲存格；列號為記錄在傳入資料中的位置（從 0 開始）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NullCells {
+    blocks: Arc<Vec<(Range<usize>, Vec<usize>)>>,
+}
+
+impl NullCells {
+    /// 將 `rows` 區段內各列的 `columns` 欄位標記為 null；區段需依列號遞增的順序加入
+    pub fn push(&mut self, rows: Range<usize>, columns: Vec<usize>) {
+        if rows.is_empty() || columns.is_empty() {
+            return;
+        }
+        let blocks = Arc::make_mut(&mut self.blocks);
+        debug_assert!(blocks.last().is_none_or(|(last, _)| last.end <= rows.start));
+        match blocks.last_mut() {
+            Some((last, last_columns)) if last.end == rows.start && *last_columns == columns => last.end = rows.end,
+            _ => blocks.push((rows, columns)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    pub fn contains(&self, row: usize, column: usize) -> bool {
+        self.columns_at(row).is_some_and(|columns| columns.contains(&column))
+    }
+
+    /// 只保留指定的列（例如抽樣結果），依 `rows` 的順序重新編號
+    pub fn select(&self, rows: &[usize]) -> NullCells {
+        let mut selected = NullCells::default();
+        for (new_row, &row) in rows.iter().enumerate() {
+            if let Some(columns) = self.columns_at(row) {
+                selected.push(new_row..new_row + 1, columns.to_vec());
+            }
+        }
+        selected
+    }
+
+    /// 只保留指定的欄位（例如遮罩移除欄位後），依 `columns` 的順序重新編號
+    pub fn select_columns(&self, columns: &[usize]) -> NullCells {
+        let mut selected = NullCells::default();
+        for (rows, nulls) in self.blocks.iter() {
+            let kept = nulls.iter().filter_map(|column| columns.iter().position(|c| c == column)).collect();
+            selected.push(rows.clone(), kept);
+        }
+        selected
+    }
+
+    fn columns_at(&self, row: usize) -> Option<&[usize]> {
+        let after = self.blocks.partition_point(|(rows, _)| rows.start <= row);
+        let (rows, columns) = self.blocks.get(after.checked_sub(1)?)?;
+        rows.contains(&row).then_some(columns.as_slice())
+    }
+}
+
+impl ValueOptions {
+    /// 指定地區格式，並啟用常見貨幣符號、百分比與擴充布林字詞
+    pub fn localized(locale: NumberLocale) -> Self {
+        Self {
+            locale,
+            currency_symbols: DEFAULT_CURRENCY_SYMBOLS.iter().map(|s| s.to_string()).collect(),
+            percent: true,
+            true_tokens: EXTENDED_TRUE_TOKENS.iter().map(|s| s.to_string()).collect(),
+            false_tokens: EXTENDED_FALSE_TOKENS.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// 將 CSV 欄位值轉換為 JSON 值（數字、布林值，其餘為字串）
+    pub fn parse(&self, column: &str, field: &str) -> anyhow::Result<Value> {
+        self.parse_with_rules(column, field).map(|(value, _)| value)
+    }
+
+    /// 轉換第 `row` 列第 `column` 欄的值；位於 [`ValueOptions::null_cells`] 的儲存格為 null
+    pub fn parse_cell(&self, row: usize, column: usize, header: &str, field: &str) -> anyhow::Result<Value> {
+        if self.null_cells.contains(row, column) {
+            return Ok(Value::Null);
+        }
+        self.parse(header, field)
+    }
+
+    /// 轉換欄位值，並回傳轉換時套用的規則
+    pub fn parse_with_rules(&self, column: &str, field: &str) -> anyhow::Result<(Value, Vec<ParseRule>)> {
+        if self.empty_as_null && field.is_empty() {
+            return Ok((Value::Null, Vec::new()));
+        }
+
+        if self.text_columns.iter().any(|c| c == column) {
+            return Ok((Value::String(field.to_string()), Vec::new()));
+        }
+
+        if let Some((text, rules)) = self.normalize_number(field) {
+            if let Some(value) = self.parse_number(column, &text, field)? {
+                return Ok((value, rules));
+            }
+        }
+
+        if let Some(value) = self.parse_number(column, field, field)? {
+            return Ok((value, Vec::new()));
+        }
+
+        if let Some((value, default_token)) = self.parse_bool(field) {
+            let rules = if default_token { Vec::new() } else { vec![ParseRule::BooleanTokens] };
+            return Ok((Value::Bool(value), rules));
+        }
+
+        Ok((Value::String(field.to_string()), Vec::new()))
+    }
+
+    /// 解析已正規化的數字字串；`original` 用於需要保留原始值的情況
+    fn parse_number(&self, column: &str, text: &str, original: &str) -> anyhow::Result<Option<Value>> {
+        let exact = self.numeric == NumericPolicy::Preserve
+            || self.decimal_columns.iter().any(|c| c == column);
+
+        // 保留原始字面，例如 "0.10" 不會變成 0.1
+        if exact {
+            if let Ok(n) = Number::from_str(text) {
+                return Ok(Some(Value::Number(n)));
+            }
+        }
+
+        if let Ok(num) = text.parse::<i64>() {
+            if self.numeric == NumericPolicy::JsSafe && num.unsigned_abs() > MAX_SAFE_INTEGER as u64 {
+                return Ok(Some(Value::String(original.to_string())));
+            }
+            return Ok(Some(Value::Number(num.into())));
+        }
+
+        // 超出 i64 範圍的整數無法以 f64 精確表示
+        if self.numeric == NumericPolicy::JsSafe && is_integer_literal(text) {
+            return Ok(Some(Value::String(original.to_string())));
+        }
+
+        if let Ok(num) = text.parse::<f64>() {
+            return match Number::from_f64(num) {
+                Some(n) => Ok(Some(Value::Number(n))),
+                None => match self.non_finite {
+                    NonFinitePolicy::String => Ok(Some(Value::String(original.to_string()))),
+                    NonFinitePolicy::Null => Ok(Some(Value::Null)),
+                    NonFinitePolicy::Error => bail!("欄位 {} 含有非有限數值: {}", column, original),
+                },
+            };
+        }
+
+        Ok(None)
+    }
+
+    /// 依字詞集合解析布林值，第二個值表示是否為預設的 true/false 字詞
+    fn parse_bool(&self, field: &str) -> Option<(bool, bool)> {
+        let lower = field.to_lowercase();
+        let is_default = lower == "true" || lower == "false";
+
+        if self.true_tokens.iter().any(|t| t.to_lowercase() == lower) {
+            Some((true, is_default))
+        } else if self.false_tokens.iter().any(|t| t.to_lowercase() == lower) {
+            Some((false, is_default))
+        } else {
+            None
+        }
+    }
+
+    /// 去除貨幣符號、百分比與地區分隔符號，轉為標準數字字串；未套用任何規則時回傳 None
+    fn normalize_number(&self, field: &str) -> Option<(String, Vec<ParseRule>)> {
+        let mut rules = Vec::new();
+        let mut s = field.trim();
+
+        let mut negative = false;
+        if let Some(rest) = s.strip_prefix('-') {
+            negative = true;
+            s = rest.trim_start();
+        }
+
+        for symbol in &self.currency_symbols {
+            if let Some(rest) = s.strip_prefix(symbol.as_str()) {
+                s = rest.trim_start();
+            } else if let Some(rest) = s.strip_suffix(symbol.as_str()) {
+                s = rest.trim_end();
+            } else {
+                continue;
+            }
+            rules.push(ParseRule::Currency);
+            break;
+        }
+
+        // 例如 "$-1,200"
+        if !negative {
+            if let Some(rest) = s.strip_prefix('-') {
+                negative = true;
+                s = rest;
+            }
+        }
+
+        let mut percent = false;
+        if self.percent {
+            if let Some(rest) = s.strip_suffix('%') {
+                s = rest.trim_end();
+                percent = true;
+                rules.push(ParseRule::Percent);
+            }
+        }
+
+        let (thousands, decimal) = self.locale.separators();
+        let (int_part, frac_part) = match s.split_once(decimal) {
+            Some((int_part, frac_part)) => {
+                if frac_part.is_empty() || !frac_part.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                if decimal != '.' {
+                    rules.push(ParseRule::DecimalComma);
+                }
+                (int_part, frac_part)
+            }
+            None => (s, ""),
+        };
+
+        let mut digits = String::new();
+        if int_part.contains(thousands) {
+            // 千分位分組：第一組 1 到 3 位數，其餘每組 3 位數
+            let groups: Vec<&str> = int_part.split(thousands).collect();
+            let valid = groups.iter().all(|g| g.bytes().all(|b| b.is_ascii_digit()))
+                && (1..=3).contains(&groups[0].len())
+                && groups[1..].iter().all(|g| g.len() == 3);
+            if !valid {
+                return None;
+            }
+            groups.iter().for_each(|g| digits.push_str(g));
+            rules.push(ParseRule::ThousandsSeparator);
+        } else {
+            if !int_part.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            digits.push_str(int_part);
+        }
+
+        if rules.is_empty() || (digits.is_empty() && frac_part.is_empty()) {
+            return None;
+        }
+
+        // 百分比以移動小數點的方式換算，避免浮點誤差
+        let (int_digits, frac_digits) = if percent {
+            let all = format!("{}{}", digits, frac_part);
+            let point = digits.len() as isize - 2;
+            if point <= 0 {
+                (String::new(), format!("{}{}", "0".repeat((-point) as usize), all))
+            } else {
+                (all[..point as usize].to_string(), all[point as usize..].to_string())
+            }
+        } else {
+            (digits, frac_part.to_string())
+        };
+
+        let int_digits = int_digits.trim_start_matches('0');
+        let mut text = String::new();
+        if negative {
+            text.push('-');
+        }
+        text.push_str(if int_digits.is_empty() { "0" } else { int_digits });
+        if !frac_digits.is_empty() {
+            text.push('.');
+            text.push_str(&frac_digits);
+        }
+
+        Some((text, rules))
+    }
+}
+
+/// 判斷字串是否為（可帶正負號的）整數字面
+pub(crate) fn is_integer_literal(field: &str) -> bool {
+    let digits = field.strip_prefix(['+', '-']).unwrap_or(field);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
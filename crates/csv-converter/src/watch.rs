@@ -0,0 +1,427 @@
+//! 監看資料夾：自動轉換新放入或修改過的 CSV 檔
+//!
+//! 檔案大小與修改時間在一段時間內沒有變化才視為寫入完成，轉換後原始檔移到 `processed/`，
+//! 失敗時移到 `failed/`，每個檔案在 `watch.log` 寫一行紀錄。
+
+use anyhow::{bail, Context};
+use csv::StringRecord;
+use notify::{RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::avro::AvroOptions;
+use crate::converter::CsvConverter;
+use crate::formats::{TomlOptions, XmlOptions, YamlOptions};
+use crate::progress::CancellationToken;
+use crate::report::ConversionReport;
+use crate::spreadsheet::XlsxExportOptions;
+use crate::sql::SqlExportOptions;
+use crate::value::ValueOptions;
+
+/// 資料夾設定檔名稱，以 `.` 開頭因此不會被當成待轉換的檔案
+pub const WATCH_CONFIG_FILE: &str = ".watch.csv";
+/// 每個檔案一行的處理紀錄
+pub const WATCH_LOG_FILE: &str = "watch.log";
+pub const PROCESSED_DIR: &str = "processed";
+pub const FAILED_DIR: &str = "failed";
+
+/// 自動轉換的輸出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WatchFormat {
+    #[default]
+    Json,
+    Yaml,
+    Toml,
+    Xml,
+    Xlsx,
+    Sqlite,
+    Sql,
+    Avro,
+}
+
+impl WatchFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            WatchFormat::Json => "json",
+            WatchFormat::Yaml => "yaml",
+            WatchFormat::Toml => "toml",
+            WatchFormat::Xml => "xml",
+            WatchFormat::Xlsx => "xlsx",
+            WatchFormat::Sqlite => "db",
+            WatchFormat::Sql => "sql",
+            WatchFormat::Avro => "avro",
+        }
+    }
+
+    /// 先寫到 `.partial` 暫存檔，成功後才取代輸出檔，轉換失敗時保留先前的輸出
+    fn convert(&self, csv_path: &str, output_path: &str, values: &ValueOptions) -> anyhow::Result<ConversionReport> {
+        let (headers, records) = CsvConverter::read_csv_file(csv_path)?;
+        CsvConverter::write_atomically(output_path, &CancellationToken::new(), |partial_path| {
+            self.write(&headers, &records, partial_path, values.clone())
+        })
+    }
+
+    /// 依格式將記錄寫到 `output_path`
+    fn write(
+        &self,
+        headers: &[String],
+        records: &[StringRecord],
+        output_path: &str,
+        values: ValueOptions,
+    ) -> anyhow::Result<ConversionReport> {
+        match self {
+            WatchFormat::Json => CsvConverter::write_json_file(headers, records, output_path, &values),
+            WatchFormat::Yaml => {
+                let options = YamlOptions { values, ..Default::default() };
+                CsvConverter::write_yaml_file(headers, records, output_path, &options)
+            }
+            WatchFormat::Toml => {
+                let options = TomlOptions { values, ..Default::default() };
+                CsvConverter::write_toml_file(headers, records, output_path, &options)
+            }
+            WatchFormat::Xml => {
+                let options = XmlOptions { values, ..Default::default() };
+                CsvConverter::write_xml_file(headers, records, output_path, &options)
+            }
+            WatchFormat::Xlsx => {
+                let options = XlsxExportOptions { values, ..Default::default() };
+                CsvConverter::write_xlsx_file(headers, records, output_path, &options)
+            }
+            WatchFormat::Sqlite => {
+                // 清除上次中斷留下的暫存檔，改名時會取代既有的資料庫
+                let _ = std::fs::remove_file(output_path);
+                let options = SqlExportOptions { values, ..Default::default() };
+                CsvConverter::write_sqlite_file(headers, records, output_path, &options)
+            }
+            WatchFormat::Sql => {
+                let options = SqlExportOptions { values, ..Default::default() };
+                CsvConverter::write_sql_script(headers, records, output_path, &options)
+            }
+            WatchFormat::Avro => {
+                let options = AvroOptions { values, ..Default::default() };
+                CsvConverter::write_avro_file(headers, records, output_path, &options)
+            }
+        }
+    }
+}
+
+impl FromStr for WatchFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(WatchFormat::Json),
+            "yaml" => Ok(WatchFormat::Yaml),
+            "toml" => Ok(WatchFormat::Toml),
+            "xml" => Ok(WatchFormat::Xml),
+            "xlsx" => Ok(WatchFormat::Xlsx),
+            "sqlite" => Ok(WatchFormat::Sqlite),
+            "sql" => Ok(WatchFormat::Sql),
+            "avro" => Ok(WatchFormat::Avro),
+            _ => bail!("未知的輸出格式: {}（可用: json, yaml, toml, xml, xlsx, sqlite, sql, avro）", s),
+        }
+    }
+}
+
+/// 資料夾的轉換設定
+#[derive(Debug, Clone)]
+pub struct WatchConfig {
+    pub format: WatchFormat,
+    /// 輸出資料夾，相對路徑以監看的資料夾為基準
+    pub output_dir: String,
+    /// 檔案多久沒有變化才視為寫入完成
+    pub stable_for: Duration,
+    pub values: ValueOptions,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            format: WatchFormat::default(),
+            output_dir: "converted".to_string(),
+            stable_for: Duration::from_secs(5),
+            values: ValueOptions::default(),
+        }
+    }
+}
+
+/// 將秒數轉為穩定等待時間，負數、NaN 與無限大視為錯誤
+pub fn stable_duration(seconds: f64) -> anyhow::Result<Duration> {
+    Duration::try_from_secs_f64(seconds).map_err(|_| anyhow::anyhow!("等待秒數必須是有限的非負數: {}", seconds))
+}
+
+impl WatchConfig {
+    /// 從 CSV 格式的設定檔讀取，欄位為 `setting,value`
+    ///
+    /// 可用的設定：`format`、`output_dir`、`stable_seconds`、`empty_as_null`、`decimal`（可重複）
+    pub fn from_csv_file(path: &str) -> anyhow::Result<Self> {
+        let mut config = WatchConfig::default();
+        config.merge_csv_file(path)?;
+        Ok(config)
+    }
+
+    /// 將設定檔中列出的設定套用到目前的設定上，未列出的保持不變；
+    /// 回傳被設定檔改成不同值的設定名稱
+    pub fn merge_csv_file(&mut self, path: &str) -> anyhow::Result<Vec<String>> {
+        let (headers, records) =
+            CsvConverter::read_csv_file(path).with_context(|| format!("無法讀取監看設定檔: {}", path))?;
+
+        let position = |name: &str| headers.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+        let (Some(setting_idx), Some(value_idx)) = (position("setting"), position("value")) else {
+            bail!("監看設定檔必須包含 setting、value 欄位: {}", path);
+        };
+
+        // 先全部解析成功才套用，設定檔有錯時不會只改一半
+        let mut merged = self.clone();
+        let mut changed = Vec::new();
+        for record in &records {
+            let setting = record.get(setting_idx).unwrap_or("").trim().to_ascii_lowercase();
+            let value = record.get(value_idx).unwrap_or("").trim();
+            let differs = match setting.as_str() {
+                "format" => replace(&mut merged.format, value.parse()?),
+                "output_dir" => replace(&mut merged.output_dir, value.to_string()),
+                "stable_seconds" => {
+                    let seconds: f64 = value.parse().with_context(|| format!("無效的 stable_seconds: {}", value))?;
+                    let stable_for = stable_duration(seconds).with_context(|| format!("無效的 stable_seconds: {}", value))?;
+                    replace(&mut merged.stable_for, stable_for)
+                }
+                "empty_as_null" => replace(&mut merged.values.empty_as_null, value.eq_ignore_ascii_case("true")),
+                // 與旗標指定的欄位合併
+                "decimal" => {
+                    if !merged.values.decimal_columns.iter().any(|c| c == value) {
+                        merged.values.decimal_columns.push(value.to_string());
+                    }
+                    false
+                }
+                other => bail!("未知的監看設定: {}（可用: format, output_dir, stable_seconds, empty_as_null, decimal）", other),
+            };
+            if differs && !changed.contains(&setting) {
+                changed.push(setting);
+            }
+        }
+        *self = merged;
+        Ok(changed)
+    }
+}
+
+/// 寫入新值並回傳是否與原值不同
+fn replace<T: PartialEq>(slot: &mut T, value: T) -> bool {
+    let differs = *slot != value;
+    *slot = value;
+    differs
+}
+
+/// 一個檔案的處理結果
+#[derive(Debug)]
+pub struct WatchOutcome {
+    /// 原始檔名
+    pub file: String,
+    /// 成功時為輸出檔路徑與轉換報告，失敗時為錯誤訊息
+    pub result: Result<(PathBuf, ConversionReport), String>,
+    /// 原始檔移動後的路徑；無法移動時為原路徑
+    pub moved_to: PathBuf,
+}
+
+/// 監看單一資料夾（不含子資料夾）
+pub struct DirectoryWatcher {
+    dir: PathBuf,
+    config: WatchConfig,
+    /// 尚未穩定的檔案：上次看到的大小、修改時間，以及從何時開始沒有變化
+    pending: HashMap<PathBuf, (u64, SystemTime, Instant)>,
+    /// 處理後無法移走的檔案與當時的大小、修改時間，內容改變前不再重試
+    stuck: HashMap<PathBuf, (u64, SystemTime)>,
+    /// 被資料夾設定檔改掉的設定
+    overridden: Vec<String>,
+}
+
+impl DirectoryWatcher {
+    /// 建立監看器；資料夾中有 [`WATCH_CONFIG_FILE`] 時，其中列出的設定優先於傳入的設定
+    pub fn new(dir: &str, mut config: WatchConfig) -> anyhow::Result<Self> {
+        let dir = PathBuf::from(dir);
+        if !dir.is_dir() {
+            bail!("{} 不是資料夾", dir.display());
+        }
+        let config_path = dir.join(WATCH_CONFIG_FILE);
+        let overridden = if config_path.is_file() {
+            config.merge_csv_file(&config_path.to_string_lossy())?
+        } else {
+            Vec::new()
+        };
+        Ok(Self { dir, config, pending: HashMap::new(), stuck: HashMap::new(), overridden })
+    }
+
+    pub fn config(&self) -> &WatchConfig {
+        &self.config
+    }
+
+    /// 被 [`WATCH_CONFIG_FILE`] 改成與傳入設定不同值的設定名稱
+    pub fn overridden_settings(&self) -> &[String] {
+        &self.overridden
+    }
+
+    /// 掃描一次資料夾，轉換已穩定的檔案並回傳處理結果
+    pub fn scan(&mut self) -> anyhow::Result<Vec<WatchOutcome>> {
+        let now = Instant::now();
+        let mut seen = Vec::new();
+        let mut ready = Vec::new();
+
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if !is_candidate(&path) {
+                continue;
+            }
+            // 檔案可能在掃描期間被移走
+            let Ok(metadata) = std::fs::metadata(&path) else {
+                continue;
+            };
+            let size = metadata.len();
+            let Ok(modified) = metadata.modified() else {
+                continue;
+            };
+            seen.push(path.clone());
+            if self.stuck.get(&path) == Some(&(size, modified)) {
+                continue;
+            }
+            self.stuck.remove(&path);
+
+            match self.pending.get(&path) {
+                Some(&(last_size, last_modified, since)) if last_size == size && last_modified == modified => {
+                    if now.duration_since(since) >= self.config.stable_for {
+                        ready.push(path);
+                    }
+                }
+                _ => {
+                    self.pending.insert(path.clone(), (size, modified, now));
+                    if self.config.stable_for.is_zero() {
+                        ready.push(path);
+                    }
+                }
+            }
+        }
+        self.pending.retain(|path, _| seen.contains(path));
+        self.stuck.retain(|path, _| seen.contains(path));
+
+        let mut outcomes = Vec::with_capacity(ready.len());
+        ready.sort();
+        for path in ready {
+            self.pending.remove(&path);
+            // 單一檔案失敗只記錄在結果中，不影響其他檔案
+            let outcome = self.process(&path);
+            if outcome.moved_to == path {
+                if let Ok(state) = std::fs::metadata(&path).and_then(|m| Ok((m.len(), m.modified()?))) {
+                    self.stuck.insert(path, state);
+                }
+            }
+            self.log(&outcome)?;
+            outcomes.push(outcome);
+        }
+        Ok(outcomes)
+    }
+
+    /// 持續監看直到取消；每處理完一個檔案呼叫 `on_outcome`
+    pub fn run(&mut self, cancel: &CancellationToken, mut on_outcome: impl FnMut(&WatchOutcome)) -> anyhow::Result<()> {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        watcher.watch(&self.dir, RecursiveMode::NonRecursive)?;
+
+        // 檔案事件會立即觸發掃描，逾時則用來檢查檔案是否已穩定
+        let interval = (self.config.stable_for / 2).clamp(Duration::from_millis(100), Duration::from_secs(1));
+        while !cancel.is_cancelled() {
+            for outcome in self.scan()? {
+                on_outcome(&outcome);
+            }
+            match rx.recv_timeout(interval) {
+                Ok(event) => {
+                    event?;
+                    // 一次寫入常產生多個事件，合併為一次掃描
+                    while rx.try_recv().is_ok() {}
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => bail!("資料夾監看已中斷"),
+            }
+        }
+        Ok(())
+    }
+
+    fn process(&self, path: &Path) -> WatchOutcome {
+        let file = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+
+        let output_dir = self.dir.join(&self.config.output_dir);
+        let output = output_dir.join(format!("{}.{}", stem, self.config.format.extension()));
+        let mut result = std::fs::create_dir_all(&output_dir)
+            .with_context(|| format!("無法建立輸出資料夾 {}", output_dir.display()))
+            .and_then(|_| {
+                self.config.format.convert(&path.to_string_lossy(), &output.to_string_lossy(), &self.config.values)
+            })
+            .map(|report| (output, report))
+            .map_err(|e| format!("{:#}", e));
+
+        let target_dir = self.dir.join(if result.is_ok() { PROCESSED_DIR } else { FAILED_DIR });
+        let moved = std::fs::create_dir_all(&target_dir).and_then(|_| {
+            let moved_to = unique_path(&target_dir, &file);
+            std::fs::rename(path, &moved_to).map(|_| moved_to)
+        });
+        let moved_to = match moved {
+            Ok(moved_to) => moved_to,
+            Err(e) => {
+                let error = format!("無法移動 {} 到 {}: {}", file, target_dir.display(), e);
+                result = Err(match result {
+                    Ok(_) => error,
+                    Err(previous) => format!("{}；{}", previous, error),
+                });
+                path.to_path_buf()
+            }
+        };
+
+        WatchOutcome { file, result, moved_to }
+    }
+
+    fn log(&self, outcome: &WatchOutcome) -> anyhow::Result<()> {
+        let mut log = OpenOptions::new().create(true).append(true).open(self.dir.join(WATCH_LOG_FILE))?;
+        writeln!(log, "{}", outcome.log_line())?;
+        Ok(())
+    }
+}
+
+impl WatchOutcome {
+    /// 以 tab 分隔的紀錄：時間、狀態、檔名、輸出檔或錯誤
+    pub fn log_line(&self) -> String {
+        let time = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%:z");
+        match &self.result {
+            Ok((output, report)) => format!(
+                "{}\tprocessed\t{}\t{}\t{} rows\t{} ms",
+                time,
+                self.file,
+                output.display(),
+                report.rows_written,
+                report.elapsed_ms
+            ),
+            Err(error) => format!("{}\tfailed\t{}\t{}", time, self.file, error.replace(['\t', '\n'], " ")),
+        }
+    }
+}
+
+/// 資料夾中直接放置、非隱藏的 .csv 檔
+fn is_candidate(path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.is_file()
+        && !name.starts_with('.')
+        && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+}
+
+/// 目標資料夾已有同名檔案時加上時間戳記
+fn unique_path(dir: &Path, file: &str) -> PathBuf {
+    let path = dir.join(file);
+    if !path.exists() {
+        return path;
+    }
+    let (stem, extension) = file.rsplit_once('.').unwrap_or((file, ""));
+    let stamp = chrono::Local::now().format("%Y%m%dT%H%M%S%3f");
+    dir.join(format!("{}.{}.{}", stem, stamp, extension))
+}
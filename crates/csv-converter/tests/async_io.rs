@@ -0,0 +1,87 @@
+#![cfg(feature = "async")]
+
+use csv_converter::{AsyncFormat, AsyncOptions, CsvConverter, ValueOptions};
+use serde_json::Value;
+
+async fn convert(input: &[u8], options: &AsyncOptions) -> anyhow::Result<String> {
+    let mut output = Vec::new();
+    CsvConverter::convert_csv_async(input, &mut output, options).await?;
+    Ok(String::from_utf8(output).unwrap())
+}
+
+/// 同步路徑的結果，每列一個 JSON 物件
+fn sync_rows(input: &[u8]) -> Vec<Value> {
+    let mut reader = csv::Reader::from_reader(input);
+    let headers: Vec<String> = reader.headers().unwrap().iter().map(|h| h.to_string()).collect();
+    let records: Vec<csv::StringRecord> = reader.records().collect::<Result<_, _>>().unwrap();
+    let rows = CsvConverter::typed_records(&headers, &records, &ValueOptions::default()).unwrap();
+    rows.into_iter().map(Value::Object).collect()
+}
+
+fn ndjson_rows(output: &str) -> Vec<Value> {
+    output.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+}
+
+#[tokio::test]
+async fn one_byte_buffer_splits_every_record() {
+    let input = b"id,name,note\n1,Ann,\"a, \"\"b\"\"\"\r\n2,\xe8\x87\xba\xe5\x8c\x97,\"x\ny\"\n3,Cy,\n";
+    let options = AsyncOptions { buffer_size: 1, ..Default::default() };
+    let output = convert(input, &options).await.unwrap();
+    assert_eq!(ndjson_rows(&output), sync_rows(input));
+
+    let options = AsyncOptions { buffer_size: 1, format: AsyncFormat::Json, ..Default::default() };
+    let output: Value = serde_json::from_str(&convert(input, &options).await.unwrap()).unwrap();
+    assert_eq!(output, Value::Array(sync_rows(input)));
+}
+
+#[tokio::test]
+async fn grows_buffers_for_long_fields_and_many_columns() {
+    let long = "x".repeat(5000);
+    let headers: Vec<String> = (0..100).map(|i| format!("c{}", i)).collect();
+    let row: Vec<String> = (0..100).map(|i| if i == 50 { long.clone() } else { i.to_string() }).collect();
+    let input = format!("{}\n{}\n{}\n", headers.join(","), row.join(","), row.join(","));
+
+    for buffer_size in [7, 64 * 1024] {
+        let options = AsyncOptions { buffer_size, ..Default::default() };
+        let output = convert(input.as_bytes(), &options).await.unwrap();
+        let rows = ndjson_rows(&output);
+        assert_eq!(rows, sync_rows(input.as_bytes()));
+        assert_eq!(rows[1]["c50"].as_str().unwrap().len(), 5000);
+        assert_eq!(rows[1]["c99"], 99);
+    }
+}
+
+#[tokio::test]
+async fn width_mismatch_reports_the_record_line() {
+    // 第 3 行的記錄含有換行，錯誤的是從第 5 行開始的記錄
+    let input = b"id,note\n1,a\n2,\"x\ny\"\n3\n4,d\n";
+    for buffer_size in [1, 1024] {
+        let options = AsyncOptions { buffer_size, ..Default::default() };
+        let error = convert(input, &options).await.unwrap_err().to_string();
+        assert!(error.contains("第 5 行有 1 個欄位，標題列有 2 個"), "{}", error);
+    }
+
+    let error = convert(b"id,note\n1,a\n\n2,b,c\n", &AsyncOptions::default()).await.unwrap_err().to_string();
+    assert!(error.contains("第 4 行"), "{}", error);
+}
+
+#[tokio::test]
+async fn strips_a_leading_byte_order_mark() {
+    let input = b"\xef\xbb\xbfid,\"name\"\n1,Ann\n";
+    for buffer_size in [1, 2, 1024] {
+        let options = AsyncOptions { buffer_size, ..Default::default() };
+        let output = convert(input, &options).await.unwrap();
+        assert_eq!(ndjson_rows(&output), sync_rows(input));
+        assert_eq!(output, "{\"id\":1,\"name\":\"Ann\"}\n");
+    }
+
+    // 只有開頭的 BOM 會被略過
+    let output = convert(b"id,name\n1,\xef\xbb\xbfAnn\n", &AsyncOptions::default()).await.unwrap();
+    assert_eq!(output, "{\"id\":1,\"name\":\"\u{feff}Ann\"}\n");
+    let report = {
+        let mut output = Vec::new();
+        CsvConverter::convert_csv_async(&b"\xef\xbb\xbfid\n1\n"[..], &mut output, &AsyncOptions::default()).await.unwrap()
+    };
+    assert_eq!(report.bytes_in, 8);
+    assert_eq!(report.rows_read, 1);
+}
@@ -0,0 +1,198 @@
+use avro_schema::file::{Block, CompressedBlock, Compression};
+use avro_schema::read::fallible_streaming_iterator::FallibleStreamingIterator;
+use avro_schema::schema::{Field, Record, Schema};
+use csv::StringRecord;
+use csv_converter::{AvroCodec, AvroOptions, CsvConverter, NonFinitePolicy, ValueOptions};
+use std::fs::File;
+use std::io::BufReader;
+
+const CSV: &str = "\
+id,name,score,active,ratio
+1,Ann,9.5,true,inf
+2,,,false,0.5
+3,\"Bob, Jr.\",-7.25,,1.5
+";
+
+const CODECS: [AvroCodec; 3] = [AvroCodec::Null, AvroCodec::Deflate, AvroCodec::Snappy];
+
+fn table() -> (Vec<String>, Vec<StringRecord>) {
+    let mut reader = csv::Reader::from_reader(CSV.as_bytes());
+    let headers = reader.headers().unwrap().iter().map(|h| h.to_string()).collect();
+    (headers, reader.records().collect::<Result<_, _>>().unwrap())
+}
+
+fn options(codec: AvroCodec) -> AvroOptions {
+    AvroOptions {
+        codec,
+        block_size: 2,
+        values: ValueOptions { non_finite: NonFinitePolicy::Null, ..Default::default() },
+        ..Default::default()
+    }
+}
+
+fn rows(records: &[StringRecord]) -> Vec<Vec<&str>> {
+    records.iter().map(|r| r.iter().collect()).collect()
+}
+
+#[test]
+fn round_trips_with_every_codec() {
+    let dir = tempfile::tempdir().unwrap();
+    let (headers, records) = table();
+    for codec in CODECS {
+        let path = dir.path().join(format!("{}.avro", codec.as_str())).to_string_lossy().into_owned();
+        CsvConverter::write_avro_file(&headers, &records, &path, &options(codec)).unwrap();
+
+        let (read_headers, read_records) = CsvConverter::read_avro_file(&path).unwrap();
+        assert_eq!(read_headers, headers);
+        // inf 依 null 策略寫成 null，讀回為空字串
+        assert_eq!(
+            rows(&read_records),
+            [
+                vec!["1", "Ann", "9.5", "true", ""],
+                vec!["2", "", "", "false", "0.5"],
+                vec!["3", "Bob, Jr.", "-7.25", "", "1.5"],
+            ],
+            "{}",
+            codec.as_str()
+        );
+    }
+}
+
+/// 以 avro-schema 讀取本模組寫出的檔案，依規格逐欄解碼
+#[test]
+fn output_is_readable_by_another_implementation() {
+    let dir = tempfile::tempdir().unwrap();
+    let (headers, records) = table();
+    for codec in CODECS {
+        let path = dir.path().join(format!("{}.avro", codec.as_str()));
+        CsvConverter::write_avro_file(&headers, &records, &path.to_string_lossy(), &options(codec)).unwrap();
+
+        let file = &mut BufReader::new(File::open(&path).unwrap());
+        let metadata = avro_schema::read::read_metadata(file).unwrap();
+        let expected = match codec {
+            AvroCodec::Null => None,
+            AvroCodec::Deflate => Some(Compression::Deflate),
+            AvroCodec::Snappy => Some(Compression::Snappy),
+        };
+        assert_eq!(metadata.compression, expected);
+        let names: Vec<&str> = metadata.record.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, headers);
+        assert_eq!(metadata.record.fields[0].schema, Schema::Long(None));
+        assert_eq!(metadata.record.fields[4].schema, Schema::Union(vec![Schema::Null, Schema::Double]));
+
+        let mut decoded = Vec::new();
+        let mut blocks = avro_schema::read::BlockStreamingIterator::new(file, metadata.compression, metadata.marker);
+        while let Some(block) = blocks.next().unwrap() {
+            let mut data = block.data.as_slice();
+            for _ in 0..block.number_of_rows {
+                let row: Vec<String> = metadata.record.fields.iter().map(|f| decode(&f.schema, &mut data)).collect();
+                decoded.push(row);
+            }
+            assert!(data.is_empty());
+        }
+        assert_eq!(
+            decoded,
+            [
+                ["1", "Ann", "9.5", "true", "null"],
+                ["2", "null", "null", "false", "0.5"],
+                ["3", "Bob, Jr.", "-7.25", "null", "1.5"],
+            ]
+        );
+    }
+}
+
+/// 讀取由 avro-schema 寫出的檔案（固定同步標記、沒有 avro.codec 時為未壓縮）
+#[test]
+fn reads_files_written_by_another_implementation() {
+    let dir = tempfile::tempdir().unwrap();
+    for compression in [None, Some(Compression::Deflate), Some(Compression::Snappy)] {
+        let path = dir.path().join(format!("{:?}.avro", compression));
+        let mut file = File::create(&path).unwrap();
+        let record = Record::new(
+            "external",
+            vec![
+                Field::new("n", Schema::Long(None)),
+                Field::new("label", Schema::Union(vec![Schema::Null, Schema::String(None)])),
+                Field::new("x", Schema::Float),
+            ],
+        );
+        avro_schema::write::write_metadata(&mut file, record, compression).unwrap();
+
+        let mut data = Vec::new();
+        for (n, label, x) in [(-1i64, Some("é"), 0.5f32), (300, None, -2.0)] {
+            avro_schema::write::encode::zigzag_encode(n, &mut data).unwrap();
+            match label {
+                Some(label) => {
+                    avro_schema::write::encode::zigzag_encode(1, &mut data).unwrap();
+                    avro_schema::write::encode::zigzag_encode(label.len() as i64, &mut data).unwrap();
+                    data.extend_from_slice(label.as_bytes());
+                }
+                None => avro_schema::write::encode::zigzag_encode(0, &mut data).unwrap(),
+            }
+            data.extend_from_slice(&x.to_le_bytes());
+        }
+        let mut block = Block::new(2, data);
+        let mut compressed = CompressedBlock::default();
+        avro_schema::write::compress(&mut block, &mut compressed, compression).unwrap();
+        avro_schema::write::write_block(&mut file, &compressed).unwrap();
+        drop(file);
+
+        let (headers, records) = CsvConverter::read_avro_file(&path.to_string_lossy()).unwrap();
+        assert_eq!(headers, ["n", "label", "x"]);
+        assert_eq!(rows(&records), [["-1", "é", "0.5"], ["300", "", "-2"]], "{:?}", compression);
+    }
+}
+
+#[test]
+fn corrupt_headers_are_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bad.avro");
+    // 檔頭項目數為 i64::MIN（zigzag 編碼為 2^64 - 1），無法取負數
+    let mut bytes = b"Obj\x01".to_vec();
+    bytes.extend_from_slice(&[0xff; 9]);
+    bytes.push(0x01);
+    std::fs::write(&path, bytes).unwrap();
+    assert!(CsvConverter::read_avro_file(&path.to_string_lossy()).is_err());
+}
+
+fn decode(schema: &Schema, data: &mut &[u8]) -> String {
+    match schema {
+        Schema::Null => "null".to_string(),
+        Schema::Boolean => {
+            let value = data[0] != 0;
+            *data = &data[1..];
+            value.to_string()
+        }
+        Schema::Long(_) => zigzag(data).to_string(),
+        Schema::Double => {
+            let value = f64::from_le_bytes(data[..8].try_into().unwrap());
+            *data = &data[8..];
+            value.to_string()
+        }
+        Schema::String(_) => {
+            let len = zigzag(data) as usize;
+            let value = String::from_utf8(data[..len].to_vec()).unwrap();
+            *data = &data[len..];
+            value
+        }
+        Schema::Union(branches) => {
+            let index = zigzag(data) as usize;
+            decode(&branches[index], data)
+        }
+        other => panic!("unexpected schema {:?}", other),
+    }
+}
+
+fn zigzag(data: &mut &[u8]) -> i64 {
+    let mut n = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = data[0];
+        *data = &data[1..];
+        n |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return (n >> 1) as i64 ^ -((n & 1) as i64);
+        }
+        shift += 7;
+    }
+}
@@ -0,0 +1,78 @@
+use csv_converter::{
+    ColumnMask, ConcatOptions, CsvConverter, MaskRule, MaskingRules, SampleOptions, Sampling, ValueOptions,
+    SOURCE_FILE_COLUMN,
+};
+use serde_json::json;
+
+fn write_inputs() -> (tempfile::TempDir, Vec<String>) {
+    let dir = tempfile::tempdir().unwrap();
+    let a = dir.path().join("a.csv");
+    let b = dir.path().join("b.csv");
+    std::fs::write(&a, "id,name,note\n1,Ann,\n2,Bob,x\n").unwrap();
+    std::fs::write(&b, "id,score\n3,9.5\n").unwrap();
+    let paths = [a, b].iter().map(|p| p.to_string_lossy().into_owned()).collect();
+    (dir, paths)
+}
+
+#[test]
+fn missing_columns_are_null_but_empty_fields_stay_empty() {
+    let (_dir, paths) = write_inputs();
+    let data = CsvConverter::concat_files(&paths, &ConcatOptions::default()).unwrap();
+    assert_eq!(data.headers, ["id", "name", "note", "score"]);
+
+    let options = ValueOptions { null_cells: data.missing.clone(), ..Default::default() };
+    let rows = CsvConverter::typed_records(&data.headers, &data.records, &options).unwrap();
+    assert_eq!(
+        serde_json::to_value(rows).unwrap(),
+        json!([
+            { "id": 1, "name": "Ann", "note": "", "score": null },
+            { "id": 2, "name": "Bob", "note": "x", "score": null },
+            { "id": 3, "name": null, "note": null, "score": 9.5 },
+        ])
+    );
+}
+
+#[test]
+fn source_column_is_never_missing() {
+    let (_dir, paths) = write_inputs();
+    let options = ConcatOptions { source_column: true, ..Default::default() };
+    let data = CsvConverter::concat_files(&paths, &options).unwrap();
+    let source = data.headers.iter().position(|h| h == SOURCE_FILE_COLUMN).unwrap();
+    assert!((0..3).all(|row| !data.missing.contains(row, source)));
+    assert!(data.missing.contains(2, 1) && data.missing.contains(0, 3));
+}
+
+#[test]
+fn null_cells_follow_sampled_rows() {
+    let (_dir, paths) = write_inputs();
+    let data = CsvConverter::concat_files(&paths, &ConcatOptions::default()).unwrap();
+    let options = SampleOptions { method: Sampling::Tail(1), ..Default::default() };
+    let sampled = CsvConverter::sample_records_with_rows(&data.headers, data.records, &options).unwrap();
+    let rows: Vec<usize> = sampled.iter().map(|(row, _)| *row).collect();
+    assert_eq!(rows, [2]);
+
+    let missing = data.missing.select(&rows);
+    assert!(missing.contains(0, 1) && missing.contains(0, 2) && !missing.contains(0, 3));
+}
+
+#[test]
+fn missing_cells_follow_columns_after_drop_mask() {
+    let (_dir, paths) = write_inputs();
+    let data = CsvConverter::concat_files(&paths, &ConcatOptions::default()).unwrap();
+    let rules = MaskingRules {
+        columns: vec![ColumnMask { column: "name".to_string(), rule: MaskRule::Drop }],
+    };
+
+    let masked = CsvConverter::mask_records(&data.headers, &data.records, &rules, None).unwrap();
+    assert_eq!(masked.kept_columns, [0, 2, 3]);
+    let options = ValueOptions { null_cells: data.missing.select_columns(&masked.kept_columns), ..Default::default() };
+    let rows = CsvConverter::typed_records(&masked.headers, &masked.records, &options).unwrap();
+    assert_eq!(
+        serde_json::to_value(rows).unwrap(),
+        json!([
+            { "id": 1, "note": "", "score": null },
+            { "id": 2, "note": "x", "score": null },
+            { "id": 3, "note": null, "score": 9.5 },
+        ])
+    );
+}
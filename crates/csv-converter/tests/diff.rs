@@ -0,0 +1,41 @@
+use csv::StringRecord;
+use csv_converter::{CsvConverter, DiffOptions, RowChange};
+
+fn read(csv: &str) -> (Vec<String>, Vec<StringRecord>) {
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let headers = reader.headers().unwrap().iter().map(|h| h.to_string()).collect();
+    (headers, reader.records().collect::<Result<_, _>>().unwrap())
+}
+
+fn keys(columns: &[&str]) -> DiffOptions {
+    DiffOptions { key_columns: columns.iter().map(|c| c.to_string()).collect() }
+}
+
+#[test]
+fn composite_keys_with_separator_do_not_collide() {
+    // 以 ", " 連接時兩列的鍵都會是 "a, b, c"
+    let (headers, records) = read("k1,k2,v\n\"a, b\",c,1\na,\"b, c\",2\n");
+    let (right_headers, right_records) = read("k1,k2,v\na,\"b, c\",2\n");
+
+    let diff = CsvConverter::diff_records((&headers, &records), (&right_headers, &right_records), &keys(&["k1", "k2"]))
+        .unwrap();
+    assert_eq!(diff.rows, [RowChange::Removed { key: vec!["a, b".to_string(), "c".to_string()] }]);
+}
+
+#[test]
+fn changed_cells_are_reported_by_key() {
+    let (left_headers, left_records) = read("id,name\n1,Ann\n2,Bob\n");
+    let (right_headers, right_records) = read("id,name\n2,Bobby\n3,Cy\n");
+
+    let diff =
+        CsvConverter::diff_records((&left_headers, &left_records), (&right_headers, &right_records), &keys(&["id"]))
+            .unwrap();
+    let key = |k: &str| vec![k.to_string()];
+    assert!(matches!(&diff.rows[0], RowChange::Removed { key: k } if *k == key("1")));
+    assert!(matches!(&diff.rows[1], RowChange::Changed { key: k, cells } if *k == key("2") && cells[0].right == "Bobby"));
+    assert!(matches!(&diff.rows[2], RowChange::Added { key: k } if *k == key("3")));
+
+    // 鍵重複時無法對應
+    let (headers, records) = read("id,name\n1,a\n1,b\n");
+    assert!(CsvConverter::diff_records((&headers, &records), (&right_headers, &right_records), &keys(&["id"])).is_err());
+}
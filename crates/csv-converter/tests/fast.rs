@@ -0,0 +1,52 @@
+use csv_converter::{CsvConverter, NonFinitePolicy, NullCells, NumberLocale, NumericPolicy, ValueOptions};
+use serde_json::Value;
+
+const FIXTURE: &str = "\
+id,price,big,flag,zip,note,note,amount
+1,9.50,12345678901234567890,true,00501,\"say \"\"hi\"\"\",first,\"1.234,5\"
+-2,0.10,9007199254740993,FALSE,A0501,,second,\"€ 12,50\"
+3,1e3,NaN,yes,007,台北,third,45%
+4,,inf,否,,\"a,b\",fourth,
+";
+
+/// 以一般路徑與快速路徑轉換同一個檔案，比較解析後的 JSON
+fn assert_same_output(options: &ValueOptions) {
+    let dir = tempfile::tempdir().unwrap();
+    let csv = dir.path().join("in.csv").to_string_lossy().into_owned();
+    let general = dir.path().join("general.json").to_string_lossy().into_owned();
+    let fast = dir.path().join("fast.json").to_string_lossy().into_owned();
+    std::fs::write(&csv, FIXTURE).unwrap();
+
+    let (headers, records) = CsvConverter::read_csv_file(&csv).unwrap();
+    let expected = CsvConverter::write_json_file(&headers, &records, &general, options);
+    let actual = CsvConverter::convert_csv_to_json_file_fast(&csv, &fast, options);
+    match (expected, actual) {
+        (Ok(expected), Ok(actual)) => assert_eq!(expected.rows_written, actual.rows_written),
+        (Err(_), Err(_)) => return,
+        (expected, actual) => panic!("結果不同: {:?} / {:?}", expected.err(), actual.err()),
+    }
+
+    let read = |path: &str| serde_json::from_str::<Value>(&std::fs::read_to_string(path).unwrap()).unwrap();
+    assert_eq!(read(&fast), read(&general), "{:?}", options);
+}
+
+#[test]
+fn fast_path_matches_general_path() {
+    assert_same_output(&ValueOptions::default());
+    assert_same_output(&ValueOptions { numeric: NumericPolicy::JsSafe, ..Default::default() });
+    assert_same_output(&ValueOptions { numeric: NumericPolicy::Preserve, ..Default::default() });
+    assert_same_output(&ValueOptions { non_finite: NonFinitePolicy::Null, empty_as_null: true, ..Default::default() });
+    assert_same_output(&ValueOptions { non_finite: NonFinitePolicy::Error, ..Default::default() });
+    assert_same_output(&ValueOptions { decimal_columns: vec!["price".to_string()], ..Default::default() });
+    assert_same_output(&ValueOptions::localized(NumberLocale::De));
+}
+
+#[test]
+fn fast_path_keeps_text_columns_and_null_cells() {
+    let text = ValueOptions { text_columns: vec!["zip".to_string(), "flag".to_string()], ..Default::default() };
+    assert_same_output(&text);
+
+    let mut null_cells = NullCells::default();
+    null_cells.push(1..3, vec![0, 3]);
+    assert_same_output(&ValueOptions { null_cells, ..Default::default() });
+}
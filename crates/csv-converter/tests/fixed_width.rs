@@ -0,0 +1,59 @@
+use csv_converter::{ColumnType, CsvConverter, FixedWidthColumn, FixedWidthLayout, NonFinitePolicy, ValueOptions};
+
+fn layout() -> FixedWidthLayout {
+    let column = |name: &str, start, width, column_type| FixedWidthColumn {
+        name: name.to_string(),
+        start,
+        width,
+        column_type,
+    };
+    FixedWidthLayout {
+        columns: vec![
+            column("id", 1, 3, Some(ColumnType::Integer)),
+            column("name", 4, 6, None),
+            column("score", 10, 5, Some(ColumnType::Float)),
+        ],
+        skip_lines: 1,
+        allow_short_lines: false,
+    }
+}
+
+#[test]
+fn bad_lines_are_reported_with_line_numbers() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("report.txt");
+    let text = "\
+REPORT 2024
+001Ann     9.5
+0x2Bob     1.0
+003Cy
+004Dee     inf
+005      12.25
+";
+    std::fs::write(&path, text).unwrap();
+    let options = ValueOptions { non_finite: NonFinitePolicy::Error, ..Default::default() };
+
+    let data = CsvConverter::read_fixed_width_file(path.to_str().unwrap(), &layout(), &options).unwrap();
+
+    assert_eq!(data.headers, ["id", "name", "score"]);
+    let records: Vec<Vec<&str>> = data.records.iter().map(|r| r.iter().collect()).collect();
+    // 空白欄位保留為空字串
+    assert_eq!(records, [vec!["001", "Ann", "9.5"], vec!["005", "", "12.25"]]);
+
+    let issues: Vec<(usize, &str)> = data.issues.iter().map(|i| (i.line, i.message.as_str())).collect();
+    assert_eq!(issues.len(), 3, "{:?}", issues);
+    assert_eq!(issues[0].0, 3);
+    assert!(issues[0].1.contains("不符合宣告型別"), "{}", issues[0].1);
+    assert_eq!(issues[1].0, 4);
+    assert!(issues[1].1.contains("長度"), "{}", issues[1].1);
+    // 轉換失敗的值只記錄該行，不會中止整個檔案
+    assert_eq!(issues[2].0, 5);
+    assert!(issues[2].1.contains("非有限數值"), "{}", issues[2].1);
+}
+
+#[test]
+fn overlapping_layout_is_rejected() {
+    let mut layout = layout();
+    layout.columns[1].width = 7;
+    assert!(layout.validate().is_err());
+}
@@ -0,0 +1,88 @@
+use csv::StringRecord;
+use csv_converter::{CsvConverter, NumericPolicy, TomlOptions, ValueOptions, XmlOptions, YamlOptions};
+
+const CSV: &str = "id,price,precise,big,name,note\n1,0.10,1.2345678901234567891,123456789012345678901234,Ann,\n2,2.5,3,7,Bob,x\n";
+
+fn read_csv(csv: &str) -> (Vec<String>, Vec<StringRecord>) {
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let headers = reader.headers().unwrap().iter().map(|h| h.to_string()).collect();
+    (headers, reader.records().collect::<Result<_, _>>().unwrap())
+}
+
+fn read() -> (Vec<String>, Vec<StringRecord>) {
+    read_csv(CSV)
+}
+
+fn exact() -> ValueOptions {
+    ValueOptions { decimal_columns: vec!["price".to_string(), "precise".to_string()], ..Default::default() }
+}
+
+fn output(dir: &tempfile::TempDir, name: &str) -> String {
+    dir.path().join(name).to_string_lossy().into_owned()
+}
+
+#[test]
+fn yaml_keeps_exact_decimals() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = output(&dir, "out.yaml");
+    let (headers, records) = read();
+
+    let options = YamlOptions { values: exact(), ..Default::default() };
+    CsvConverter::write_yaml_file(&headers, &records, &path, &options).unwrap();
+    let yaml: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(yaml[0]["price"], serde_yaml::Value::String("0.10".to_string()));
+    assert_eq!(yaml[0]["precise"], serde_yaml::Value::String("1.2345678901234567891".to_string()));
+    assert_eq!(yaml[0]["big"], serde_yaml::Value::from(1.2345678901234568e23));
+    assert_eq!(yaml[1]["price"], serde_yaml::Value::from(2.5));
+    assert_eq!(yaml[1]["precise"], serde_yaml::Value::from(3));
+    assert_eq!(yaml[0]["note"], serde_yaml::Value::String(String::new()));
+
+    // 一般轉換仍輸出數字
+    let options = YamlOptions { document_per_row: true, ..Default::default() };
+    CsvConverter::write_yaml_file(&headers, &records, &path, &options).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(text.matches("---").count(), 2);
+    assert!(text.contains("price: 0.1\n"), "{}", text);
+}
+
+#[test]
+fn toml_keeps_exact_decimals_and_omits_nulls() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = output(&dir, "out.toml");
+    let (headers, records) = read();
+    let values = ValueOptions { numeric: NumericPolicy::Preserve, empty_as_null: true, ..Default::default() };
+
+    let report = CsvConverter::write_toml_file(&headers, &records, &path, &TomlOptions { values, ..Default::default() })
+        .unwrap();
+    assert!(report.warnings.iter().any(|w| w.contains("省略了 1 個")), "{:?}", report.warnings);
+    let toml: toml::Table = std::fs::read_to_string(&path).unwrap().parse().unwrap();
+    let rows = toml["rows"].as_array().unwrap();
+    assert_eq!(rows[0]["price"].as_str(), Some("0.10"));
+    assert_eq!(rows[0]["precise"].as_str(), Some("1.2345678901234567891"));
+    assert_eq!(rows[0]["big"].as_str(), Some("123456789012345678901234"));
+    assert!(rows[0].get("note").is_none());
+    assert_eq!(rows[1]["price"].as_float(), Some(2.5));
+    assert_eq!(rows[1]["id"].as_integer(), Some(2));
+}
+
+#[test]
+fn xml_keeps_literals_and_deduplicates_names() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = output(&dir, "out.xml");
+    let csv = "a b,a_b,1x,price\n1,2,3,0.10\n";
+    let (headers, records) = read_csv(csv);
+
+    let options = XmlOptions { values: exact(), ..Default::default() };
+    let report = CsvConverter::write_xml_file(&headers, &records, &path, &options).unwrap();
+    assert_eq!(report.warnings.len(), 3, "{:?}", report.warnings);
+    let xml = std::fs::read_to_string(&path).unwrap();
+    for element in ["<a_b>1</a_b>", "<a_b_2>2</a_b_2>", "<_1x>3</_1x>", "<price>0.10</price>"] {
+        assert!(xml.contains(element), "{} 不在 {}", element, xml);
+    }
+
+    // 屬性名稱重複會產生無效的 XML
+    let options = XmlOptions { all_attributes: true, values: exact(), ..Default::default() };
+    CsvConverter::write_xml_file(&headers, &records, &path, &options).unwrap();
+    let xml = std::fs::read_to_string(&path).unwrap();
+    assert!(xml.contains(r#"<row a_b="1" a_b_2="2" _1x="3" price="0.10"/>"#), "{}", xml);
+}
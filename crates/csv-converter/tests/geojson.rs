@@ -0,0 +1,106 @@
+use csv::StringRecord;
+use csv_converter::{BoundingBox, CsvConverter, GeoJsonOptions, NumberLocale, ValueOptions};
+use serde_json::{json, Value};
+
+fn convert(csv: &str, options: &GeoJsonOptions) -> anyhow::Result<(Value, csv_converter::ConversionReport)> {
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.to_string()).collect();
+    let records: Vec<StringRecord> = reader.records().collect::<Result<_, _>>()?;
+    convert_records(&headers, &records, options)
+}
+
+fn convert_records(
+    headers: &[String],
+    records: &[StringRecord],
+    options: &GeoJsonOptions,
+) -> anyhow::Result<(Value, csv_converter::ConversionReport)> {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("out.geojson").to_string_lossy().into_owned();
+    let report = CsvConverter::write_geojson_file(headers, records, &path, options)?;
+    Ok((serde_json::from_str(&std::fs::read_to_string(&path)?)?, report))
+}
+
+fn coordinates(collection: &Value) -> Vec<Value> {
+    collection["features"].as_array().unwrap().iter().map(|f| f["geometry"]["coordinates"].clone()).collect()
+}
+
+#[test]
+fn detects_latitude_and_longitude_columns() {
+    let (collection, _) = convert("name,Latitude,LNG\nTaipei,25.03,121.56\n", &GeoJsonOptions::default()).unwrap();
+    assert_eq!(collection["type"], "FeatureCollection");
+    let feature = &collection["features"][0];
+    assert_eq!(feature["geometry"], json!({ "type": "Point", "coordinates": [121.56, 25.03] }));
+    assert_eq!(feature["properties"], json!({ "name": "Taipei" }));
+
+    let (collection, _) = convert("緯度,經度\n25.03,121.56\n", &GeoJsonOptions::default()).unwrap();
+    assert_eq!(coordinates(&collection), [json!([121.56, 25.03])]);
+
+    let options = GeoJsonOptions { lat_column: Some("y".into()), lon_column: Some("x".into()), ..Default::default() };
+    let (collection, _) = convert("x,y,lat\n121.56,25.03,0\n", &options).unwrap();
+    assert_eq!(coordinates(&collection), [json!([121.56, 25.03])]);
+    assert_eq!(collection["features"][0]["properties"], json!({ "lat": 0 }));
+
+    assert!(convert("name,y\nTaipei,25.03\n", &GeoJsonOptions::default()).is_err());
+    assert!(convert("lat,lon\n1,2\n", &GeoJsonOptions { lat_column: Some("y".into()), ..Default::default() }).is_err());
+}
+
+#[test]
+fn parses_coordinates_with_the_configured_locale() {
+    let csv = "name,lat,lon\nTaipei,\"25,03\",\"121,56\"\nZero,0,0\n";
+    let values = ValueOptions::localized(NumberLocale::De);
+    let (collection, report) = convert(csv, &GeoJsonOptions { values, ..Default::default() }).unwrap();
+    assert_eq!(coordinates(&collection), [json!([121.56, 25.03]), json!([0.0, 0.0])]);
+    assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+
+    // 預設地區格式下逗號不是小數點
+    let (collection, report) = convert(csv, &GeoJsonOptions::default()).unwrap();
+    assert_eq!(coordinates(&collection).len(), 1);
+    assert_eq!(report.rows_rejected, 1);
+}
+
+#[test]
+fn writes_invalid_coordinates_to_the_rejects_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let rejects = dir.path().join("rejects.csv").to_string_lossy().into_owned();
+    let headers = vec!["name".to_string(), "lat".to_string(), "lon".to_string()];
+    let records = vec![
+        StringRecord::from(vec!["ok", "25.03", "121.56"]),
+        StringRecord::from(vec!["north", "91", "0"]),
+        StringRecord::from(vec!["east", "0", "-180.5"]),
+        StringRecord::from(vec!["text", "abc", "0"]),
+        StringRecord::from(vec!["inf", "0", "inf"]),
+        // 欄位數不足的記錄也照原樣寫入
+        StringRecord::from(vec!["short"]),
+        StringRecord::from(vec!["edge", "-90", "180"]),
+    ];
+    let options = GeoJsonOptions { rejects_path: Some(rejects.clone()), ..Default::default() };
+    let (collection, report) = convert_records(&headers, &records, &options).unwrap();
+
+    assert_eq!(coordinates(&collection), [json!([121.56, 25.03]), json!([180.0, -90.0])]);
+    assert_eq!(report.rows_written, 2);
+    assert_eq!(report.rows_rejected, 5);
+    assert!(report.warnings.iter().any(|w| w.contains("5 筆記錄的座標無效")), "{:?}", report.warnings);
+
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_path(&rejects).unwrap();
+    assert_eq!(reader.headers().unwrap(), vec!["name", "lat", "lon", "_reject_reason"]);
+    let rows: Vec<StringRecord> = reader.records().map(Result::unwrap).collect();
+    let names: Vec<&str> = rows.iter().map(|r| &r[0]).collect();
+    assert_eq!(names, ["north", "east", "text", "inf", "short"]);
+    assert_eq!(rows[0].get(3).unwrap(), r#"無效的座標: lat="91", lon="0""#);
+    assert_eq!(rows[4].len(), 2);
+}
+
+#[test]
+fn filters_points_outside_the_bounding_box() {
+    let csv = "name,lat,lon\nTaipei,25.03,121.56\nTokyo,35.68,139.69\nKaohsiung,22.63,120.30\n";
+    let bbox: BoundingBox = "119,21,123,26".parse().unwrap();
+    let (collection, report) = convert(csv, &GeoJsonOptions { bbox: Some(bbox), ..Default::default() }).unwrap();
+
+    assert_eq!(coordinates(&collection), [json!([121.56, 25.03]), json!([120.30, 22.63])]);
+    assert_eq!(collection["bbox"], json!([119.0, 21.0, 123.0, 26.0]));
+    assert!(report.warnings.iter().any(|w| w.contains("1 筆記錄不在指定範圍內")), "{:?}", report.warnings);
+
+    assert!("119,21,123".parse::<BoundingBox>().is_err());
+    assert!("123,21,119,26".parse::<BoundingBox>().is_err());
+    assert!("a,b,c,d".parse::<BoundingBox>().is_err());
+}
@@ -0,0 +1,165 @@
+use csv_converter::{CsvConverter, IncrementalOptions, IncrementalSummary};
+use serde_json::{json, Value};
+use std::io::Write;
+use tempfile::TempDir;
+
+struct Fixture {
+    _dir: TempDir,
+    csv: String,
+    output: String,
+}
+
+fn fixture(output: &str, text: &str) -> Fixture {
+    let dir = tempfile::tempdir().unwrap();
+    let csv = dir.path().join("events.csv").to_string_lossy().into_owned();
+    let output = dir.path().join(output).to_string_lossy().into_owned();
+    std::fs::write(&csv, text).unwrap();
+    Fixture { _dir: dir, csv, output }
+}
+
+impl Fixture {
+    fn run(&self) -> IncrementalSummary {
+        CsvConverter::convert_csv_incremental(&self.csv, &self.output, &IncrementalOptions::default()).unwrap()
+    }
+
+    fn append(&self, text: &str) {
+        std::fs::OpenOptions::new().append(true).open(&self.csv).unwrap().write_all(text.as_bytes()).unwrap();
+    }
+
+    fn rows(&self) -> Vec<Value> {
+        let text = std::fs::read_to_string(&self.output).unwrap();
+        text.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+    }
+
+    fn state(&self) -> Value {
+        let text = std::fs::read_to_string(format!("{}.state.json", self.output)).unwrap();
+        serde_json::from_str(&text).unwrap()
+    }
+}
+
+#[test]
+fn first_run_converts_everything_and_saves_the_offset() {
+    let text = "id,name\n1,Ann\n2,Bob\n";
+    let f = fixture("out.ndjson", text);
+
+    let summary = f.run();
+    assert_eq!(summary.report.rows_written, 2);
+    assert_eq!(summary.total_rows, 2);
+    assert_eq!(summary.full_rebuild, None);
+    assert_eq!(f.rows(), [json!({ "id": 1, "name": "Ann" }), json!({ "id": 2, "name": "Bob" })]);
+    assert_eq!(f.state()["offset"], text.len());
+    assert_eq!(f.state()["rows"], 2);
+
+    // 沒有新列時不寫出任何東西
+    let summary = f.run();
+    assert_eq!(summary.report.rows_written, 0);
+    assert_eq!(summary.report.bytes_in, 0);
+    assert_eq!(f.rows().len(), 2);
+}
+
+#[test]
+fn appended_rows_are_converted_once() {
+    let f = fixture("out.jsonl", "id,name\n1,Ann\n");
+    f.run();
+
+    // 最後一列沒有換行時可能還在寫入，留到下次
+    f.append("2,Bob\n3,C");
+    let summary = f.run();
+    assert_eq!(summary.report.rows_written, 1);
+    assert_eq!(summary.report.bytes_in, "2,Bob\n".len() as u64);
+
+    f.append("y\n");
+    let summary = f.run();
+    assert_eq!(summary.report.rows_written, 1);
+    assert_eq!(summary.total_rows, 3);
+    assert_eq!(summary.full_rebuild, None);
+    let names: Vec<Value> = f.rows().into_iter().map(|row| row["name"].clone()).collect();
+    assert_eq!(names, ["Ann", "Bob", "Cy"]);
+}
+
+#[test]
+fn changed_header_restarts_from_scratch() {
+    let f = fixture("out.ndjson", "id,name\n1,Ann\n");
+    f.run();
+
+    std::fs::write(&f.csv, "id,full_name\n1,Ann\n2,Bob\n").unwrap();
+    let summary = f.run();
+    assert_eq!(summary.full_rebuild.as_deref(), Some("標題列已改變"));
+    assert_eq!(summary.total_rows, 2);
+    assert_eq!(f.rows(), [json!({ "id": 1, "full_name": "Ann" }), json!({ "id": 2, "full_name": "Bob" })]);
+}
+
+#[test]
+fn truncated_or_rewritten_files_are_rebuilt() {
+    let f = fixture("out.ndjson", "id,name\n1,Ann\n2,Bob\n");
+    f.run();
+
+    std::fs::write(&f.csv, "id,name\n3,Cy\n").unwrap();
+    let summary = f.run();
+    assert_eq!(summary.full_rebuild.as_deref(), Some("檔案被截斷"));
+    assert_eq!(f.rows(), [json!({ "id": 3, "name": "Cy" })]);
+
+    // 長度足夠但最後一筆已轉換的記錄不同，視為輪替
+    std::fs::write(&f.csv, "id,name\n4,Dee\n5,Eve\n").unwrap();
+    let summary = f.run();
+    assert_eq!(summary.full_rebuild.as_deref(), Some("檔案已被輪替或改寫"));
+    assert_eq!(f.rows(), [json!({ "id": 4, "name": "Dee" }), json!({ "id": 5, "name": "Eve" })]);
+
+    // 輸出檔被刪除時也從頭轉換
+    std::fs::remove_file(&f.output).unwrap();
+    let summary = f.run();
+    assert_eq!(summary.full_rebuild.as_deref(), Some("輸出檔不存在"));
+    assert_eq!(f.rows().len(), 2);
+}
+
+#[test]
+fn width_mismatch_is_an_error_and_keeps_the_state() {
+    let f = fixture("out.ndjson", "id,name\n1,Ann\n");
+    f.run();
+    let state = f.state();
+
+    f.append("2\n");
+    assert!(CsvConverter::convert_csv_incremental(&f.csv, &f.output, &IncrementalOptions::default()).is_err());
+    assert_eq!(f.state(), state);
+}
+
+#[test]
+fn unsupported_output_is_rejected() {
+    let f = fixture("out.json", "id\n1\n");
+    assert!(CsvConverter::convert_csv_incremental(&f.csv, &f.output, &IncrementalOptions::default()).is_err());
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn parquet_appends_numbered_parts() {
+    use std::path::Path;
+
+    let f = fixture("out.parquet", "id,name\n1,Ann\n2,Bob\n");
+    let part = |n: usize| Path::new(&f.output).join(format!("part-{:05}.parquet", n));
+    let rows = |n: usize| {
+        use polars::prelude::{ParquetReader, SerReader};
+        ParquetReader::new(std::fs::File::open(part(n)).unwrap()).finish().unwrap().height()
+    };
+
+    f.run();
+    assert_eq!(rows(0), 2);
+
+    f.append("3,Cy\n");
+    let summary = f.run();
+    assert_eq!(summary.total_rows, 3);
+    assert_eq!(rows(1), 1);
+    assert_eq!(f.state()["parts"], 2);
+
+    // 沒有新列時不新增檔案
+    f.run();
+    assert!(!part(2).exists());
+
+    // 型別改變時刪除舊的部分檔並重新轉換，目錄中的其他檔案保留
+    std::fs::write(Path::new(&f.output).join("keep.txt"), "").unwrap();
+    f.append("x,Dee\n");
+    let summary = f.run();
+    assert!(summary.full_rebuild.unwrap().contains("id"));
+    assert_eq!(rows(0), 4);
+    assert!(!part(1).exists());
+    assert!(Path::new(&f.output).join("keep.txt").exists());
+}
@@ -0,0 +1,83 @@
+use csv_converter::{CsvConverter, FormatOptions, LintKind, QuoteStyle};
+
+fn write(dir: &tempfile::TempDir, bytes: &[u8]) -> String {
+    let path = dir.path().join("input.csv").to_string_lossy().into_owned();
+    std::fs::write(&path, bytes).unwrap();
+    path
+}
+
+fn lint(bytes: &[u8]) -> Vec<(usize, LintKind)> {
+    let dir = tempfile::tempdir().unwrap();
+    let issues = CsvConverter::lint_csv_file(&write(&dir, bytes)).unwrap();
+    issues.iter().map(|issue| (issue.line, issue.kind)).collect()
+}
+
+fn format(bytes: &[u8], options: &FormatOptions) -> String {
+    let dir = tempfile::tempdir().unwrap();
+    String::from_utf8(CsvConverter::format_csv_file(&write(&dir, bytes), options).unwrap()).unwrap()
+}
+
+#[test]
+fn clean_file_has_no_issues() {
+    assert!(lint(b"id,name\n1,\"a, b\"\n2,\"say \"\"hi\"\"\"\n").is_empty());
+    assert!(lint(b"").is_empty());
+}
+
+#[test]
+fn reports_each_kind_with_its_line() {
+    assert_eq!(lint(b"id,name\n1,a\n2\n3,c,d\n"), [(3, LintKind::FieldCount), (4, LintKind::FieldCount)]);
+    assert_eq!(lint(b"id,name\n1,a\r\n2,b\n3,c\r\n"), [(2, LintKind::MixedLineEndings)]);
+    assert_eq!(lint(b"id,name\n1,a\"b\n"), [(2, LintKind::StrayQuote)]);
+    assert_eq!(lint(b"id,name\n1,\"a\"b\n"), [(2, LintKind::StrayQuote)]);
+    assert_eq!(lint(b"id,name\n1,\"a\n2,b\n"), [(2, LintKind::StrayQuote)]);
+    assert_eq!(lint(b"id ,name\n1, a\n2,\" b\"\n"), [(1, LintKind::TrailingWhitespace), (2, LintKind::TrailingWhitespace)]);
+    assert_eq!(lint(b"id,name,id\n1,a,2\n"), [(1, LintKind::DuplicateHeader)]);
+    assert_eq!(lint(b"id,,name\n1,a,b\n"), [(1, LintKind::BlankHeader)]);
+    assert_eq!(lint(b"id,name\n1,\xff\n"), [(2, LintKind::InvalidUtf8)]);
+}
+
+#[test]
+fn line_numbers_count_quoted_newlines() {
+    assert_eq!(lint(b"id,note\n1,\"x\ny\"\n2\n"), [(4, LintKind::FieldCount)]);
+}
+
+#[test]
+fn leading_blank_lines_are_not_the_header() {
+    // csv 讀取器會略過空白行，標題是第一個非空白行
+    assert!(lint(b"\n\nid,name\n1,a\n").is_empty());
+    assert_eq!(lint(b"\nid,name\n1\n"), [(3, LintKind::FieldCount)]);
+    assert_eq!(lint(b"\nid,id\n1,2\n"), [(2, LintKind::DuplicateHeader)]);
+}
+
+#[test]
+fn format_normalizes_and_round_trips() {
+    let input = b"\n id , name\r\n1, a \r\n2,\"b\"\r\n\r\n3,\"x,\"\"y\"\"\"\r\n";
+    let formatted = format(input, &FormatOptions::default());
+    assert_eq!(formatted, "id,name\n1, a \n2,b\n3,\"x,\"\"y\"\"\"\n");
+    // 格式化後的結果再格式化不會改變，且檢查沒有問題
+    assert_eq!(format(formatted.as_bytes(), &FormatOptions::default()), formatted);
+    assert_eq!(lint(formatted.as_bytes()), [(2, LintKind::TrailingWhitespace)]);
+
+    let options = FormatOptions { trim_fields: true, ..Default::default() };
+    let trimmed = format(input, &options);
+    assert_eq!(trimmed, "id,name\n1,a\n2,b\n3,\"x,\"\"y\"\"\"\n");
+    assert!(lint(trimmed.as_bytes()).is_empty());
+    assert_eq!(format(trimmed.as_bytes(), &options), trimmed);
+}
+
+#[test]
+fn format_quote_styles() {
+    let input = b"id,name\n1,a\n";
+    let always = FormatOptions { quote_style: QuoteStyle::Always, ..Default::default() };
+    assert_eq!(format(input, &always), "\"id\",\"name\"\n\"1\",\"a\"\n");
+    let non_numeric = FormatOptions { quote_style: QuoteStyle::NonNumeric, ..Default::default() };
+    assert_eq!(format(input, &non_numeric), "\"id\",\"name\"\n1,\"a\"\n");
+    assert_eq!(format(format(input, &always).as_bytes(), &FormatOptions::default()), "id,name\n1,a\n");
+}
+
+#[test]
+fn format_rejects_unparseable_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write(&dir, b"id,name\n1,a\n2\n");
+    assert!(CsvConverter::format_csv_file(&path, &FormatOptions::default()).is_err());
+}
@@ -0,0 +1,111 @@
+use csv::StringRecord;
+use csv_converter::{ColumnMask, CsvConverter, Generalization, MaskRule, MaskedData, MaskingRules};
+
+fn read(csv: &str) -> (Vec<String>, Vec<StringRecord>) {
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let headers = reader.headers().unwrap().iter().map(|h| h.to_string()).collect();
+    (headers, reader.records().collect::<Result<_, _>>().unwrap())
+}
+
+fn rules(rules: &[(&str, &str)]) -> MaskingRules {
+    let columns = rules
+        .iter()
+        .map(|(column, rule)| ColumnMask { column: column.to_string(), rule: rule.parse().unwrap() })
+        .collect();
+    MaskingRules { columns }
+}
+
+fn column(data: &MaskedData, name: &str) -> Vec<String> {
+    let idx = data.headers.iter().position(|h| h == name).unwrap();
+    data.records.iter().map(|r| r[idx].to_string()).collect()
+}
+
+fn mask(csv: &str, rule: &str, salt: Option<&str>) -> anyhow::Result<Vec<String>> {
+    let (headers, records) = read(csv);
+    let data = CsvConverter::mask_records(&headers, &records, &rules(&[("v", rule)]), salt)?;
+    Ok(column(&data, "v"))
+}
+
+#[test]
+fn hash_is_stable_for_the_same_salt() {
+    let csv = "v\nann@example.com\nbob@example.com\nann@example.com\n";
+    let first = mask(csv, "hash", Some("pepper")).unwrap();
+    assert_eq!(first, mask(csv, "hash", Some("pepper")).unwrap());
+    assert_eq!(first[0], first[2]);
+    assert_ne!(first[0], first[1]);
+    assert_eq!(first[0].len(), 16);
+    assert!(first[0].bytes().all(|b| b.is_ascii_hexdigit()));
+    assert_ne!(first, mask(csv, "hash", Some("salt")).unwrap());
+
+    // 沒有鹽值時不能雜湊，錯誤訊息不含欄位值
+    for salt in [None, Some("")] {
+        let error = mask(csv, "hash", salt).unwrap_err().to_string();
+        assert!(error.contains("鹽值") && !error.contains("ann"), "{}", error);
+    }
+    assert!(mask(csv, "redact", None).is_ok());
+}
+
+#[test]
+fn redact_and_partial_hide_values() {
+    let csv = "v,n\n0912345678,1\n12,2\n,3\n";
+    assert_eq!(mask(csv, "redact", None).unwrap(), ["[REDACTED]", "[REDACTED]", ""]);
+    assert_eq!(mask(csv, "partial", None).unwrap(), ["****5678", "****", ""]);
+    assert_eq!(mask(csv, "partial:2", None).unwrap(), ["****78", "****", ""]);
+    assert_eq!(mask("v\n身分證字號A123\n", "partial:3", None).unwrap(), ["****123"]);
+}
+
+#[test]
+fn generalize_dates_and_numbers() {
+    let dates = "v\n2024-03-05\n2023-12-31T23:59:00\n";
+    assert_eq!(mask(dates, "generalize", None).unwrap(), ["2024-03", "2023-12"]);
+    assert_eq!(mask(dates, "generalize:year", None).unwrap(), ["2024", "2023"]);
+    assert_eq!(mask("v\n37\n30\n-5\n0.5\n", "generalize:10", None).unwrap(), ["30-39", "30-39", "-10--1", "0-9"]);
+
+    // 超出範圍的值回報錯誤而不是溢位
+    for value in ["1e30", "-1e30", "abc"] {
+        assert!(mask(&format!("v\n{}\n", value), "generalize:10", None).is_err(), "{}", value);
+    }
+    assert!(mask("v\n9223372036854775807\n", "generalize:1000", None).is_err());
+    assert!(mask("v\n2024/03/05\n", "generalize:month", None).is_err());
+}
+
+#[test]
+fn bucket_sizes_must_fit_i64() {
+    assert_eq!("generalize:10".parse::<MaskRule>().unwrap(), MaskRule::Generalize(Generalization::Bucket(10)));
+    assert!("generalize:9223372036854775807".parse::<MaskRule>().is_ok());
+    for rule in ["generalize:9223372036854775808", "generalize:0", "generalize:-1", "partial:x", "scramble"] {
+        assert!(rule.parse::<MaskRule>().is_err(), "{}", rule);
+    }
+}
+
+#[test]
+fn drop_removes_columns() {
+    let (headers, records) = read("id,email,name\n1,a@b.c,Ann\n");
+    let data =
+        CsvConverter::mask_records(&headers, &records, &rules(&[("email", "drop"), ("name", "redact")]), None).unwrap();
+    assert_eq!(data.headers, ["id", "name"]);
+    assert_eq!(data.kept_columns, [0, 2]);
+    assert_eq!(data.records[0].iter().collect::<Vec<_>>(), ["1", "[REDACTED]"]);
+
+    assert!(CsvConverter::mask_records(&headers, &records, &rules(&[("phone", "drop")]), None).is_err());
+}
+
+#[test]
+fn rules_are_read_from_csv() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("mask.csv");
+    std::fs::write(&path, "column,rule,param\nemail,hash,\nphone,partial,3\nborn,generalize,year\n").unwrap();
+    let rules = MaskingRules::from_csv_file(&path.to_string_lossy()).unwrap();
+    let parsed: Vec<(&str, MaskRule)> = rules.columns.iter().map(|c| (c.column.as_str(), c.rule)).collect();
+    assert_eq!(
+        parsed,
+        [
+            ("email", MaskRule::Hash),
+            ("phone", MaskRule::Partial(3)),
+            ("born", MaskRule::Generalize(Generalization::Year)),
+        ]
+    );
+
+    std::fs::write(&path, "column,rule\nemail,hash\nemail,drop\n").unwrap();
+    assert!(MaskingRules::from_csv_file(&path.to_string_lossy()).is_err());
+}
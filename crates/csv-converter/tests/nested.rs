@@ -0,0 +1,44 @@
+use csv_converter::{CsvConverter, GroupAggregate, NestShape, NestedOptions, NumberLocale, ValueOptions};
+use serde_json::{json, Value};
+
+fn nest(csv: &str, options: &NestedOptions) -> Value {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("out.json").to_string_lossy().into_owned();
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let headers: Vec<String> = reader.headers().unwrap().iter().map(|h| h.to_string()).collect();
+    let records: Vec<csv::StringRecord> = reader.records().collect::<Result<_, _>>().unwrap();
+    CsvConverter::write_nested_json_file(&headers, &records, &path, options).unwrap();
+    serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap()
+}
+
+fn options(values: ValueOptions) -> NestedOptions {
+    NestedOptions {
+        group_by: vec!["city".to_string()],
+        shape: NestShape::Array,
+        aggregates: vec![GroupAggregate::Count, GroupAggregate::Sum("amount".to_string())],
+        keep_keys: false,
+        values,
+    }
+}
+
+#[test]
+fn localized_sums_are_not_reparsed() {
+    // 0,5 + 122,956 = 123.456，若以德語格式重新解析會變成 123456
+    let csv = "city,amount\nBerlin,\"0,5\"\nBerlin,\"122,956\"\nWien,\"1.000\"\nWien,2\n";
+    let tree = nest(csv, &options(ValueOptions::localized(NumberLocale::De)));
+    let sums: Vec<&Value> = tree.as_array().unwrap().iter().map(|g| &g["sum_amount"]).collect();
+    assert_eq!(sums, [&json!(123.456), &json!(1002)]);
+}
+
+#[test]
+fn groups_without_values_sum_to_null() {
+    let csv = "city,amount\nTokyo,\nOsaka,3\n";
+    let tree = nest(csv, &options(ValueOptions::default()));
+    assert_eq!(
+        tree,
+        json!([
+            { "city": "Tokyo", "count": 1, "sum_amount": null, "rows": [{ "amount": "" }] },
+            { "city": "Osaka", "count": 1, "sum_amount": 3, "rows": [{ "amount": 3 }] },
+        ])
+    );
+}
@@ -0,0 +1,83 @@
+use csv_converter::{CancellationToken, Cancelled, CsvConverter, Progress, ProgressMonitor, ValueOptions};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn cancelled_conversion_keeps_existing_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("in.csv").to_string_lossy().into_owned();
+    let output = dir.path().join("out.json").to_string_lossy().into_owned();
+    let rows: String = (0..100).map(|i| format!("{}\n", i)).collect();
+    std::fs::write(&input, format!("n\n{}", rows)).unwrap();
+    std::fs::write(&output, "old").unwrap();
+
+    // 處理到第 10 列時取消
+    let cancel = CancellationToken::new();
+    let observer_token = cancel.clone();
+    let monitor = ProgressMonitor {
+        observer: Some(Arc::new(move |progress: &Progress| {
+            if progress.rows == 10 {
+                observer_token.cancel();
+            }
+        })),
+        cancel,
+        every_rows: 1,
+    };
+    let error =
+        CsvConverter::convert_csv_to_json_file_with_progress(&input, &output, &ValueOptions::default(), &monitor)
+            .unwrap_err();
+    assert!(error.is::<Cancelled>());
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), "old");
+    assert!(!dir.path().join("out.json.partial").exists());
+}
+
+#[test]
+fn progress_is_reported_until_the_end() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("in.csv").to_string_lossy().into_owned();
+    let output = dir.path().join("out.json").to_string_lossy().into_owned();
+    std::fs::write(&input, "n\n1\n2\n3\n").unwrap();
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let observer_seen = seen.clone();
+    let monitor = ProgressMonitor {
+        observer: Some(Arc::new(move |progress: &Progress| observer_seen.lock().unwrap().push(progress.rows))),
+        every_rows: 2,
+        ..Default::default()
+    };
+    let report =
+        CsvConverter::convert_csv_to_json_file_with_progress(&input, &output, &ValueOptions::default(), &monitor)
+            .unwrap();
+    assert_eq!(report.rows_written, 3);
+    assert_eq!(*seen.lock().unwrap(), [0, 2, 3]);
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+    assert_eq!(json, serde_json::json!([{ "n": 1 }, { "n": 2 }, { "n": 3 }]));
+}
+
+#[test]
+fn atomic_write_replaces_output_only_on_success() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("out.txt").to_string_lossy().into_owned();
+    std::fs::write(&output, "old").unwrap();
+    let cancel = CancellationToken::new();
+
+    let result = CsvConverter::write_atomically(&output, &cancel, |path| -> anyhow::Result<()> {
+        std::fs::write(path, "half")?;
+        anyhow::bail!("寫入失敗")
+    });
+    assert!(result.is_err());
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), "old");
+
+    // 不檢查取消的寫入函式寫完後才發現已取消
+    cancel.cancel();
+    let error = CsvConverter::write_atomically(&output, &cancel, |path| Ok(std::fs::write(path, "new")?)).unwrap_err();
+    assert!(error.is::<Cancelled>());
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), "old");
+
+    let written = CsvConverter::write_atomically(&output, &CancellationToken::new(), |path| {
+        std::fs::write(path, "new")?;
+        Ok(3)
+    });
+    assert_eq!(written.unwrap(), 3);
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), "new");
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+}
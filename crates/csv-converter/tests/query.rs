@@ -0,0 +1,44 @@
+#![cfg(feature = "query")]
+
+use csv_converter::{CsvConverter, QueryOptions, ValueOptions};
+use serde_json::{json, Value};
+
+fn write_csv(dir: &tempfile::TempDir, name: &str, text: &str) -> String {
+    let path = dir.path().join(name).to_string_lossy().into_owned();
+    std::fs::write(&path, text).unwrap();
+    path
+}
+
+#[test]
+fn result_types_survive_conversion() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_csv(&dir, "zips.csv", "zip,flag,n\n00501,true,1\nA0501,false,\n");
+    let sql = format!("SELECT zip, CAST(flag AS VARCHAR) AS flag_text, n FROM '{}' ORDER BY zip", path);
+
+    let data = CsvConverter::query_files(&sql, &QueryOptions::default()).unwrap();
+    let values = data.value_options(&ValueOptions::default());
+    let rows = CsvConverter::typed_records(&data.headers, &data.records, &values).unwrap();
+    let rows: Vec<Value> = rows.into_iter().map(Value::Object).collect();
+    assert_eq!(
+        rows,
+        [
+            json!({ "zip": "00501", "flag_text": "true", "n": 1 }),
+            json!({ "zip": "A0501", "flag_text": "false", "n": null }),
+        ]
+    );
+}
+
+#[test]
+fn quotes_in_identifiers_and_comments_are_not_strings() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_csv(&dir, "n.csv", "n\n1\n2\n");
+    let sql = format!(
+        "SELECT n AS \"it's\" -- the table's path\n/* isn't a string */ FROM '{}' WHERE n > 1",
+        path
+    );
+
+    let data = CsvConverter::query_files(&sql, &QueryOptions::default()).unwrap();
+    assert_eq!(data.headers, ["it's"]);
+    assert_eq!(data.records.len(), 1);
+    assert_eq!(&data.records[0][0], "2");
+}
@@ -0,0 +1,116 @@
+use csv::StringRecord;
+use csv_converter::{CsvConverter, GeoJsonOptions, TemplateOptions, ValueOptions};
+use serde_json::{json, Value};
+
+fn path(dir: &tempfile::TempDir, name: &str) -> String {
+    dir.path().join(name).to_string_lossy().into_owned()
+}
+
+fn read(csv: &str) -> (Vec<String>, Vec<StringRecord>) {
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let headers = reader.headers().unwrap().iter().map(|h| h.to_string()).collect();
+    (headers, reader.records().collect::<Result<_, _>>().unwrap())
+}
+
+fn file_len(path: &str) -> u64 {
+    std::fs::metadata(path).unwrap().len()
+}
+
+#[test]
+fn counts_rows_and_bytes() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = path(&dir, "points.csv");
+    let output = path(&dir, "points.geojson");
+    std::fs::write(&input, "name,lat,lon\na,25,121\nb,95,121\nc,-33.9,18.4\n").unwrap();
+
+    let report = CsvConverter::convert_csv_to_geojson_file(&input, &output, &GeoJsonOptions::default()).unwrap();
+    assert_eq!((report.rows_read, report.rows_written, report.rows_rejected), (3, 2, 1));
+    assert_eq!(report.bytes_in, file_len(&input));
+    assert_eq!(report.bytes_out, file_len(&output));
+
+    // 直接轉換已讀入的記錄時不知道輸入大小
+    let (headers, records) = read("id\n1\n2\n");
+    let report = CsvConverter::write_json_file(&headers, &records, &output, &ValueOptions::default()).unwrap();
+    assert_eq!((report.rows_read, report.rows_written, report.rows_rejected), (2, 2, 0));
+    assert_eq!(report.bytes_in, 0);
+    assert_eq!(report.bytes_out, file_len(&output));
+}
+
+#[test]
+fn warns_once_per_duplicate_header() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = path(&dir, "out.json");
+    let (headers, records) = read("a,b,a,a,b,c\n1,2,3,4,5,6\n");
+
+    let report = CsvConverter::write_json_file(&headers, &records, &output, &ValueOptions::default()).unwrap();
+    assert_eq!(report.warnings.len(), 2, "{:?}", report.warnings);
+    assert!(report.warnings[0].contains("欄位名稱 a 重複"), "{:?}", report.warnings);
+    assert!(report.warnings[1].contains("欄位名稱 b 重複"), "{:?}", report.warnings);
+
+    let (headers, records) = read("a,b\n1,2\n");
+    let report = CsvConverter::write_json_file(&headers, &records, &output, &ValueOptions::default()).unwrap();
+    assert!(report.warnings.is_empty());
+}
+
+#[test]
+fn output_size_sums_every_written_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let (headers, records) = read("id,name\n1,Ann\n2,Bob\n");
+    let options = TemplateOptions {
+        template: "name: {{name}}\n".to_string(),
+        output: format!("{}/{{{{id}}}}.yaml", path(&dir, "out")),
+        ..Default::default()
+    };
+
+    let report = CsvConverter::render_template(&headers, &records, &options).unwrap();
+    let written = file_len(&path(&dir, "out/1.yaml")) + file_len(&path(&dir, "out/2.yaml"));
+    assert_eq!(report.bytes_out, written);
+    assert_eq!(report.rows_written, 2);
+}
+
+#[test]
+fn serializes_to_a_stable_shape() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = path(&dir, "out.json");
+    let (headers, records) = read("id,name\n1,Ann\n2,\n");
+    let report = CsvConverter::write_json_file(&headers, &records, &output, &ValueOptions::default()).unwrap();
+
+    let json: Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+    let mut keys: Vec<&str> = json.as_object().unwrap().keys().map(String::as_str).collect();
+    keys.sort();
+    assert_eq!(
+        keys,
+        ["bytes_in", "bytes_out", "elapsed_ms", "rows_read", "rows_rejected", "rows_written", "schema", "warnings"]
+    );
+    assert_eq!(json["rows_read"], 2);
+    assert_eq!(json["bytes_out"], file_len(&output));
+    assert!(json["elapsed_ms"].is_u64());
+    assert_eq!(json["warnings"], json!([]));
+    assert_eq!(
+        json["schema"],
+        json!({ "columns": [
+            { "name": "id", "column_type": "integer", "nullable": false, "rules": [] },
+            { "name": "name", "column_type": "string", "nullable": true, "rules": [] },
+        ] })
+    );
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn output_size_of_a_directory_counts_only_new_parts() {
+    use csv_converter::IncrementalOptions;
+    use std::path::Path;
+
+    let dir = tempfile::tempdir().unwrap();
+    let input = path(&dir, "events.csv");
+    let output = path(&dir, "out.parquet");
+    let part = |n: usize| file_len(&Path::new(&output).join(format!("part-{:05}.parquet", n)).to_string_lossy());
+    std::fs::write(&input, "id,name\n1,Ann\n2,Bob\n").unwrap();
+
+    let summary = CsvConverter::convert_csv_incremental(&input, &output, &IncrementalOptions::default()).unwrap();
+    assert_eq!(summary.report.bytes_out, part(0));
+
+    std::fs::write(&input, "id,name\n1,Ann\n2,Bob\n3,Cy\n").unwrap();
+    let summary = CsvConverter::convert_csv_incremental(&input, &output, &IncrementalOptions::default()).unwrap();
+    assert_eq!(summary.report.bytes_out, part(1));
+}
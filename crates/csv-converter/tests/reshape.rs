@@ -0,0 +1,100 @@
+use csv_converter::{Aggregate, CsvConverter, Dialect, PivotOptions, UnpivotOptions};
+
+fn pivot(csv: &str, sorted: bool) -> anyhow::Result<String> {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("long.csv").to_string_lossy().into_owned();
+    let output = dir.path().join("wide.csv").to_string_lossy().into_owned();
+    std::fs::write(&input, csv).unwrap();
+    let options = PivotOptions {
+        index: vec!["id".to_string()],
+        columns: "metric".to_string(),
+        values: "value".to_string(),
+        aggregate: Aggregate::Sum,
+        sorted,
+        ..Default::default()
+    };
+    CsvConverter::pivot_csv_file(&input, &output, &options)?;
+    Ok(std::fs::read_to_string(&output).unwrap())
+}
+
+#[test]
+fn sorted_mode_accepts_numeric_key_order() {
+    let csv = "id,metric,value\n9,a,1\n9,b,2\n10,a,3\n10,a,4\nx,b,5\n";
+    let expected = "id,a,b\n9,1,2\n10,7,\nx,,5\n";
+    assert_eq!(pivot(csv, true).unwrap(), expected);
+    // 一般模式的輸出順序與 sorted 模式一致
+    assert_eq!(pivot(csv, false).unwrap(), expected);
+}
+
+#[test]
+fn sorted_mode_rejects_unsorted_keys() {
+    let csv = "id,metric,value\n10,a,1\n9,a,2\n";
+    let error = pivot(csv, true).unwrap_err();
+    assert!(error.to_string().contains("第 3 行"), "{}", error);
+
+    // 同一索引分散在不相鄰的位置
+    let csv = "id,metric,value\nx,a,1\ny,a,2\nx,a,3\n";
+    assert!(pivot(csv, true).is_err());
+}
+
+#[test]
+fn unpivot_round_trips_pivot() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("wide.csv").to_string_lossy().into_owned();
+    let output = dir.path().join("long.csv").to_string_lossy().into_owned();
+    std::fs::write(&input, "id,a,b\n1,5,\n2,6,7\n").unwrap();
+    let options = UnpivotOptions {
+        id_columns: vec!["id".to_string()],
+        skip_empty: true,
+        ..Default::default()
+    };
+
+    let report = CsvConverter::unpivot_csv_file(&input, &output, &options).unwrap();
+    assert_eq!(report.rows_written, 3);
+    let long = std::fs::read_to_string(&output).unwrap();
+    assert_eq!(long, "id,variable,value\n1,a,5\n2,a,6\n2,b,7\n");
+    assert_eq!(pivot(&long.replace("variable", "metric"), true).unwrap(), "id,a,b\n1,5,\n2,6,7\n");
+}
+
+#[test]
+fn pivot_columns_use_the_key_order() {
+    let csv = "id,metric,value\n1,10,1\n1,9,2\n1,x,3\n1,2.5,4\n1,-1,5\n";
+    let expected = "id,-1,2.5,9,10,x\n1,5,4,2,1,3\n";
+    assert_eq!(pivot(csv, false).unwrap(), expected);
+    assert_eq!(pivot(csv, true).unwrap(), expected);
+}
+
+#[test]
+fn reshape_reads_with_the_input_dialect() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("out.csv").to_string_lossy().into_owned();
+    let options = PivotOptions {
+        index: vec!["id".to_string()],
+        columns: "metric".to_string(),
+        values: "value".to_string(),
+        ..Default::default()
+    };
+
+    // 依副檔名以 tab 分隔
+    let tsv = dir.path().join("long.tsv").to_string_lossy().into_owned();
+    std::fs::write(&tsv, "id\tmetric\tvalue\n1\ta\t2,5\n").unwrap();
+    CsvConverter::pivot_csv_file(&tsv, &output, &PivotOptions { aggregate: Aggregate::First, ..options.clone() })
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), "id,a\n1,\"2,5\"\n");
+
+    let input = dir.path().join("long.csv").to_string_lossy().into_owned();
+    std::fs::write(&input, "id;metric;value\n1;'a;b';2\n1;'a;b';3\n").unwrap();
+    let dialect = Dialect { delimiter: b';', quote: b'\'', comment: None };
+    let pivot_options = PivotOptions { dialect: Some(dialect), ..options };
+    CsvConverter::pivot_csv_file(&input, &output, &pivot_options).unwrap();
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), "id,a;b\n1,5\n");
+
+    std::fs::write(&input, "id;a;b\n1;'x;y';2\n").unwrap();
+    let unpivot_options = UnpivotOptions {
+        id_columns: vec!["id".to_string()],
+        dialect: Some(dialect),
+        ..Default::default()
+    };
+    CsvConverter::unpivot_csv_file(&input, &output, &unpivot_options).unwrap();
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), "id,variable,value\n1,a,x;y\n1,b,2\n");
+}
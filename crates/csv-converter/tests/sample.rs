@@ -0,0 +1,58 @@
+use csv_converter::{CsvConverter, SampleOptions, Sampling};
+
+fn tail(csv: &str, count: usize) -> Vec<Vec<String>> {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("input.csv").to_string_lossy().into_owned();
+    std::fs::write(&path, csv).unwrap();
+    let options = SampleOptions { method: Sampling::Tail(count), ..Default::default() };
+    let (_, records) = CsvConverter::sample_csv_file(&path, &options).unwrap();
+    records.iter().map(|r| r.iter().map(String::from).collect()).collect()
+}
+
+#[test]
+fn tail_ignores_trailing_blank_lines() {
+    let csv = "id,name\n1,a\n2,b\n\n3,c\r\n\r\n\n\n";
+    assert_eq!(tail(csv, 2), [["2", "b"], ["3", "c"]]);
+    assert_eq!(tail(csv, 3), [["1", "a"], ["2", "b"], ["3", "c"]]);
+    assert_eq!(tail(csv, 5).len(), 3);
+    assert!(tail(csv, 0).is_empty());
+}
+
+#[test]
+fn tail_handles_quoted_newlines() {
+    // 最後一列的欄位內含換行，切在換行處仍會得到兩個欄位
+    let csv = "id,note\n1,a\n2,\"x\n3,y\"\n4,b\n";
+    assert_eq!(tail(csv, 2), [["2", "x\n3,y"], ["4", "b"]]);
+    assert_eq!(tail(csv, 1), [["4", "b"]]);
+    let csv = "id,note\n1,a\n2,\"x\n3,y\"\n";
+    assert_eq!(tail(csv, 1), [["2", "x\n3,y"]]);
+}
+
+#[test]
+fn tail_matches_streaming_on_large_input() {
+    let mut csv = String::from("n,text\n");
+    for i in 0..20_000 {
+        csv.push_str(&format!("{},row {}\n", i, i));
+    }
+    let rows = tail(&csv, 3);
+    assert_eq!(rows, [["19997", "row 19997"], ["19998", "row 19998"], ["19999", "row 19999"]]);
+}
+
+#[test]
+fn every_nth_rejects_zero_step() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("input.csv").to_string_lossy().into_owned();
+    std::fs::write(&path, "id\n1\n2\n3\n").unwrap();
+    let headers = vec!["id".to_string()];
+    let records = vec![csv::StringRecord::from(vec!["1"]), csv::StringRecord::from(vec!["2"])];
+
+    let options = SampleOptions { method: Sampling::EveryNth(0), ..Default::default() };
+    assert!(CsvConverter::sample_csv_file(&path, &options).is_err());
+    assert!(CsvConverter::sample_records(&headers, records.clone(), &options).is_err());
+    assert!("every:0".parse::<Sampling>().is_err());
+
+    let options = SampleOptions { method: Sampling::EveryNth(2), ..Default::default() };
+    let (_, sampled) = CsvConverter::sample_csv_file(&path, &options).unwrap();
+    assert_eq!(sampled, vec![csv::StringRecord::from(vec!["1"]), csv::StringRecord::from(vec!["3"])]);
+    assert_eq!(CsvConverter::sample_records(&headers, records, &options).unwrap().len(), 1);
+}
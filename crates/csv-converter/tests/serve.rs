@@ -0,0 +1,207 @@
+#![cfg(feature = "serve")]
+
+use csv_converter::{CsvConverter, Dialect, Schema, ServeOptions, ValueOptions};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+/// 在系統挑選的連接埠啟動服務，回傳位址與結束服務用的 sender
+async fn start(options: ServeOptions) -> (SocketAddr, oneshot::Sender<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (stop, stopped) = oneshot::channel::<()>();
+    tokio::spawn(CsvConverter::serve_http(listener, options, async {
+        stopped.await.ok();
+    }));
+    (addr, stop)
+}
+
+struct Reply {
+    status: u16,
+    headers: Vec<(String, String)>,
+    /// 本文可能是 Parquet 等二進位格式
+    body: Vec<u8>,
+}
+
+impl Reply {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+
+    fn text(&self) -> &str {
+        std::str::from_utf8(&self.body).expect("回應本文不是 UTF-8")
+    }
+
+    fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap()
+    }
+}
+
+enum Payload<'a> {
+    Sized(&'a str),
+    Chunked(&'a [&'a str]),
+}
+
+/// 以 HTTP/1.1 送出請求並讀到連線關閉，chunked 回應會先解碼
+async fn request(addr: SocketAddr, path: &str, accept: Option<&str>, payload: Payload<'_>) -> Reply {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let mut head = format!("POST {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n", path, addr);
+    if let Some(accept) = accept {
+        head.push_str(&format!("Accept: {}\r\n", accept));
+    }
+    let body = match payload {
+        Payload::Sized(body) => {
+            head.push_str(&format!("Content-Length: {}\r\n\r\n", body.len()));
+            body.to_string()
+        }
+        Payload::Chunked(chunks) => {
+            head.push_str("Transfer-Encoding: chunked\r\n\r\n");
+            let mut body: String = chunks.iter().map(|c| format!("{:x}\r\n{}\r\n", c.len(), c)).collect();
+            body.push_str("0\r\n\r\n");
+            body
+        }
+    };
+    stream.write_all(head.as_bytes()).await.unwrap();
+    // 伺服器可能在讀完本文前就回應 413 並關閉連線
+    let _ = stream.write_all(body.as_bytes()).await;
+
+    let mut raw = Vec::new();
+    let _ = stream.read_to_end(&mut raw).await;
+    let end = raw.windows(4).position(|w| w == b"\r\n\r\n").expect("回應沒有完整的標頭");
+    let head = String::from_utf8_lossy(&raw[..end]);
+    let body = &raw[end + 4..];
+    let mut lines = head.lines();
+    let status = lines.next().unwrap().split(' ').nth(1).unwrap().parse().unwrap();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+    let chunked = headers.iter().any(|(n, v)| n.eq_ignore_ascii_case("transfer-encoding") && v == "chunked");
+    let body = if chunked { dechunk(body) } else { body.to_vec() };
+    Reply { status, headers, body }
+}
+
+fn dechunk(mut raw: &[u8]) -> Vec<u8> {
+    let mut body = Vec::new();
+    loop {
+        let line_end = raw.windows(2).position(|w| w == b"\r\n").expect("回應在 chunk 中途結束");
+        let size = std::str::from_utf8(&raw[..line_end]).unwrap();
+        let size = usize::from_str_radix(size.trim(), 16).unwrap();
+        if size == 0 {
+            return body;
+        }
+        let rest = &raw[line_end + 2..];
+        body.extend_from_slice(&rest[..size]);
+        raw = &rest[size + 2..];
+    }
+}
+
+const CSV: &str = "id,name\n1,Ann\n2,Bob\n";
+
+#[tokio::test]
+async fn converts_to_json_and_ndjson_as_a_stream() {
+    let (addr, _stop) = start(ServeOptions { buffer_size: 8, ..Default::default() }).await;
+
+    let reply = request(addr, "/convert", None, Payload::Sized(CSV)).await;
+    assert_eq!(reply.status, 200);
+    assert_eq!(reply.header("content-type"), Some("application/json"));
+    // 邊讀邊寫，因此沒有 Content-Length
+    assert_eq!(reply.header("transfer-encoding"), Some("chunked"));
+    assert_eq!(reply.json(), json!([{ "id": 1, "name": "Ann" }, { "id": 2, "name": "Bob" }]));
+
+    let reply = request(addr, "/convert", Some("application/x-ndjson"), Payload::Chunked(&["id,na", "me\n1,Ann\n2,Bob\n"])).await;
+    assert_eq!(reply.status, 200);
+    assert_eq!(reply.header("content-type"), Some("application/x-ndjson"));
+    let lines: Vec<Value> = reply.text().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(lines, [json!({ "id": 1, "name": "Ann" }), json!({ "id": 2, "name": "Bob" })]);
+}
+
+#[tokio::test]
+async fn accept_header_is_negotiated() {
+    let (addr, _stop) = start(ServeOptions::default()).await;
+    let cases = [
+        ("application/json;q=0, */*", Some("application/x-ndjson")),
+        ("*/*;q=0.5, application/x-ndjson", Some("application/x-ndjson")),
+        ("application/x-ndjson;q=0.2, application/json;q=0.8", Some("application/json")),
+        ("application/*", Some("application/json")),
+        // 啟用 parquet 時萬用字元會選到剩下的 Parquet
+        #[cfg(not(feature = "parquet"))]
+        ("application/json;q=0, application/x-ndjson;q=0, */*", None),
+        #[cfg(feature = "parquet")]
+        ("application/json;q=0, application/x-ndjson;q=0, */*", Some("application/vnd.apache.parquet")),
+        ("text/html", None),
+    ];
+    for (accept, expected) in cases {
+        let reply = request(addr, "/convert", Some(accept), Payload::Sized(CSV)).await;
+        match expected {
+            Some(content_type) => {
+                assert_eq!(reply.status, 200, "{}", accept);
+                assert_eq!(reply.header("content-type"), Some(content_type), "{}", accept);
+            }
+            None => assert_eq!(reply.status, 406, "{}", accept),
+        }
+    }
+}
+
+#[cfg(feature = "parquet")]
+#[tokio::test]
+async fn parquet_is_returned_when_asked_by_name() {
+    let (addr, _stop) = start(ServeOptions::default()).await;
+    for accept in ["application/vnd.apache.parquet", "application/x-parquet"] {
+        let reply = request(addr, "/convert", Some(accept), Payload::Sized(CSV)).await;
+        assert_eq!(reply.status, 200, "{}", accept);
+        assert_eq!(reply.header("content-type"), Some("application/vnd.apache.parquet"));
+        assert!(reply.body.starts_with(b"PAR1") && reply.body.ends_with(b"PAR1"), "{}", accept);
+    }
+    // /profile 不提供 Parquet
+    let reply = request(addr, "/profile", Some("application/vnd.apache.parquet"), Payload::Sized(CSV)).await;
+    assert_eq!(reply.status, 406);
+}
+
+#[tokio::test]
+async fn oversized_bodies_are_rejected() {
+    let (addr, _stop) = start(ServeOptions { max_body_bytes: 16, ..Default::default() }).await;
+    let large = "id\n".to_string() + &"1234567890\n".repeat(10);
+
+    for path in ["/convert", "/profile"] {
+        let reply = request(addr, path, None, Payload::Sized(&large)).await;
+        assert_eq!(reply.status, 413, "{} Content-Length", path);
+        assert!(reply.json()["error"].as_str().unwrap().contains("16"));
+
+        // 沒有 Content-Length 時讀到超過上限為止
+        let reply = request(addr, path, None, Payload::Chunked(&["id\n1\n", &large])).await;
+        assert_eq!(reply.status, 413, "{} chunked", path);
+    }
+
+    let reply = request(addr, "/convert", None, Payload::Sized("id\n1\n")).await;
+    assert_eq!(reply.status, 200);
+}
+
+#[tokio::test]
+async fn validate_reports_violations_with_422() {
+    let (headers, records) = CsvConverter::read_csv("id,name\n1,Ann\n".as_bytes(), &Dialect::default()).unwrap();
+    let schema = Schema::infer_with(&headers, &records, &ValueOptions::default());
+    let (addr, _stop) = start(ServeOptions { schema: Some(schema), ..Default::default() }).await;
+
+    let reply = request(addr, "/validate", None, Payload::Sized("id,name\n2,Bob\n")).await;
+    assert_eq!(reply.status, 200);
+    assert_eq!(reply.json(), json!([]));
+
+    let reply = request(addr, "/validate", None, Payload::Sized("id,name\nx,Bob\n")).await;
+    assert_eq!(reply.status, 422);
+    assert_eq!(reply.json().as_array().unwrap().len(), 1);
+
+    let reply = request(addr, "/validate", Some("application/x-ndjson"), Payload::Sized("id,name\nx,Bob\ny,Cy\n")).await;
+    assert_eq!(reply.status, 422);
+    assert_eq!(reply.text().lines().count(), 2);
+}
+
+#[tokio::test]
+async fn validate_without_schema_is_not_found() {
+    let (addr, stop) = start(ServeOptions::default()).await;
+    let reply = request(addr, "/validate", None, Payload::Sized(CSV)).await;
+    assert_eq!(reply.status, 404);
+    stop.send(()).unwrap();
+}
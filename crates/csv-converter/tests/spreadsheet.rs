@@ -0,0 +1,149 @@
+use csv_converter::{
+    CsvConverter, NumericPolicy, SheetSelector, SpreadsheetOptions, ValueOptions, XlsxExportOptions,
+};
+use rust_xlsxwriter::{ExcelDateTime, Format, Workbook};
+use tempfile::TempDir;
+
+/// 產生測試用的活頁簿：第一個工作表是封面，資料放在第二個工作表的 B2 起，
+/// 包含空白標題、日期儲存格與一列空白列
+fn fixture() -> (TempDir, String) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("fixture.xlsx").to_string_lossy().into_owned();
+    let mut workbook = Workbook::new();
+
+    let cover = workbook.add_worksheet();
+    cover.set_name("cover").unwrap();
+    cover.write_string(0, 0, "quarterly report").unwrap();
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("people").unwrap();
+    let date = Format::new().set_num_format("yyyy-mm-dd");
+    for (col, header) in ["id", "name", "joined", ""].iter().enumerate() {
+        sheet.write_string(1, col as u16 + 1, *header).unwrap();
+    }
+    let rows = [(1, "Ann", (2024, 1, 31)), (2, "Bob", (2023, 12, 1)), (3, "Cy", (2022, 6, 15))];
+    for (i, (id, name, (y, m, d))) in rows.iter().enumerate() {
+        // 第二筆資料後留一列空白
+        let row = if i < 2 { i as u32 + 2 } else { i as u32 + 3 };
+        sheet.write_number(row, 1, *id as f64).unwrap();
+        sheet.write_string(row, 2, *name).unwrap();
+        let joined = ExcelDateTime::from_ymd(*y, *m, *d).unwrap();
+        sheet.write_datetime_with_format(row, 3, &joined, &date).unwrap();
+        sheet.write_boolean(row, 4, i % 2 == 0).unwrap();
+    }
+
+    workbook.save(&path).unwrap();
+    (dir, path)
+}
+
+fn read(path: &str, sheet: SheetSelector, range: Option<&str>) -> anyhow::Result<(Vec<String>, Vec<Vec<String>>)> {
+    let options = SpreadsheetOptions { sheet, range: range.map(String::from) };
+    let (headers, records) = CsvConverter::read_spreadsheet_file(path, &options)?;
+    let rows = records.iter().map(|r| r.iter().map(String::from).collect()).collect();
+    Ok((headers, rows))
+}
+
+fn people() -> SheetSelector {
+    SheetSelector::Name("people".to_string())
+}
+
+#[test]
+fn reads_sheet_by_name_or_index() {
+    let (_dir, path) = fixture();
+
+    let (headers, rows) = read(&path, people(), None).unwrap();
+    assert_eq!(headers, ["id", "name", "joined", "column_4"]);
+    assert_eq!(
+        rows,
+        [
+            ["1", "Ann", "2024-01-31", "true"],
+            ["2", "Bob", "2023-12-01", "false"],
+            ["3", "Cy", "2022-06-15", "true"],
+        ]
+    );
+    assert_eq!(read(&path, SheetSelector::Index(1), None).unwrap(), (headers, rows));
+
+    let (headers, rows) = read(&path, SheetSelector::Index(0), None).unwrap();
+    assert_eq!(headers, ["quarterly report"]);
+    assert!(rows.is_empty());
+}
+
+#[test]
+fn missing_sheet_is_an_error() {
+    let (_dir, path) = fixture();
+    assert!(read(&path, SheetSelector::Name("nope".to_string()), None).is_err());
+    assert!(read(&path, SheetSelector::Index(5), None).is_err());
+}
+
+#[test]
+fn range_crops_to_block() {
+    let (_dir, path) = fixture();
+
+    let (headers, rows) = read(&path, people(), Some("C2:D4")).unwrap();
+    assert_eq!(headers, ["name", "joined"]);
+    assert_eq!(rows, [["Ann", "2024-01-31"], ["Bob", "2023-12-01"]]);
+
+    // 只給左上角時讀到資料結尾
+    let (headers, rows) = read(&path, people(), Some("c3")).unwrap();
+    assert_eq!(headers, ["Ann", "2024-01-31", "true"]);
+    assert_eq!(rows, [["Bob", "2023-12-01", "false"], ["Cy", "2022-06-15", "true"]]);
+}
+
+#[test]
+fn range_end_is_clamped_to_data() {
+    let (_dir, path) = fixture();
+    let whole = read(&path, people(), None).unwrap();
+    assert_eq!(read(&path, people(), Some("B2:XFD1048576")).unwrap(), whole);
+    // 起點在資料左上方也只讀到資料範圍
+    let (headers, rows) = read(&path, people(), Some("A1:E6")).unwrap();
+    assert_eq!(headers, ["column_1", "column_2", "column_3", "column_4", "column_5"]);
+    assert_eq!(rows[0], ["", "id", "name", "joined", ""]);
+    assert_eq!(rows.len(), 4);
+}
+
+#[test]
+fn range_beyond_data_is_empty() {
+    let (_dir, path) = fixture();
+    for range in ["Z1000", "Z1000:ZZ2000", "B100", "G2"] {
+        let (headers, rows) = read(&path, people(), Some(range)).unwrap();
+        assert!(headers.is_empty() && rows.is_empty(), "{}", range);
+    }
+}
+
+#[test]
+fn invalid_ranges_are_rejected() {
+    let (_dir, path) = fixture();
+    for range in ["F100:B2", "B1:A5", "A5:B1", "AAAAAAAA1", "XFE1", "A0", "A1048577", "1A", "B2:"] {
+        assert!(read(&path, people(), Some(range)).is_err(), "{}", range);
+    }
+}
+
+#[test]
+fn xlsx_export_round_trips_typed_cells() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("out.xlsx").to_string_lossy().into_owned();
+    let csv = "id,name,score,active,big\n1,Ann,9.5,true,12345678901234567890\n2,,0.25,false,\n";
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let headers: Vec<String> = reader.headers().unwrap().iter().map(|h| h.to_string()).collect();
+    let records: Vec<csv::StringRecord> = reader.records().collect::<Result<_, _>>().unwrap();
+    let options = XlsxExportOptions {
+        include_schema: true,
+        values: ValueOptions { numeric: NumericPolicy::Preserve, ..Default::default() },
+        ..Default::default()
+    };
+
+    let report = CsvConverter::write_xlsx_file(&headers, &records, &path, &options).unwrap();
+    assert_eq!(report.rows_written, 2);
+    assert!(report.warnings.iter().any(|w| w.contains("以文字儲存")), "{:?}", report.warnings);
+
+    let (read_headers, read_records) =
+        CsvConverter::read_spreadsheet_file(&path, &SpreadsheetOptions::default()).unwrap();
+    assert_eq!(read_headers, headers);
+    assert_eq!(read_records, records);
+
+    let schema = SpreadsheetOptions { sheet: SheetSelector::Name("schema".to_string()), range: None };
+    let (schema_headers, schema_rows) = CsvConverter::read_spreadsheet_file(&path, &schema).unwrap();
+    assert_eq!(schema_headers, ["name", "type", "nullable", "rules"]);
+    assert_eq!(schema_rows.len(), headers.len());
+    assert_eq!(&schema_rows[2][1], "float");
+}
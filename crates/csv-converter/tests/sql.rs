@@ -0,0 +1,159 @@
+use csv::StringRecord;
+use csv_converter::{CsvConverter, NumericPolicy, SqlExportOptions, ValueOptions};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::Connection;
+
+const CSV: &str = "\
+id,name,score,active,note
+1,O'Brien,9.5,true,\"say \"\"hi\"\"\"
+2,Ann,,false,
+3,\"line
+break\",7,true,-- not a comment
+";
+
+fn table(csv: &str) -> (Vec<String>, Vec<StringRecord>) {
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let headers = reader.headers().unwrap().iter().map(|h| h.to_string()).collect();
+    (headers, reader.records().collect::<Result<_, _>>().unwrap())
+}
+
+fn rows(conn: &Connection, sql: &str) -> Vec<Vec<SqlValue>> {
+    let mut stmt = conn.prepare(sql).unwrap();
+    let columns = stmt.column_count();
+    stmt.query_map([], |row| (0..columns).map(|i| row.get::<_, SqlValue>(i)).collect())
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+fn expected_rows() -> Vec<Vec<SqlValue>> {
+    use SqlValue::{Integer, Null, Real, Text};
+    vec![
+        vec![Integer(1), Text("O'Brien".into()), Real(9.5), Integer(1), Text("say \"hi\"".into())],
+        vec![Integer(2), Text("Ann".into()), Null, Integer(0), Null],
+        vec![Integer(3), Text("line\nbreak".into()), Real(7.0), Integer(1), Text("-- not a comment".into())],
+    ]
+}
+
+#[test]
+fn sqlite_export_creates_typed_table_and_indexes() {
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = dir.path().join("out.db").to_string_lossy().into_owned();
+    let (headers, records) = table(CSV);
+    let options = SqlExportOptions {
+        indexes: vec!["name".to_string()],
+        ..SqlExportOptions::new("people \"2024\"")
+    };
+
+    let report = CsvConverter::write_sqlite_file(&headers, &records, &db_path, &options).unwrap();
+    assert_eq!(report.rows_written, 3);
+
+    let conn = Connection::open(&db_path).unwrap();
+    let declared: Vec<(String, String, bool)> = conn
+        .prepare("SELECT name, type, \"notnull\" FROM pragma_table_info('people \"2024\"')")
+        .unwrap()
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        declared,
+        [
+            ("id".to_string(), "INTEGER".to_string(), true),
+            ("name".to_string(), "TEXT".to_string(), true),
+            ("score".to_string(), "REAL".to_string(), false),
+            ("active".to_string(), "INTEGER".to_string(), true),
+            ("note".to_string(), "TEXT".to_string(), false),
+        ]
+    );
+    assert_eq!(rows(&conn, "SELECT * FROM \"people \"\"2024\"\"\" ORDER BY id"), expected_rows());
+
+    let index: String = conn
+        .query_row("SELECT name FROM sqlite_master WHERE type = 'index'", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(index, "idx_people \"2024\"_name");
+}
+
+#[test]
+fn sqlite_export_replaces_existing_table() {
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = dir.path().join("out.db").to_string_lossy().into_owned();
+    let options = SqlExportOptions::default();
+
+    let (headers, records) = table("a\n1\n2\n3\n");
+    CsvConverter::write_sqlite_file(&headers, &records, &db_path, &options).unwrap();
+    let (headers, records) = table("a\n9\n");
+    CsvConverter::write_sqlite_file(&headers, &records, &db_path, &options).unwrap();
+
+    let conn = Connection::open(&db_path).unwrap();
+    assert_eq!(rows(&conn, "SELECT a FROM data"), vec![vec![SqlValue::Integer(9)]]);
+}
+
+#[test]
+fn sqlite_export_rejects_unknown_index_column() {
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = dir.path().join("out.db").to_string_lossy().into_owned();
+    let (headers, records) = table(CSV);
+    let options = SqlExportOptions {
+        indexes: vec!["missing".to_string()],
+        ..Default::default()
+    };
+
+    let error = CsvConverter::write_sqlite_file(&headers, &records, &db_path, &options).unwrap_err();
+    assert!(error.to_string().contains("missing"), "{}", error);
+}
+
+#[test]
+fn sql_script_escapes_values_and_loads_into_sqlite() {
+    let dir = tempfile::tempdir().unwrap();
+    let sql_path = dir.path().join("out.sql").to_string_lossy().into_owned();
+    let (headers, records) = table(CSV);
+    // 每批兩列，確認跨批次的 INSERT 都正確結尾
+    let options = SqlExportOptions {
+        batch_size: 2,
+        indexes: vec!["id".to_string()],
+        ..SqlExportOptions::new("it's")
+    };
+
+    CsvConverter::write_sql_script(&headers, &records, &sql_path, &options).unwrap();
+    let script = std::fs::read_to_string(&sql_path).unwrap();
+    assert!(script.contains("'O''Brien'"), "{}", script);
+    assert_eq!(script.matches("INSERT INTO").count(), 2);
+
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(&script).unwrap();
+    assert_eq!(rows(&conn, "SELECT * FROM \"it's\" ORDER BY id"), expected_rows());
+}
+
+#[test]
+fn integers_beyond_i64_stay_exact_when_preserved() {
+    let dir = tempfile::tempdir().unwrap();
+    let sql_path = dir.path().join("out.sql").to_string_lossy().into_owned();
+    let db_path = dir.path().join("out.db").to_string_lossy().into_owned();
+    let (headers, records) = table("n\n1\n123456789012345678901234567890\n");
+    let options = SqlExportOptions {
+        values: ValueOptions { numeric: NumericPolicy::Preserve, ..Default::default() },
+        ..Default::default()
+    };
+
+    CsvConverter::write_sql_script(&headers, &records, &sql_path, &options).unwrap();
+    CsvConverter::write_sqlite_file(&headers, &records, &db_path, &options).unwrap();
+
+    let from_script = Connection::open_in_memory().unwrap();
+    from_script.execute_batch(&std::fs::read_to_string(&sql_path).unwrap()).unwrap();
+    for conn in [from_script, Connection::open(&db_path).unwrap()] {
+        // 數值欄位中有超出 i64 的整數時整欄宣告為 TEXT，SQLite 才不會轉回浮點數
+        let declared: String = conn
+            .query_row("SELECT type FROM pragma_table_info('data')", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(declared, "TEXT");
+        let values: Vec<String> = conn
+            .prepare("SELECT n FROM data ORDER BY rowid")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(values, ["1", "123456789012345678901234567890"]);
+    }
+}
@@ -0,0 +1,95 @@
+use csv_converter::table::truncate_to_width;
+use csv_converter::{CsvConverter, TableOptions};
+use unicode_width::UnicodeWidthStr;
+
+const CSV: &str = "name,qty,ok\nApple,3,true\n\"a|b <i> & c\",12,false\n\"two\nlines\",7,true\n";
+
+fn read(csv: &str) -> (Vec<String>, Vec<csv::StringRecord>) {
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let headers = reader.headers().unwrap().iter().map(|h| h.to_string()).collect();
+    (headers, reader.records().collect::<Result<_, _>>().unwrap())
+}
+
+#[test]
+fn markdown_aligns_by_type_and_escapes_cells() {
+    let (headers, records) = read(CSV);
+    let markdown = CsvConverter::render_markdown_table(&headers, &records, &TableOptions::default());
+    assert_eq!(
+        markdown,
+        "| name | qty | ok |\n\
+         | :--- | ---: | :---: |\n\
+         | Apple | 3 | true |\n\
+         | a\\|b &lt;i&gt; &amp; c | 12 | false |\n\
+         | two<br>lines | 7 | true |\n"
+    );
+
+    let options = TableOptions { max_rows: Some(1), ..Default::default() };
+    let markdown = CsvConverter::render_markdown_table(&headers, &records, &options);
+    assert_eq!(markdown.lines().count(), 3);
+    // 已是實體的文字也會再跳脫，顯示原本的字面
+    let (headers, records) = read("a\n&lt;\n");
+    let markdown = CsvConverter::render_markdown_table(&headers, &records, &TableOptions::default());
+    assert!(markdown.ends_with("| &amp;lt; |\n"), "{}", markdown);
+}
+
+#[test]
+fn html_escapes_cells_and_title() {
+    let (headers, records) = read(CSV);
+    let options = TableOptions { title: Some("<Fruit & \"Co\">".to_string()), ..Default::default() };
+    let html = CsvConverter::render_html_table(&headers, &records, &options);
+    assert!(html.contains("<title>&lt;Fruit &amp; &quot;Co&quot;&gt;</title>"), "{}", html);
+    assert!(html.contains(
+        "<tr><td style=\"text-align: left\">a|b &lt;i&gt; &amp; c</td>\
+         <td style=\"text-align: right\">12</td>\
+         <td style=\"text-align: center\">false</td></tr>"
+    ));
+    assert!(html.contains("<th style=\"text-align: right\">qty</th>"));
+    assert_eq!(html.matches("<tr>").count(), 4);
+    assert!(html.ends_with("</table>\n</body>\n</html>\n"));
+}
+
+#[test]
+fn terminal_table_pads_by_display_width() {
+    let (headers, records) = read("城市,人口\n臺北,2500000\nNY,8000000\n");
+    let table = CsvConverter::render_terminal_table(&headers, &records, &TableOptions::default());
+    assert_eq!(
+        table,
+        "┌──────┬─────────┐\n\
+         │ 城市 │ 人口    │\n\
+         ├──────┼─────────┤\n\
+         │ 臺北 │ 2500000 │\n\
+         │ NY   │ 8000000 │\n\
+         └──────┴─────────┘\n"
+    );
+    // 每一行的顯示寬度都相同
+    let widths: Vec<usize> = table.lines().map(|line| line.width()).collect();
+    assert!(widths.iter().all(|w| *w == widths[0]), "{:?}", widths);
+}
+
+#[test]
+fn terminal_table_truncates_and_flattens_newlines() {
+    let (headers, records) = read(CSV);
+    let options = TableOptions { max_width: Some(6), ..Default::default() };
+    let table = CsvConverter::render_terminal_table(&headers, &records, &options);
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines[3], "│ Apple  │   3 │ true  │");
+    assert_eq!(lines[4], "│ a|b <… │  12 │ false │");
+    assert_eq!(lines[5], "│ two l… │   7 │ true  │");
+}
+
+#[test]
+fn truncates_by_display_width() {
+    assert_eq!(truncate_to_width("hello", 10), "hello");
+    assert_eq!(truncate_to_width("hello", 5), "hello");
+    assert_eq!(truncate_to_width("hello", 4), "hel…");
+    assert_eq!(truncate_to_width("hello", 1), "…");
+    assert_eq!(truncate_to_width("hello", 0), "");
+    assert_eq!(truncate_to_width("", 0), "");
+    // 全形字寬度為 2，放不下時不會切成一半
+    assert_eq!(truncate_to_width("臺北市", 5), "臺北…");
+    assert_eq!(truncate_to_width("臺北市", 4), "臺…");
+    assert_eq!(truncate_to_width("臺北市", 2), "…");
+    for width in 0..8 {
+        assert!(truncate_to_width("臺北市政府", width).width() <= width);
+    }
+}
@@ -0,0 +1,75 @@
+use csv_converter::{CsvConverter, TemplateOptions};
+
+fn read(csv: &str) -> (Vec<String>, Vec<csv::StringRecord>) {
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let headers = reader.headers().unwrap().iter().map(|h| h.to_string()).collect();
+    (headers, reader.records().collect::<Result<_, _>>().unwrap())
+}
+
+fn options(template: &str, output: String) -> TemplateOptions {
+    TemplateOptions { template: template.to_string(), output, ..Default::default() }
+}
+
+#[test]
+fn per_file_names_cannot_leave_the_output_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out").to_string_lossy().into_owned();
+    let output = format!("{}/{{{{name}}}}.txt", out);
+
+    for name in ["../escape", "a/../../escape", "/tmp/escape"] {
+        let (headers, records) = read(&format!("name\nok\n{}\n", name));
+        let error = CsvConverter::render_template(&headers, &records, &options("x", output.clone())).unwrap_err();
+        assert!(error.to_string().contains("離開了輸出資料夾"), "{}: {}", name, error);
+    }
+    // 檢查在寫入任何檔案前完成
+    assert!(!dir.path().join("out").exists());
+    assert!(!dir.path().join("escape.txt").exists());
+
+    // 子資料夾仍然可以使用
+    let (headers, records) = read("name\nok\nsub/inner\n");
+    CsvConverter::render_template(&headers, &records, &options("{{name}}", output)).unwrap();
+    assert_eq!(std::fs::read_to_string(dir.path().join("out/sub/inner.txt")).unwrap(), "sub/inner");
+}
+
+#[test]
+fn group_context_does_not_shadow_group_columns() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("groups.txt").to_string_lossy().into_owned();
+    let (headers, records) = read("count,rows,id\n7,a,1\n7,a,2\n8,b,3\n");
+    let options = TemplateOptions {
+        group_by: vec!["count".to_string(), "rows".to_string()],
+        ..options("{{count}} {{rows}} {{_count}}:{{#each _rows}}{{id}}{{/each}}\n", output.clone())
+    };
+
+    CsvConverter::render_template(&headers, &records, &options).unwrap();
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), "7 a 2:12\n8 b 1:3\n");
+}
+
+#[test]
+fn grouping_by_reserved_names_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("groups.txt").to_string_lossy().into_owned();
+    let (headers, records) = read("_count,id\n1,1\n");
+    let options = TemplateOptions { group_by: vec!["_count".to_string()], ..options("x", output) };
+    assert!(CsvConverter::render_template(&headers, &records, &options).is_err());
+}
+
+#[test]
+fn failing_body_writes_no_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = format!("{}/{{{{id}}}}.txt", dir.path().join("out").to_string_lossy());
+    // 第二筆缺少 note 欄位，strict 模式下無法套用範本
+    let headers = vec!["id".to_string(), "note".to_string()];
+    let records = vec![csv::StringRecord::from(vec!["1", "a"]), csv::StringRecord::from(vec!["2"])];
+    let options = TemplateOptions { strict: true, ..options("{{note}}", output) };
+
+    let error = CsvConverter::render_template(&headers, &records, &options).unwrap_err();
+    assert!(format!("{:#}", error).contains("第 2 筆無法套用範本"), "{:#}", error);
+    // 第一筆的檔案也不會寫出
+    assert!(!dir.path().join("out").exists());
+
+    let records = vec![csv::StringRecord::from(vec!["1", "a"]), csv::StringRecord::from(vec!["2", "b"])];
+    let report = CsvConverter::render_template(&headers, &records, &options).unwrap();
+    assert_eq!(report.rows_written, 2);
+    assert_eq!(std::fs::read_to_string(dir.path().join("out/2.txt")).unwrap(), "b");
+}
@@ -0,0 +1,176 @@
+use csv_converter::{NonFinitePolicy, NumberLocale, NumericPolicy, ParseRule, ValueOptions};
+use serde_json::{json, Value};
+
+fn parse(options: &ValueOptions, field: &str) -> Value {
+    options.parse("column", field).unwrap()
+}
+
+fn parse_with_rules(options: &ValueOptions, field: &str) -> (Value, Vec<ParseRule>) {
+    options.parse_with_rules("column", field).unwrap()
+}
+
+fn numeric(numeric: NumericPolicy) -> ValueOptions {
+    ValueOptions { numeric, ..Default::default() }
+}
+
+#[test]
+fn js_safe_stringifies_integers_beyond_2_pow_53() {
+    let options = numeric(NumericPolicy::JsSafe);
+    assert_eq!(parse(&options, "9007199254740991"), json!(9007199254740991_i64));
+    assert_eq!(parse(&options, "-9007199254740991"), json!(-9007199254740991_i64));
+    assert_eq!(parse(&options, "9007199254740992"), json!("9007199254740992"));
+    assert_eq!(parse(&options, "-9007199254740993"), json!("-9007199254740993"));
+    // 超出 i64 範圍
+    assert_eq!(parse(&options, "123456789012345678901234"), json!("123456789012345678901234"));
+    assert_eq!(parse(&options, "1.5"), json!(1.5));
+
+    // 一般模式會變成 f64
+    let lossy = parse(&ValueOptions::default(), "123456789012345678901234");
+    assert!(lossy.is_f64(), "{}", lossy);
+}
+
+#[test]
+fn preserve_keeps_the_literal() {
+    let options = numeric(NumericPolicy::Preserve);
+    assert_eq!(parse(&options, "0.10").to_string(), "0.10");
+    assert_eq!(parse(&options, "123456789012345678901234").to_string(), "123456789012345678901234");
+    assert_eq!(parse(&options, "007"), json!(7));
+    assert_eq!(parse(&options, "abc"), json!("abc"));
+    assert_eq!(parse(&ValueOptions::default(), "0.10").to_string(), "0.1");
+
+    // 只有 decimal_columns 中的欄位保留字面
+    let options = ValueOptions { decimal_columns: vec!["price".to_string()], ..Default::default() };
+    assert_eq!(options.parse("price", "0.10").unwrap().to_string(), "0.10");
+    assert_eq!(options.parse("ratio", "0.10").unwrap().to_string(), "0.1");
+}
+
+#[test]
+fn non_finite_policies() {
+    for field in ["NaN", "inf", "-inf", "infinity"] {
+        let string = ValueOptions { non_finite: NonFinitePolicy::String, ..Default::default() };
+        assert_eq!(parse(&string, field), json!(field));
+        let null = ValueOptions { non_finite: NonFinitePolicy::Null, ..Default::default() };
+        assert_eq!(parse(&null, field), Value::Null);
+        let error = ValueOptions { non_finite: NonFinitePolicy::Error, ..Default::default() };
+        let message = error.parse("score", field).unwrap_err().to_string();
+        assert!(message.contains("score") && message.contains(field), "{}", message);
+    }
+    // 超出 f64 範圍同樣視為非有限數
+    let null = ValueOptions { non_finite: NonFinitePolicy::Null, ..Default::default() };
+    assert_eq!(parse(&null, "1e999"), Value::Null);
+}
+
+#[test]
+fn european_grouping() {
+    let de = ValueOptions::localized(NumberLocale::De);
+    assert_eq!(
+        parse_with_rules(&de, "1.234.567,89"),
+        (json!(1234567.89), vec![ParseRule::DecimalComma, ParseRule::ThousandsSeparator])
+    );
+    assert_eq!(parse_with_rules(&de, "1.234"), (json!(1234), vec![ParseRule::ThousandsSeparator]));
+    assert_eq!(parse_with_rules(&de, "0,5"), (json!(0.5), vec![ParseRule::DecimalComma]));
+    // 分組不符時改以一般格式解析
+    assert_eq!(parse_with_rules(&de, "12.34"), (json!(12.34), Vec::new()));
+    assert_eq!(parse(&de, "1.2,5"), json!("1.2,5"));
+
+    let fr = ValueOptions::localized(NumberLocale::Fr);
+    assert_eq!(parse(&fr, "1 234,5"), json!(1234.5));
+    assert_eq!(parse(&fr, "1\u{a0}234\u{a0}567"), json!(1234567));
+    assert_eq!(parse(&fr, "12 34"), json!("12 34"));
+
+    let en = ValueOptions::localized(NumberLocale::En);
+    assert_eq!(parse_with_rules(&en, "1,234.5"), (json!(1234.5), vec![ParseRule::ThousandsSeparator]));
+    assert_eq!(parse(&en, "1,23"), json!("1,23"));
+    // 未指定地區時千分位不會被解析
+    assert_eq!(parse(&ValueOptions::default(), "1,234"), json!("1,234"));
+}
+
+#[test]
+fn swiss_grouping() {
+    let ch = ValueOptions::localized(NumberLocale::Ch);
+    assert_eq!(parse_with_rules(&ch, "1'234.56"), (json!(1234.56), vec![ParseRule::ThousandsSeparator]));
+    assert_eq!(parse(&ch, "1\u{2019}234\u{2019}567"), json!(1234567));
+    assert_eq!(parse(&ch, "CHF 1'000"), json!("CHF 1'000"));
+    assert_eq!(parse(&ch, "12'34"), json!("12'34"));
+    assert_eq!("ch".parse::<NumberLocale>().unwrap(), NumberLocale::Ch);
+    assert!("CH".parse::<NumberLocale>().is_err());
+
+    let chf = ValueOptions { currency_symbols: vec!["CHF".to_string()], ..ch };
+    assert_eq!(parse(&chf, "CHF 1'000"), json!(1000));
+}
+
+#[test]
+fn strips_currency_symbols() {
+    let en = ValueOptions::localized(NumberLocale::En);
+    assert_eq!(
+        parse_with_rules(&en, "$1,200.00"),
+        (json!(1200.0), vec![ParseRule::Currency, ParseRule::ThousandsSeparator])
+    );
+    assert_eq!(parse(&en, "US$ 5"), json!(5));
+    assert_eq!(parse(&en, "NT$1,000"), json!(1000));
+    assert_eq!(parse(&en, "-$5"), json!(-5));
+    assert_eq!(parse(&en, "$-1,200"), json!(-1200));
+    assert_eq!(parse(&en, "$"), json!("$"));
+    assert_eq!(parse(&en, "$abc"), json!("$abc"));
+
+    let de = ValueOptions::localized(NumberLocale::De);
+    assert_eq!(
+        parse_with_rules(&de, "1.234,56 €"),
+        (json!(1234.56), vec![ParseRule::Currency, ParseRule::DecimalComma, ParseRule::ThousandsSeparator])
+    );
+    // 未設定貨幣符號時保留為字串
+    assert_eq!(parse(&ValueOptions::default(), "$5"), json!("$5"));
+}
+
+#[test]
+fn percent_shifts_the_decimal_point() {
+    let en = ValueOptions::localized(NumberLocale::En);
+    assert_eq!(parse_with_rules(&en, "45%"), (json!(0.45), vec![ParseRule::Percent]));
+    assert_eq!(parse(&en, "5%"), json!(0.05));
+    assert_eq!(parse(&en, "12.5%"), json!(0.125));
+    assert_eq!(parse(&en, "150%"), json!(1.5));
+    assert_eq!(parse(&en, "-0.5%"), json!(-0.005));
+    assert_eq!(parse(&en, "1,000%"), json!(10.0));
+    assert_eq!(parse(&ValueOptions::localized(NumberLocale::De), "12,5 %"), json!(0.125));
+
+    // 以移動小數點換算，保留字面時不會有浮點誤差
+    let exact = ValueOptions { numeric: NumericPolicy::Preserve, ..en.clone() };
+    assert_eq!(parse(&exact, "10%").to_string(), "0.10");
+    assert_eq!(parse(&exact, "0.07%").to_string(), "0.0007");
+    assert_eq!(parse(&ValueOptions::default(), "45%"), json!("45%"));
+}
+
+#[test]
+fn extended_boolean_tokens() {
+    let options = ValueOptions::localized(NumberLocale::Plain);
+    for (field, expected) in [("Yes", true), ("y", true), ("ON", true), ("是", true), ("no", false), ("N", false), ("否", false)] {
+        assert_eq!(parse_with_rules(&options, field), (json!(expected), vec![ParseRule::BooleanTokens]), "{}", field);
+    }
+    // 預設的 true/false 不記錄規則
+    assert_eq!(parse_with_rules(&options, "TRUE"), (json!(true), Vec::new()));
+    assert_eq!(parse(&ValueOptions::default(), "yes"), json!("yes"));
+
+    let custom = ValueOptions { true_tokens: vec!["ja".into()], false_tokens: vec!["nein".into()], ..Default::default() };
+    assert_eq!(parse(&custom, "Ja"), json!(true));
+    assert_eq!(parse(&custom, "NEIN"), json!(false));
+    assert_eq!(parse(&custom, "true"), json!("true"));
+}
+
+#[test]
+fn schema_records_the_rules_per_column() {
+    let csv = "price,share,flag,plain\n\"$1,200.50\",45%,yes,1\n$3,5%,no,2\n";
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let headers: Vec<String> = reader.headers().unwrap().iter().map(|h| h.to_string()).collect();
+    let records: Vec<csv::StringRecord> = reader.records().collect::<Result<_, _>>().unwrap();
+    let schema = csv_converter::Schema::infer_with(&headers, &records, &ValueOptions::localized(NumberLocale::En));
+    let rules: Vec<Vec<ParseRule>> = schema.columns.iter().map(|c| c.rules.clone()).collect();
+    assert_eq!(
+        rules,
+        [
+            vec![ParseRule::Currency, ParseRule::ThousandsSeparator],
+            vec![ParseRule::Percent],
+            vec![ParseRule::BooleanTokens],
+            vec![],
+        ]
+    );
+}
@@ -0,0 +1,152 @@
+use csv_converter::watch::{stable_duration, FAILED_DIR, PROCESSED_DIR, WATCH_CONFIG_FILE, WATCH_LOG_FILE};
+use csv_converter::{DirectoryWatcher, NonFinitePolicy, ValueOptions, WatchConfig, WatchFormat};
+use std::path::Path;
+use std::time::Duration;
+
+fn immediate() -> WatchConfig {
+    WatchConfig { stable_for: Duration::ZERO, ..Default::default() }
+}
+
+fn watcher(dir: &Path, config: WatchConfig) -> DirectoryWatcher {
+    DirectoryWatcher::new(&dir.to_string_lossy(), config).unwrap()
+}
+
+#[test]
+fn scan_converts_stable_files_and_moves_them() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("people.csv"), "id,name\n1,Ann\n").unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "not csv").unwrap();
+    let config = WatchConfig { stable_for: Duration::from_millis(50), ..Default::default() };
+    let mut watcher = watcher(dir.path(), config);
+
+    // 第一次看到的檔案要等穩定後才轉換
+    assert!(watcher.scan().unwrap().is_empty());
+    std::thread::sleep(Duration::from_millis(100));
+    let outcomes = watcher.scan().unwrap();
+    assert_eq!(outcomes.len(), 1);
+    let (output, report) = outcomes[0].result.as_ref().unwrap();
+    assert_eq!(report.rows_written, 1);
+    assert_eq!(output, &dir.path().join("converted/people.json"));
+    assert_eq!(outcomes[0].moved_to, dir.path().join(PROCESSED_DIR).join("people.csv"));
+    assert!(!dir.path().join("people.csv").exists());
+    assert!(dir.path().join("notes.txt").exists());
+
+    let log = std::fs::read_to_string(dir.path().join(WATCH_LOG_FILE)).unwrap();
+    assert!(log.contains("\tprocessed\tpeople.csv\t"), "{}", log);
+    assert!(watcher.scan().unwrap().is_empty());
+}
+
+#[test]
+fn failed_file_does_not_stop_the_scan() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("a_bad.csv"), "id,name\n1\n").unwrap();
+    std::fs::write(dir.path().join("b_good.csv"), "id,name\n1,Ann\n").unwrap();
+    let mut watcher = watcher(dir.path(), immediate());
+
+    let outcomes = watcher.scan().unwrap();
+    assert_eq!(outcomes.len(), 2);
+    assert!(outcomes[0].result.is_err());
+    assert_eq!(outcomes[0].moved_to, dir.path().join(FAILED_DIR).join("a_bad.csv"));
+    assert!(outcomes[1].result.is_ok());
+    assert!(dir.path().join("converted/b_good.json").exists());
+}
+
+#[test]
+fn file_that_cannot_be_moved_is_reported_once() {
+    let dir = tempfile::tempdir().unwrap();
+    // processed 是一般檔案，無法建立資料夾
+    std::fs::write(dir.path().join(PROCESSED_DIR), "").unwrap();
+    std::fs::write(dir.path().join("stuck.csv"), "id\n1\n").unwrap();
+    let mut watcher = watcher(dir.path(), immediate());
+
+    let outcomes = watcher.scan().unwrap();
+    assert_eq!(outcomes.len(), 1);
+    assert!(outcomes[0].result.as_ref().unwrap_err().contains("無法移動"));
+    assert_eq!(outcomes[0].moved_to, dir.path().join("stuck.csv"));
+    // 內容沒變時不再重試
+    assert!(watcher.scan().unwrap().is_empty());
+
+    std::fs::write(dir.path().join("stuck.csv"), "id\n1\n2\n").unwrap();
+    std::fs::remove_file(dir.path().join(PROCESSED_DIR)).unwrap();
+    let outcomes = watcher.scan().unwrap();
+    assert_eq!(outcomes.len(), 1);
+    assert!(outcomes[0].result.is_ok(), "{:?}", outcomes[0].result);
+}
+
+#[test]
+fn config_file_overrides_only_listed_settings() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join(WATCH_CONFIG_FILE), "setting,value\nformat,yaml\ndecimal,price\n").unwrap();
+    let mut config = immediate();
+    config.format = WatchFormat::Xml;
+    config.output_dir = "out".to_string();
+    config.values.decimal_columns.push("amount".to_string());
+    let watcher = watcher(dir.path(), config);
+
+    assert_eq!(watcher.config().format, WatchFormat::Yaml);
+    assert_eq!(watcher.config().output_dir, "out");
+    assert_eq!(watcher.config().stable_for, Duration::ZERO);
+    assert_eq!(watcher.config().values.decimal_columns, ["amount", "price"]);
+    assert_eq!(watcher.overridden_settings(), ["format"]);
+}
+
+#[test]
+fn invalid_stable_seconds_are_rejected() {
+    for seconds in [-1.0, f64::INFINITY, f64::NAN, 1e30] {
+        assert!(stable_duration(seconds).is_err(), "{}", seconds);
+    }
+    assert_eq!(stable_duration(1.5).unwrap(), Duration::from_millis(1500));
+
+    let dir = tempfile::tempdir().unwrap();
+    for value in ["-1", "inf", "NaN"] {
+        std::fs::write(dir.path().join(WATCH_CONFIG_FILE), format!("setting,value\nstable_seconds,{}\n", value)).unwrap();
+        assert!(DirectoryWatcher::new(&dir.path().to_string_lossy(), immediate()).is_err(), "{}", value);
+    }
+}
+
+#[test]
+fn failed_conversion_keeps_the_previous_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let converted = dir.path().join("converted");
+    std::fs::create_dir(&converted).unwrap();
+    std::fs::write(converted.join("scores.yaml"), "old").unwrap();
+    std::fs::write(dir.path().join("scores.csv"), "id,score\n1,NaN\n").unwrap();
+    let values = ValueOptions { non_finite: NonFinitePolicy::Error, ..Default::default() };
+    let config = WatchConfig { format: WatchFormat::Yaml, values, ..immediate() };
+    let mut watcher = watcher(dir.path(), config);
+
+    let outcomes = watcher.scan().unwrap();
+    assert!(outcomes[0].result.is_err());
+    assert_eq!(std::fs::read_to_string(converted.join("scores.yaml")).unwrap(), "old");
+    assert!(!converted.join("scores.yaml.partial").exists());
+
+    // 成功時取代先前的輸出
+    std::fs::write(dir.path().join("scores.csv"), "id,score\n1,2.5\n").unwrap();
+    let outcomes = watcher.scan().unwrap();
+    assert!(outcomes[0].result.is_ok());
+    assert_eq!(std::fs::read_to_string(converted.join("scores.yaml")).unwrap(), "- id: 1\n  score: 2.5\n");
+    let entries: Vec<_> = std::fs::read_dir(&converted).unwrap().map(|e| e.unwrap().file_name()).collect();
+    assert_eq!(entries, ["scores.yaml"]);
+}
+
+#[test]
+fn sqlite_output_is_replaced_by_a_new_drop() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = WatchConfig { format: WatchFormat::Sqlite, ..immediate() };
+    let mut watcher = watcher(dir.path(), config);
+    let count = || {
+        let conn = rusqlite::Connection::open(dir.path().join("converted/data.db")).unwrap();
+        conn.query_row("SELECT COUNT(*) FROM data", [], |row| row.get::<_, i64>(0)).unwrap()
+    };
+
+    std::fs::write(dir.path().join("data.csv"), "id\n1\n2\n3\n").unwrap();
+    assert!(watcher.scan().unwrap()[0].result.is_ok());
+    assert_eq!(count(), 3);
+
+    // 上次中斷留下的暫存檔不會混入新的資料庫
+    std::fs::write(dir.path().join("converted/data.db.partial"), "garbage").unwrap();
+    std::fs::write(dir.path().join("data.csv"), "id\n4\n").unwrap();
+    assert!(watcher.scan().unwrap()[0].result.is_ok());
+    assert_eq!(count(), 1);
+    assert!(!dir.path().join("converted/data.db.partial").exists());
+}
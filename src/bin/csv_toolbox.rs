@@ -1,17 +1,1467 @@
 // 引入必要的模組
-use csv_converter::CsvConverter;
+use anyhow::{bail, Context};
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use csv::StringRecord;
+use csv_converter::mask::MASK_SALT_ENV;
+use csv_converter::spreadsheet::is_spreadsheet_path;
+use csv_converter::value::{DEFAULT_CURRENCY_SYMBOLS, EXTENDED_FALSE_TOKENS, EXTENDED_TRUE_TOKENS};
+use csv_converter::watch::{stable_duration, WATCH_CONFIG_FILE};
+use csv_converter::{
+    Aggregate, AvroCodec, AvroOptions, BoundingBox, CancellationToken, Cancelled, ColumnProfile, ConcatOptions,
+    ConversionReport, CsvConverter, Dialect, DiffOptions, DirectoryWatcher, FixedWidthLayout, FormatOptions,
+    GeoJsonOptions, GroupAggregate, IncrementalOptions, MaskingRules, NestShape, NestedOptions, NonFinitePolicy,
+    NumberLocale, NumericPolicy, PivotOptions, Progress, ProgressMonitor, QuoteStyle, RowChange, SampleOptions,
+    Sampling, Schema, SheetSelector, SpreadsheetOptions, SqlExportOptions, TableOptions, TemplateOptions,
+    TomlOptions, UnpivotOptions, ValueOptions, WatchConfig, WatchFormat, XlsxExportOptions, XmlOptions,
+    YamlOptions,
+};
 use cargo_tutorial::create_sample_csv_file;
+#[cfg(feature = "query")]
+use csv_converter::QueryOptions;
+#[cfg(feature = "serve")]
+use csv_converter::ServeOptions;
+use indicatif::{ProgressBar, ProgressStyle};
+use log::{Level, LevelFilter};
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::{Arc, OnceLock};
 
-fn main() -> std::io::Result<()> {
-    println!("🚀 CSV 工具箱");
-    println!("==============");
+/// 驗證未通過（lint、fmt --check、validate 有問題或 diff 有差異）的結束碼
+const EXIT_INVALID: u8 = 1;
+/// 執行失敗的結束碼；命令列用法錯誤同樣為 2
+const EXIT_ERROR: u8 = 2;
+/// 按 Ctrl-C 取消的結束碼
+const EXIT_INTERRUPTED: u8 = 130;
 
-    // 生成測試 CSV 檔案
-    create_sample_csv_file("demo.csv", 50)?;
+/// CSV 工具箱：在 CSV、試算表與各種資料格式之間轉換，並檢查、比較資料
+///
+/// 結束碼：0 成功、1 驗證未通過（lint、fmt --check、validate 有問題或 diff 有差異）、2 錯誤
+#[derive(Parser)]
+#[command(name = "csv_toolbox", version)]
+struct Cli {
+    #[command(flatten)]
+    global: GlobalArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// 所有子指令共用的旗標
+#[derive(Args)]
+struct GlobalArgs {
+    /// CSV 欄位分隔字元（預設為逗號，.tsv 檔為 tab；tab 可寫成 \t）
+    #[arg(long, global = true, value_parser = parse_ascii_char)]
+    delimiter: Option<u8>,
+    /// CSV 引號字元
+    #[arg(long, global = true, value_parser = parse_ascii_char)]
+    quote: Option<u8>,
+    /// 讀取 CSV 時略過以此字元開頭的行
+    #[arg(long, global = true, value_parser = parse_ascii_char)]
+    comment: Option<u8>,
+    /// 寫出轉換報告 JSON（- 表示 stdout）
+    #[arg(long, global = true, value_name = "檔案")]
+    report: Option<String>,
+    /// 顯示更多訊息（-v 除錯、-vv 追蹤）；RUST_LOG 可覆蓋
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    verbose: u8,
+    /// 只顯示錯誤
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+}
+
+impl GlobalArgs {
+    /// 是否指定了任何 CSV 方言旗標
+    fn has_dialect(&self) -> bool {
+        self.delimiter.is_some() || self.quote.is_some() || self.comment.is_some()
+    }
+}
+
+/// 讀取輸入檔的旗標
+///
+/// 輸入檔可為 CSV、試算表、Avro 或固定寬度檔；含萬用字元（例如 "data/*.csv"）時依欄位名稱合併所有符合的檔案
+#[derive(Args, Clone)]
+struct InputArgs {
+    /// 試算表的工作表名稱或索引（從 0 開始）
+    #[arg(long)]
+    sheet: Option<SheetSelector>,
+    /// 試算表的儲存格範圍，例如 B2:F100
+    #[arg(long)]
+    range: Option<String>,
+    /// 以固定寬度格式解析輸入
+    #[arg(long, value_name = "配置檔")]
+    layout: Option<String>,
+    /// 固定寬度檔開頭略過的行數，覆蓋配置檔的設定
+    #[arg(long, requires = "layout")]
+    skip_lines: Option<usize>,
+    /// 固定寬度檔允許長度不足的行
+    #[arg(long, requires = "layout")]
+    allow_short_lines: bool,
+    /// 只取樣本：head:N、tail:N、every:N、reservoir:N 或 stratified:欄位:N
+    #[arg(long)]
+    sample: Option<Sampling>,
+    /// 隨機抽樣的種子
+    #[arg(long, default_value_t = 0, requires = "sample")]
+    seed: u64,
+    /// 輸出前套用個資遮罩
+    #[arg(long, value_name = "規則檔")]
+    mask: Option<String>,
+    /// 從檔案讀取遮罩使用的鹽值（忽略結尾換行），預設取自環境變數 CSV_MASK_SALT
+    #[arg(long, value_name = "檔案", requires = "mask")]
+    salt_file: Option<String>,
+    /// 合併多個檔案時將欄位改名
+    #[arg(long, value_name = "舊名稱=新名稱", value_parser = parse_rename)]
+    rename: Vec<(String, String)>,
+    /// 合併多個檔案時加上記錄來源檔案的欄位
+    #[arg(long)]
+    source_column: bool,
+}
+
+/// 欄位值轉換的旗標
+#[derive(Args, Clone)]
+struct ValueArgs {
+    /// 數值轉換策略：lossy、preserve 或 js-safe
+    #[arg(long)]
+    numeric: Option<NumericPolicy>,
+    /// NaN、inf 等非有限數值的處理方式：string、null 或 error
+    #[arg(long)]
+    non_finite: Option<NonFinitePolicy>,
+    /// 需保持精確十進位的欄位
+    #[arg(long, value_name = "欄位")]
+    decimal: Vec<String>,
+    /// 數字的地區格式：plain、en、de、fr 或 ch
+    #[arg(long)]
+    locale: Option<NumberLocale>,
+    /// 數字前後可去除的貨幣符號（default 為常見符號）
+    #[arg(long, value_name = "符號")]
+    currency: Vec<String>,
+    /// 將 45% 轉為 0.45
+    #[arg(long)]
+    percent: bool,
+    /// 空欄位轉為 null
+    #[arg(long)]
+    empty_as_null: bool,
+    /// 布林字詞集合
+    #[arg(long, value_enum)]
+    bool_tokens: Option<BoolTokens>,
+    /// 額外視為 true 的字詞
+    #[arg(long = "true", value_name = "字詞")]
+    true_tokens: Vec<String>,
+    /// 額外視為 false 的字詞
+    #[arg(long = "false", value_name = "字詞")]
+    false_tokens: Vec<String>,
+}
+
+/// 可選用的布林字詞集合
+#[derive(Clone, Copy, ValueEnum)]
+enum BoolTokens {
+    /// yes/no、on/off、是/否 等
+    Extended,
+}
+
+/// SQL 匯出的旗標
+#[derive(Args)]
+struct SqlArgs {
+    /// 資料表名稱
+    #[arg(long)]
+    table: Option<String>,
+    /// 建立索引的欄位
+    #[arg(long, value_name = "欄位")]
+    index: Vec<String>,
+    /// 每個 INSERT 陳述式包含的列數
+    #[arg(long)]
+    batch_size: Option<usize>,
+}
+
+/// 表格輸出（markdown、html、show）的參數
+#[derive(Args)]
+struct TableArgs {
+    input: String,
+    /// 輸出檔，省略時印到 stdout
+    output: Option<String>,
+    /// 最多顯示的列數
+    #[arg(long)]
+    rows: Option<usize>,
+    /// 每欄最大寬度
+    #[arg(long)]
+    max_width: Option<usize>,
+    #[command(flatten)]
+    source: InputArgs,
+    #[command(flatten)]
+    values: ValueArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 依輸出檔的副檔名轉換（json、yaml、toml、xml、xlsx、db、sql、geojson、avro、parquet、csv、tsv）
+    Convert {
+        input: String,
+        output: String,
+        #[command(flatten)]
+        source: InputArgs,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 產生測試用的使用者資料 CSV
+    Generate {
+        output: String,
+        /// 產生的列數
+        #[arg(short = 'n', long, default_value_t = 50)]
+        rows: usize,
+    },
+    /// 推斷每個欄位的型別、是否可為 null 與套用的解析規則
+    #[command(alias = "schema")]
+    InferSchema {
+        input: String,
+        /// 以 JSON 輸出，可作為 validate --schema 的輸入
+        #[arg(long)]
+        json: bool,
+        #[command(flatten)]
+        source: InputArgs,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 印出每個欄位的統計概況
+    Profile {
+        input: String,
+        /// 以 JSON 輸出
+        #[arg(long)]
+        json: bool,
+        #[command(flatten)]
+        source: InputArgs,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 檢查 CSV 格式，並依 schema 檢查欄位與型別；有問題時結束碼為 1
+    Validate {
+        input: String,
+        /// infer-schema --json 產生的 schema
+        #[arg(long, value_name = "schema.json")]
+        schema: Option<String>,
+        #[command(flatten)]
+        source: InputArgs,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 以輸入的 CSV 方言印出前幾列
+    Head {
+        input: String,
+        /// 印出的列數，預設 10；指定 --sample 時改印抽樣結果
+        #[arg(short = 'n', long, conflicts_with = "sample")]
+        rows: Option<usize>,
+        #[command(flatten)]
+        source: InputArgs,
+    },
+    /// 比較兩個檔案的欄位與列；有差異時結束碼為 1
+    Diff {
+        left: String,
+        right: String,
+        /// 識別同一列的鍵欄位，省略時依列順序比較
+        #[arg(long, value_name = "欄位")]
+        key: Vec<String>,
+        #[command(flatten)]
+        source: InputArgs,
+    },
+    /// 轉換為 JSON
+    Json {
+        input: String,
+        output: String,
+        /// 以記憶體映射直接轉換單一 CSV 檔，不支援其他輸入處理旗標
+        #[arg(long)]
+        fast: bool,
+        #[command(flatten)]
+        source: InputArgs,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 匯出 SQLite 資料庫
+    Sqlite {
+        input: String,
+        output: String,
+        #[command(flatten)]
+        sql: SqlArgs,
+        #[command(flatten)]
+        source: InputArgs,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 產生 SQL 腳本
+    Sql {
+        input: String,
+        output: String,
+        #[command(flatten)]
+        sql: SqlArgs,
+        #[command(flatten)]
+        source: InputArgs,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 匯出 xlsx
+    Xlsx {
+        input: String,
+        output: String,
+        /// 資料工作表名稱
+        #[arg(long, value_name = "名稱")]
+        sheet_name: Option<String>,
+        /// 另外加入列出推斷 schema 的工作表
+        #[arg(long)]
+        with_schema: bool,
+        #[command(flatten)]
+        source: InputArgs,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 匯出 GeoJSON
+    Geojson {
+        input: String,
+        output: String,
+        /// 緯度欄位
+        #[arg(long, value_name = "欄位")]
+        lat: Option<String>,
+        /// 經度欄位
+        #[arg(long, value_name = "欄位")]
+        lon: Option<String>,
+        /// 只保留範圍內的點：西,南,東,北
+        #[arg(long)]
+        bbox: Option<BoundingBox>,
+        /// 座標無效的記錄寫到此檔案
+        #[arg(long, value_name = "檔案")]
+        rejects: Option<String>,
+        #[command(flatten)]
+        source: InputArgs,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 匯出 YAML
+    Yaml {
+        input: String,
+        output: String,
+        /// 每列輸出為一份文件
+        #[arg(long)]
+        document_per_row: bool,
+        #[command(flatten)]
+        source: InputArgs,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 匯出 TOML
+    Toml {
+        input: String,
+        output: String,
+        /// 陣列表格名稱
+        #[arg(long, value_name = "名稱")]
+        table: Option<String>,
+        #[command(flatten)]
+        source: InputArgs,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 匯出 XML
+    Xml {
+        input: String,
+        output: String,
+        /// 根元素名稱
+        #[arg(long, value_name = "名稱")]
+        root: Option<String>,
+        /// 每列的元素名稱
+        #[arg(long, value_name = "名稱")]
+        row: Option<String>,
+        /// 輸出為屬性的欄位
+        #[arg(long, value_name = "欄位")]
+        attribute: Vec<String>,
+        /// 所有欄位都輸出為屬性
+        #[arg(long)]
+        all_attributes: bool,
+        #[command(flatten)]
+        source: InputArgs,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 輸出 Markdown 表格
+    Markdown(TableArgs),
+    /// 輸出 HTML 表格
+    Html(TableArgs),
+    /// 在終端機顯示表格
+    Show(TableArgs),
+    /// 匯出 Parquet
+    #[cfg(feature = "parquet")]
+    Parquet {
+        input: String,
+        output: String,
+        #[command(flatten)]
+        source: InputArgs,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 匯出 Avro；以 .avro 檔為輸入時會讀回記錄，可用來驗證輸出
+    Avro {
+        input: String,
+        output: String,
+        /// 壓縮方式：null、deflate 或 snappy
+        #[arg(long)]
+        codec: Option<AvroCodec>,
+        /// 記錄型別名稱
+        #[arg(long, value_name = "名稱")]
+        record_name: Option<String>,
+        /// 記錄型別的命名空間
+        #[arg(long, value_name = "名稱")]
+        namespace: Option<String>,
+        /// 每個資料區塊的列數
+        #[arg(long)]
+        block_size: Option<usize>,
+        #[command(flatten)]
+        source: InputArgs,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 只轉換上次之後新增的列，輸出為 .ndjson 或 .parquet
+    Incremental {
+        input: String,
+        output: String,
+        /// 記錄轉換進度的狀態檔
+        #[arg(long, value_name = "檔案")]
+        state: Option<String>,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 依欄位名稱合併符合樣式的所有檔案
+    Concat {
+        pattern: String,
+        output: String,
+        #[command(flatten)]
+        source: InputArgs,
+    },
+    /// 長表轉寬表
+    Pivot {
+        input: String,
+        output: String,
+        /// 保留為列索引的欄位
+        #[arg(long, value_name = "欄位", required = true)]
+        index: Vec<String>,
+        /// 值成為新欄位名稱的欄位
+        #[arg(long, value_name = "欄位")]
+        columns: String,
+        /// 填入儲存格的欄位
+        #[arg(long = "values", value_name = "欄位")]
+        values_column: String,
+        /// 同一格有多筆值時的彙總方式：sum、mean、first 或 count
+        #[arg(long)]
+        agg: Option<Aggregate>,
+        /// 輸入已依索引欄位排序（數字依數值、其餘依位元組順序），逐組輸出以節省記憶體
+        #[arg(long)]
+        sorted: bool,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 寬表轉長表
+    Unpivot {
+        input: String,
+        output: String,
+        /// 保留的識別欄位
+        #[arg(long, value_name = "欄位")]
+        id: Vec<String>,
+        /// 要轉為列的欄位，省略時為識別欄位以外的所有欄位
+        #[arg(long, value_name = "欄位")]
+        value_column: Vec<String>,
+        /// 原欄位名稱的輸出欄位
+        #[arg(long, value_name = "名稱")]
+        variable_name: Option<String>,
+        /// 值的輸出欄位
+        #[arg(long, value_name = "名稱")]
+        value_name: Option<String>,
+        /// 略過空值
+        #[arg(long)]
+        skip_empty: bool,
+    },
+    /// 依欄位分組輸出巢狀 JSON
+    Nested {
+        input: String,
+        output: String,
+        /// 分組欄位，依序形成巢狀層級
+        #[arg(long, value_name = "欄位", required = true)]
+        group_by: Vec<String>,
+        /// 分組節點的形式：object 或 array
+        #[arg(long)]
+        shape: Option<NestShape>,
+        /// 分組彙總：count 或 sum:欄位
+        #[arg(long)]
+        agg: Vec<GroupAggregate>,
+        /// 子記錄保留分組欄位
+        #[arg(long)]
+        keep_keys: bool,
+        #[command(flatten)]
+        source: InputArgs,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 檢查 CSV 檔的格式問題；有問題時結束碼為 1
+    Lint {
+        #[arg(required = true)]
+        inputs: Vec<String>,
+    },
+    /// 將 CSV 檔改寫為標準格式
+    Fmt {
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// 引號規則：necessary、always 或 non-numeric
+        #[arg(long)]
+        quote_style: Option<QuoteStyle>,
+        /// 去除欄位值前後的空白
+        #[arg(long)]
+        trim_fields: bool,
+        /// 只檢查不改寫，格式不符時結束碼為 1
+        #[arg(long)]
+        check: bool,
+    },
+    /// 監看資料夾，自動轉換放入的 CSV 檔；資料夾中 .watch.csv 列出的設定優先於旗標，按 Ctrl-C 結束
+    Watch {
+        dir: String,
+        /// 輸出格式：json、yaml、toml、xml、xlsx、sqlite、sql 或 avro
+        #[arg(long)]
+        format: Option<WatchFormat>,
+        /// 輸出資料夾
+        #[arg(long, value_name = "資料夾")]
+        output_dir: Option<String>,
+        /// 檔案大小維持不變多少秒後才轉換
+        #[arg(long, value_name = "秒數")]
+        stable_seconds: Option<f64>,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 依 Handlebars 範本逐列或逐組輸出；輸出路徑含 {{欄位}} 時每列一個檔案
+    Render {
+        input: String,
+        template: String,
+        output: String,
+        /// 依這些欄位分組，每組輸出一次
+        #[arg(long, value_name = "欄位")]
+        group_by: Vec<String>,
+        /// 引用不存在的欄位時視為錯誤
+        #[arg(long)]
+        strict: bool,
+        #[command(flatten)]
+        source: InputArgs,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 以 SQL 查詢檔案，例如 "SELECT city, avg(value) FROM 'demo.csv' GROUP BY city"
+    #[cfg(feature = "query")]
+    Query {
+        sql: String,
+        /// 輸出檔，格式依副檔名決定；省略時印到 stdout（終端機顯示表格，否則為 CSV）
+        #[arg(short, long)]
+        output: Option<String>,
+        /// 將檔案註冊為資料表
+        #[arg(long, value_name = "名稱=檔案", value_parser = parse_table)]
+        table: Vec<(String, String)>,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+    /// 啟動本機 HTTP 服務：POST /convert、/profile、/validate，回應格式依 Accept 決定
+    #[cfg(feature = "serve")]
+    Serve {
+        /// 監聽位址；連接埠 0 由系統挑選，實際位址會印到 stdout
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+        /// 請求本文大小上限（MiB）
+        #[arg(long, default_value_t = 64)]
+        max_body_mb: usize,
+        /// POST /validate 使用的 schema（infer-schema --json 的輸出）
+        #[arg(long)]
+        schema: Option<String>,
+        #[command(flatten)]
+        values: ValueArgs,
+    },
+}
+
+/// 子指令執行完成後的結果
+enum Outcome {
+    Success,
+    /// 檢查或比較有發現問題
+    Invalid,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    init_logging(&cli.global);
+
+    match run(cli) {
+        Ok(Outcome::Success) => ExitCode::SUCCESS,
+        Ok(Outcome::Invalid) => ExitCode::from(EXIT_INVALID),
+        Err(error) if error.is::<Cancelled>() => {
+            log::warn!("{}", error);
+            ExitCode::from(EXIT_INTERRUPTED)
+        }
+        Err(error) => {
+            log::error!("{:#}", error);
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
 
-    // 將 CSV 轉換為 JSON 並儲存
-    CsvConverter::convert_csv_to_json_file("demo.csv", "demo.json")?;
+fn run(cli: Cli) -> anyhow::Result<Outcome> {
+    let global = &cli.global;
 
-    println!("CSV 轉換完成！");
+    match cli.command {
+        Command::Convert { input, output, source, values } => {
+            let mut options = value_options(&values);
+            let (headers, records) = read_input(global, &source, &mut options, &input)?;
+            let report = convert_by_extension(&headers, &records, &output, options)?;
+            log::info!("已轉換: {}", output);
+            save_report(global, &input, report)?;
+        }
+        Command::Generate { output, rows } => {
+            create_sample_csv_file(&output, rows)?;
+            log::info!("已產生 {} 列測試資料: {}", rows, output);
+        }
+        Command::InferSchema { input, json, source, values } => {
+            let mut options = value_options(&values);
+            let (headers, records) = read_input(global, &source, &mut options, &input)?;
+            let schema = Schema::infer_with(&headers, &records, &options);
+            if json {
+                println!("{}", serde_json::to_string_pretty(&schema)?);
+            } else {
+                print_schema(&schema);
+            }
+        }
+        Command::Profile { input, json, source, values } => {
+            let mut options = value_options(&values);
+            let (headers, records) = read_input(global, &source, &mut options, &input)?;
+            let profiles = CsvConverter::profile_records(&headers, &records, &options);
+            if json {
+                println!("{}", serde_json::to_string_pretty(&profiles)?);
+            } else {
+                print_profiles(&profiles);
+            }
+        }
+        Command::Validate { input, schema, source, values } => {
+            let mut failed = false;
+            // 格式檢查以位元組掃描，只適用於單一、以逗號分隔的 CSV 檔
+            if is_plain_csv(global, &source, &input) {
+                for issue in CsvConverter::lint_csv_file(&input)? {
+                    println!("{}:{}: [{}] {}", input, issue.line, issue.kind.as_str(), issue.message);
+                    failed = true;
+                }
+            }
+            let mut options = value_options(&values);
+            let (headers, records) = match read_input(global, &source, &mut options, &input) {
+                Ok(data) => data,
+                // 格式有問題時可能無法解析記錄，已回報的問題即為結果
+                Err(error) if failed => {
+                    log::warn!("無法讀取記錄，略過 schema 檢查: {:#}", error);
+                    return Ok(Outcome::Invalid);
+                }
+                Err(error) => return Err(error),
+            };
+            if let Some(schema_path) = schema {
+                let schema = Schema::from_json_file(&schema_path)?;
+                for violation in schema.validate(&headers, &records, &options) {
+                    println!("{}:{}: [{}] {}", input, violation.line, violation.column, violation.message);
+                    failed = true;
+                }
+            }
+            if failed {
+                return Ok(Outcome::Invalid);
+            }
+            log::info!("檢查通過: {}（{} 列）", input, records.len());
+        }
+        Command::Head { input, rows, mut source } => {
+            source.sample.get_or_insert(Sampling::Head(rows.unwrap_or(10)));
+            let (headers, records) = read_input(global, &source, &mut ValueOptions::default(), &input)?;
+            let result = CsvConverter::write_csv(&headers, &records, std::io::stdout().lock(), &dialect(global, &input));
+            // 輸出接到 `head` 等提早結束的程式時不視為錯誤
+            match result {
+                Err(error) if !is_broken_pipe(&error) => return Err(error),
+                _ => {}
+            }
+        }
+        Command::Diff { left, right, key, source } => {
+            let mut values = ValueOptions::default();
+            let (left_headers, left_records) = read_input(global, &source, &mut values, &left)?;
+            let (right_headers, right_records) = read_input(global, &source, &mut values, &right)?;
+            let diff = CsvConverter::diff_records(
+                (&left_headers, &left_records),
+                (&right_headers, &right_records),
+                &DiffOptions { key_columns: key },
+            )?;
+            for column in &diff.removed_columns {
+                println!("- 欄位 {}", column);
+            }
+            for column in &diff.added_columns {
+                println!("+ 欄位 {}", column);
+            }
+            for change in &diff.rows {
+                match change {
+                    RowChange::Removed { key } => println!("- {}", display_key(key)),
+                    RowChange::Added { key } => println!("+ {}", display_key(key)),
+                    RowChange::Changed { key, cells } => {
+                        let cells: Vec<String> =
+                            cells.iter().map(|c| format!("{}: {} → {}", c.column, c.left, c.right)).collect();
+                        println!("~ {}: {}", display_key(key), cells.join("; "));
+                    }
+                }
+            }
+            if !diff.is_empty() {
+                return Ok(Outcome::Invalid);
+            }
+            log::info!("兩個檔案相同");
+        }
+        Command::Json { input, output, fast, source, values } => {
+            let mut options = value_options(&values);
+            let has_input_options = source.layout.is_some()
+                || source.sheet.is_some()
+                || source.range.is_some()
+                || source.mask.is_some()
+                || source.sample.is_some();
+            let plain_csv = !has_input_options && is_plain_csv(global, &source, &input);
+            let report = if fast {
+                if !plain_csv {
+                    bail!("--fast 只能直接轉換單一、以逗號分隔的 CSV 檔");
+                }
+                write_output(&output, |path| CsvConverter::convert_csv_to_json_file_fast(&input, path, &options))?
+            } else if plain_csv {
+                // 邊讀邊寫，按 Ctrl-C 時在記錄之間停止
+                let (monitor, bar) = progress_monitor(&input)?;
+                let result = CsvConverter::convert_csv_to_json_file_with_progress(&input, &output, &options, &monitor);
+                bar.finish_and_clear();
+                result?
+            } else {
+                let (headers, records) = read_input(global, &source, &mut options, &input)?;
+                write_output(&output, |path| CsvConverter::write_json_file(&headers, &records, path, &options))?
+            };
+            log::info!("已轉換為 JSON: {}", output);
+            save_report(global, &input, report)?;
+        }
+        Command::Sqlite { input, output, sql, source, values } => {
+            let mut options = sql_options(&sql, value_options(&values));
+            let (headers, records) = read_input(global, &source, &mut options.values, &input)?;
+            let report = CsvConverter::write_sqlite_file(&headers, &records, &output, &options)?;
+            log::info!("已匯出 SQLite 資料庫: {}", output);
+            save_report(global, &input, report)?;
+        }
+        Command::Sql { input, output, sql, source, values } => {
+            let mut options = sql_options(&sql, value_options(&values));
+            let (headers, records) = read_input(global, &source, &mut options.values, &input)?;
+            let report = write_output(&output, |path| CsvConverter::write_sql_script(&headers, &records, path, &options))?;
+            log::info!("已產生 SQL 腳本: {}", output);
+            save_report(global, &input, report)?;
+        }
+        Command::Xlsx { input, output, sheet_name, with_schema, source, values } => {
+            let mut options = XlsxExportOptions {
+                values: value_options(&values),
+                include_schema: with_schema,
+                ..Default::default()
+            };
+            if let Some(name) = sheet_name {
+                options.sheet_name = name;
+            }
+            let (headers, records) = read_input(global, &source, &mut options.values, &input)?;
+            let report = write_output(&output, |path| CsvConverter::write_xlsx_file(&headers, &records, path, &options))?;
+            log::info!("已匯出 xlsx: {}", output);
+            save_report(global, &input, report)?;
+        }
+        Command::Geojson { input, output, lat, lon, bbox, rejects, source, values } => {
+            let mut options = GeoJsonOptions {
+                lat_column: lat,
+                lon_column: lon,
+                bbox,
+                rejects_path: rejects,
+                values: value_options(&values),
+            };
+            let (headers, records) = read_input(global, &source, &mut options.values, &input)?;
+            let report = write_output(&output, |path| CsvConverter::write_geojson_file(&headers, &records, path, &options))?;
+            log::info!("已匯出 GeoJSON: {}", output);
+            save_report(global, &input, report)?;
+        }
+        Command::Yaml { input, output, document_per_row, source, values } => {
+            let mut options = YamlOptions { document_per_row, values: value_options(&values) };
+            let (headers, records) = read_input(global, &source, &mut options.values, &input)?;
+            let report = write_output(&output, |path| CsvConverter::write_yaml_file(&headers, &records, path, &options))?;
+            log::info!("已匯出 YAML: {}", output);
+            save_report(global, &input, report)?;
+        }
+        Command::Toml { input, output, table, source, values } => {
+            let mut options = TomlOptions { values: value_options(&values), ..Default::default() };
+            if let Some(table) = table {
+                options.table_name = table;
+            }
+            let (headers, records) = read_input(global, &source, &mut options.values, &input)?;
+            let report = write_output(&output, |path| CsvConverter::write_toml_file(&headers, &records, path, &options))?;
+            log::info!("已匯出 TOML: {}", output);
+            save_report(global, &input, report)?;
+        }
+        Command::Xml { input, output, root, row, attribute, all_attributes, source, values } => {
+            let mut options = XmlOptions {
+                attribute_columns: attribute,
+                all_attributes,
+                values: value_options(&values),
+                ..Default::default()
+            };
+            if let Some(root) = root {
+                options.root_element = root;
+            }
+            if let Some(row) = row {
+                options.row_element = row;
+            }
+            let (headers, records) = read_input(global, &source, &mut options.values, &input)?;
+            let report = write_output(&output, |path| CsvConverter::write_xml_file(&headers, &records, path, &options))?;
+            log::info!("已匯出 XML: {}", output);
+            save_report(global, &input, report)?;
+        }
+        Command::Markdown(table) => render_table(global, table, CsvConverter::render_markdown_table)?,
+        Command::Html(table) => render_table(global, table, CsvConverter::render_html_table)?,
+        Command::Show(table) => render_table(global, table, CsvConverter::render_terminal_table)?,
+        #[cfg(feature = "parquet")]
+        Command::Parquet { input, output, source, values } => {
+            let mut options = value_options(&values);
+            let (headers, records) = read_input(global, &source, &mut options, &input)?;
+            let report = write_output(&output, |path| CsvConverter::write_parquet_file(&headers, &records, path, &options))?;
+            log::info!("已匯出 Parquet: {}", output);
+            save_report(global, &input, report)?;
+        }
+        Command::Avro { input, output, codec, record_name, namespace, block_size, source, values } => {
+            let mut options = AvroOptions { namespace, values: value_options(&values), ..Default::default() };
+            if let Some(codec) = codec {
+                options.codec = codec;
+            }
+            if let Some(name) = record_name {
+                options.record_name = name;
+            }
+            if let Some(block_size) = block_size {
+                options.block_size = block_size;
+            }
+            let (headers, records) = read_input(global, &source, &mut options.values, &input)?;
+            let report = write_output(&output, |path| CsvConverter::write_avro_file(&headers, &records, path, &options))?;
+            log::info!("已匯出 Avro: {}", output);
+            save_report(global, &input, report)?;
+        }
+        Command::Incremental { input, output, state, values } => {
+            let options = IncrementalOptions { state_path: state, values: value_options(&values) };
+            let summary = CsvConverter::convert_csv_incremental(&input, &output, &options)?;
+            if let Some(reason) = &summary.full_rebuild {
+                log::warn!("{}，已重新完整轉換", reason);
+            }
+            log::info!("新增 {} 列，共 {} 列: {}", summary.report.rows_written, summary.total_rows, output);
+            save_report(global, &input, summary.report)?;
+        }
+        Command::Concat { pattern, output, source } => {
+            let (headers, records) = read_input(global, &source, &mut ValueOptions::default(), &pattern)?;
+            let dialect = Dialect::for_path(&output);
+            write_output(&output, |path| CsvConverter::write_csv_file(&headers, &records, path, &dialect))?;
+            log::info!("已合併 {} 列: {}", records.len(), output);
+        }
+        Command::Pivot { input, output, index, columns, values_column, agg, sorted, values } => {
+            let options = PivotOptions {
+                index,
+                columns,
+                values: values_column,
+                aggregate: agg.unwrap_or_default(),
+                sorted,
+                value_options: value_options(&values),
+                dialect: global.has_dialect().then(|| dialect(global, &input)),
+            };
+            let report = write_output(&output, |path| CsvConverter::pivot_csv_file(&input, path, &options))?;
+            log::info!("已產生寬表: {}", output);
+            save_report(global, &input, report)?;
+        }
+        Command::Unpivot { input, output, id, value_column, variable_name, value_name, skip_empty } => {
+            let mut options = UnpivotOptions {
+                id_columns: id,
+                value_columns: value_column,
+                skip_empty,
+                dialect: global.has_dialect().then(|| dialect(global, &input)),
+                ..Default::default()
+            };
+            if let Some(name) = variable_name {
+                options.variable_name = name;
+            }
+            if let Some(name) = value_name {
+                options.value_name = name;
+            }
+            let report = write_output(&output, |path| CsvConverter::unpivot_csv_file(&input, path, &options))?;
+            log::info!("已產生長表: {}", output);
+            save_report(global, &input, report)?;
+        }
+        Command::Nested { input, output, group_by, shape, agg, keep_keys, source, values } => {
+            let mut options = NestedOptions {
+                group_by,
+                shape: shape.unwrap_or_default(),
+                aggregates: agg,
+                keep_keys,
+                values: value_options(&values),
+            };
+            let (headers, records) = read_input(global, &source, &mut options.values, &input)?;
+            let report = write_output(&output, |path| CsvConverter::write_nested_json_file(&headers, &records, path, &options))?;
+            log::info!("已轉換為分組 JSON: {}", output);
+            save_report(global, &input, report)?;
+        }
+        Command::Lint { inputs } => {
+            if global.has_dialect() {
+                bail!("lint 只支援以逗號分隔的 CSV 檔");
+            }
+            let mut failed = false;
+            for input in &inputs {
+                for issue in CsvConverter::lint_csv_file(input)? {
+                    println!("{}:{}: [{}] {}", input, issue.line, issue.kind.as_str(), issue.message);
+                    failed = true;
+                }
+            }
+            if failed {
+                return Ok(Outcome::Invalid);
+            }
+        }
+        Command::Fmt { inputs, quote_style, trim_fields, check } => {
+            if global.has_dialect() {
+                bail!("fmt 只支援以逗號分隔的 CSV 檔");
+            }
+            let options = FormatOptions { quote_style: quote_style.unwrap_or_default(), trim_fields };
+            let mut unformatted = false;
+            for input in &inputs {
+                let formatted = CsvConverter::format_csv_file(input, &options)?;
+                if std::fs::read(input)? == formatted {
+                    continue;
+                }
+                if check {
+                    println!("格式不符: {}", input);
+                    unformatted = true;
+                } else {
+                    write_output(input, |partial| Ok(std::fs::write(partial, &formatted)?))?;
+                    log::info!("已格式化: {}", input);
+                }
+            }
+            if unformatted {
+                return Ok(Outcome::Invalid);
+            }
+        }
+        Command::Watch { dir, format, output_dir, stable_seconds, values } => {
+            let mut config = WatchConfig { values: value_options(&values), ..Default::default() };
+            if let Some(format) = format {
+                config.format = format;
+            }
+            if let Some(output_dir) = output_dir {
+                config.output_dir = output_dir;
+            }
+            if let Some(seconds) = stable_seconds {
+                config.stable_for = stable_duration(seconds)?;
+            }
+            let mut watcher = DirectoryWatcher::new(&dir, config)?;
+            for setting in watcher.overridden_settings() {
+                log::warn!("{} 中的 {} 設定取代了命令列指定的值", WATCH_CONFIG_FILE, setting);
+            }
+
+            let cancel = interrupt_token()?;
+
+            log::info!(
+                "監看 {}，輸出 {} 到 {}/（按 Ctrl-C 結束）",
+                dir,
+                watcher.config().format.extension(),
+                watcher.config().output_dir
+            );
+            watcher.run(&cancel, |outcome| println!("{}", outcome.log_line()))?;
+        }
+        Command::Render { input, template, output, group_by, strict, source, values } => {
+            let mut options = TemplateOptions {
+                template: std::fs::read_to_string(&template)?,
+                output: output.clone(),
+                group_by,
+                strict,
+                values: value_options(&values),
+            };
+            let (headers, records) = read_input(global, &source, &mut options.values, &input)?;
+            let report = CsvConverter::render_template(&headers, &records, &options)?;
+            log::info!("已依範本輸出: {}", output);
+            save_report(global, &input, report)?;
+        }
+        #[cfg(feature = "query")]
+        Command::Query { sql, output, table, values } => {
+            let options = QueryOptions {
+                tables: table,
+                dialect: global.has_dialect().then(|| dialect(global, "")),
+                values: value_options(&values),
+            };
+            let data = CsvConverter::query_files(&sql, &options)?;
+            let (headers, records) = (&data.headers, &data.records);
+            let values = data.value_options(&options.values);
+            match output {
+                Some(output) => {
+                    let report = convert_by_extension(headers, records, &output, values)?;
+                    log::info!("已輸出查詢結果 {} 列: {}", records.len(), output);
+                    save_report(global, "", report)?;
+                }
+                None if std::io::stdout().is_terminal() => {
+                    let table = TableOptions { values, ..Default::default() };
+                    print!("{}", CsvConverter::render_terminal_table(headers, records, &table));
+                }
+                None => CsvConverter::write_csv(headers, records, std::io::stdout().lock(), &Dialect::default())?,
+            }
+        }
+        #[cfg(feature = "serve")]
+        Command::Serve { listen, max_body_mb, schema, values } => {
+            let Some(max_body_bytes) = max_body_mb.checked_mul(1024 * 1024) else {
+                bail!("--max-body-mb 超出可表示的範圍: {}", max_body_mb);
+            };
+            let options = ServeOptions {
+                max_body_bytes,
+                schema: schema.as_deref().map(Schema::from_json_file).transpose()?,
+                values: value_options(&values),
+                ..Default::default()
+            };
+            tokio::runtime::Runtime::new()?.block_on(async {
+                let listener = tokio::net::TcpListener::bind(&listen).await?;
+                // 供呼叫端（例如整合測試）讀取實際的連接埠
+                println!("http://{}", listener.local_addr()?);
+                log::info!("HTTP 服務已啟動（按 Ctrl-C 結束）");
+                let shutdown = async {
+                    tokio::signal::ctrl_c().await.ok();
+                };
+                CsvConverter::serve_http(listener, options, shutdown).await
+            })?;
+        }
+    }
+
+    Ok(Outcome::Success)
+}
+
+/// 依 -v／-q 設定記錄層級，RUST_LOG 優先；訊息輸出到 stderr，stdout 只留給資料
+fn init_logging(global: &GlobalArgs) {
+    let level = match (global.quiet, global.verbose) {
+        (true, _) => LevelFilter::Error,
+        (false, 0) => LevelFilter::Info,
+        (false, 1) => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+    env_logger::Builder::new()
+        .filter_level(level)
+        .parse_default_env()
+        .format(|buf, record| match record.level() {
+            Level::Error => writeln!(buf, "❌ {}", record.args()),
+            Level::Warn => writeln!(buf, "⚠️  {}", record.args()),
+            Level::Info => writeln!(buf, "{}", record.args()),
+            level => writeln!(buf, "[{}] {}", level.as_str().to_ascii_lowercase(), record.args()),
+        })
+        .init();
+}
+
+/// 依輸出檔的副檔名選擇格式，各格式使用預設設定
+fn convert_by_extension(
+    headers: &[String],
+    records: &[StringRecord],
+    output: &str,
+    values: ValueOptions,
+) -> anyhow::Result<ConversionReport> {
+    let extension = Path::new(output)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    log::debug!("輸出格式: {}", extension);
+
+    // SQLite 只取代資料庫中的單一資料表，寫入本身在交易中完成
+    if matches!(extension.as_str(), "db" | "sqlite" | "sqlite3") {
+        let options = SqlExportOptions { values, ..Default::default() };
+        return CsvConverter::write_sqlite_file(headers, records, output, &options);
+    }
+    let dialect = Dialect::for_path(output);
+    write_output(output, |path| match extension.as_str() {
+        "json" => CsvConverter::write_json_file(headers, records, path, &values),
+        "yaml" | "yml" => {
+            let options = YamlOptions { values, ..Default::default() };
+            CsvConverter::write_yaml_file(headers, records, path, &options)
+        }
+        "toml" => {
+            let options = TomlOptions { values, ..Default::default() };
+            CsvConverter::write_toml_file(headers, records, path, &options)
+        }
+        "xml" => {
+            let options = XmlOptions { values, ..Default::default() };
+            CsvConverter::write_xml_file(headers, records, path, &options)
+        }
+        "xlsx" => {
+            let options = XlsxExportOptions { values, ..Default::default() };
+            CsvConverter::write_xlsx_file(headers, records, path, &options)
+        }
+        "sql" => {
+            let options = SqlExportOptions { values, ..Default::default() };
+            CsvConverter::write_sql_script(headers, records, path, &options)
+        }
+        "geojson" => {
+            let options = GeoJsonOptions { values, ..Default::default() };
+            CsvConverter::write_geojson_file(headers, records, path, &options)
+        }
+        "avro" => {
+            let options = AvroOptions { values, ..Default::default() };
+            CsvConverter::write_avro_file(headers, records, path, &options)
+        }
+        #[cfg(feature = "parquet")]
+        "parquet" => CsvConverter::write_parquet_file(headers, records, path, &values),
+        "csv" | "tsv" | "tab" => CsvConverter::write_csv_file(headers, records, path, &dialect),
+        _ => bail!(
+            "無法由副檔名判斷輸出格式: {}（可用: json, yaml, toml, xml, xlsx, db, sql, geojson, avro, parquet, csv, tsv）",
+            output
+        ),
+    })
+}
+
+/// 輸出 Markdown、HTML 或終端機表格；未指定輸出檔時印到 stdout
+fn render_table(
+    global: &GlobalArgs,
+    table: TableArgs,
+    render: fn(&[String], &[StringRecord], &TableOptions) -> String,
+) -> anyhow::Result<()> {
+    let mut options = TableOptions {
+        max_rows: table.rows,
+        max_width: table.max_width,
+        title: Some(table.input.clone()),
+        values: value_options(&table.values),
+    };
+    let (headers, records) = read_input(global, &table.source, &mut options.values, &table.input)?;
+    let rendered = render(&headers, &records, &options);
+    match table.output {
+        Some(path) => write_output(&path, |partial| Ok(std::fs::write(partial, &rendered)?))?,
+        None => print!("{}", rendered),
+    }
     Ok(())
 }
+
+/// 讀取輸入檔；指定 --mask 時套用個資遮罩
+fn read_input(
+    global: &GlobalArgs,
+    source: &InputArgs,
+    values: &mut ValueOptions,
+    input: &str,
+) -> anyhow::Result<(Vec<String>, Vec<StringRecord>)> {
+    log::debug!("讀取 {}", input);
+    let (headers, records) = read_records(global, source, values, input)?;
+    log::debug!("讀入 {} 列、{} 個欄位", records.len(), headers.len());
+
+    // 鹽值來自 --salt-file 或環境變數，不接受命令列參數以免出現在指令歷史與 ps 中
+    let Some(mask_path) = &source.mask else {
+        return Ok((headers, records));
+    };
+    let rules = MaskingRules::from_csv_file(mask_path)?;
+    let salt = match &source.salt_file {
+        Some(path) => Some(
+            std::fs::read_to_string(path)
+                .with_context(|| format!("無法讀取鹽值檔: {}", path))?
+                .trim_end_matches(['\r', '\n'])
+                .to_string(),
+        ),
+        None => std::env::var(MASK_SALT_ENV).ok(),
+    };
+    let masked = CsvConverter::mask_records(&headers, &records, &rules, salt.as_deref())?;
+    // 合併時缺少的儲存格以欄位位置記錄，移除欄位後需重新對應
+    values.null_cells = values.null_cells.select_columns(&masked.kept_columns);
+    Ok((masked.headers, masked.records))
+}
+
+fn read_records(
+    global: &GlobalArgs,
+    source: &InputArgs,
+    values: &mut ValueOptions,
+    input: &str,
+) -> anyhow::Result<(Vec<String>, Vec<StringRecord>)> {
+    let Some(method) = &source.sample else {
+        return read_all_records(global, source, values, input);
+    };
+    let options = SampleOptions { method: method.clone(), seed: source.seed };
+    // 一般 CSV 以串流方式抽樣，其他來源先讀入再抽樣
+    if is_plain_csv(global, source, input) {
+        return CsvConverter::sample_csv_file(input, &options);
+    }
+    let (headers, records) = read_all_records(global, source, values, input)?;
+    let sampled = CsvConverter::sample_records_with_rows(&headers, records, &options)?;
+    // 合併時缺少的儲存格依列號記錄，需跟著抽樣結果重新編號
+    let rows: Vec<usize> = sampled.iter().map(|(row, _)| *row).collect();
+    values.null_cells = values.null_cells.select(&rows);
+    Ok((headers, sampled.into_iter().map(|(_, record)| record).collect()))
+}
+
+/// 依輸入類型讀取：多檔合併、固定寬度檔、試算表、Avro 或 CSV；固定寬度檔有問題的行記錄為警告，
+/// 合併時來源檔案缺少的儲存格記錄在 `values.null_cells`
+fn read_all_records(
+    global: &GlobalArgs,
+    source: &InputArgs,
+    values: &mut ValueOptions,
+    input: &str,
+) -> anyhow::Result<(Vec<String>, Vec<StringRecord>)> {
+    if is_glob(input) {
+        let paths = CsvConverter::expand_glob(input)?;
+        log::debug!("合併 {} 個檔案", paths.len());
+        let data = CsvConverter::concat_files(&paths, &concat_options(global, source, input))?;
+        values.null_cells = data.missing;
+        return Ok((data.headers, data.records));
+    }
+    let Some(layout_path) = &source.layout else {
+        if is_plain_csv(global, source, input) && std::io::stderr().is_terminal() {
+            return read_csv_with_progress(input);
+        }
+        return CsvConverter::read_input_file_with_dialect(input, &sheet_options(source), &dialect(global, input));
+    };
+
+    let mut layout = FixedWidthLayout::from_csv_file(layout_path)?;
+    if let Some(skip) = source.skip_lines {
+        layout.skip_lines = skip;
+    }
+    layout.allow_short_lines = source.allow_short_lines;
+
+    let data = CsvConverter::read_fixed_width_file(input, &layout, values)?;
+    for issue in &data.issues {
+        log::warn!("{}:{}: {}", input, issue.line, issue.message);
+    }
+    Ok((data.headers, data.records))
+}
+
+/// 讀取 CSV 時在 stderr 顯示進度條；按 Ctrl-C 取消讀取，尚未寫出任何輸出，再按一次直接結束
+fn read_csv_with_progress(input: &str) -> anyhow::Result<(Vec<String>, Vec<StringRecord>)> {
+    let (monitor, bar) = progress_monitor(input)?;
+    let result = CsvConverter::read_csv_file_with_progress(input, &monitor);
+    bar.finish_and_clear();
+    result
+}
+
+/// 依輸入檔大小顯示進度的監看設定，stderr 不是終端機時進度條不會顯示；取消旗標為 [`interrupt_token`]
+fn progress_monitor(input: &str) -> anyhow::Result<(ProgressMonitor, ProgressBar)> {
+    let bar = if std::io::stderr().is_terminal() {
+        ProgressBar::new(std::fs::metadata(input)?.len())
+    } else {
+        ProgressBar::hidden()
+    };
+    bar.set_style(ProgressStyle::with_template("{spinner} [{bar:40}] {bytes}/{total_bytes} {msg} ({eta})")?);
+
+    let observer_bar = bar.clone();
+    let monitor = ProgressMonitor {
+        observer: Some(Arc::new(move |progress: &Progress| {
+            observer_bar.set_position(progress.bytes_read);
+            observer_bar.set_message(format!("{} 列", progress.rows));
+        })),
+        cancel: interrupt_token()?,
+        every_rows: 1000,
+    };
+    Ok((monitor, bar))
+}
+
+/// 第一次按 Ctrl-C 時設定的取消旗標，再按一次直接結束；整個程式共用同一個處理函式
+fn interrupt_token() -> anyhow::Result<CancellationToken> {
+    static TOKEN: OnceLock<CancellationToken> = OnceLock::new();
+    if let Some(token) = TOKEN.get() {
+        return Ok(token.clone());
+    }
+    let token = CancellationToken::new();
+    let handler_token = token.clone();
+    ctrlc::set_handler(move || {
+        if handler_token.is_cancelled() {
+            std::process::exit(EXIT_INTERRUPTED.into());
+        }
+        handler_token.cancel();
+    })?;
+    Ok(TOKEN.get_or_init(|| token).clone())
+}
+
+/// 先寫到 `.partial` 暫存檔，完成後才改名為輸出檔；失敗或按 Ctrl-C 時保留原本的輸出檔
+fn write_output<T>(output: &str, write: impl FnOnce(&str) -> anyhow::Result<T>) -> anyhow::Result<T> {
+    CsvConverter::write_atomically(output, &interrupt_token()?, write)
+}
+
+/// 將警告記錄下來；指定 --report 時另外寫出 JSON 報告（`-` 表示 stdout）
+fn save_report(global: &GlobalArgs, input: &str, mut report: ConversionReport) -> anyhow::Result<()> {
+    // 先讀入再轉換的記錄不知道來源大小，由輸入檔補上
+    if report.bytes_in == 0 && report.rows_read > 0 {
+        report.bytes_in = std::fs::metadata(input).map_or(0, |m| m.len());
+    }
+    for warning in &report.warnings {
+        log::warn!("{}", warning);
+    }
+    match global.report.as_deref() {
+        Some("-") => println!("{}", report.to_json()?),
+        Some(path) => std::fs::write(path, report.to_json()?)?,
+        None => {}
+    }
+    Ok(())
+}
+
+/// 印出推斷出的 schema 與各欄位套用的解析規則
+fn print_schema(schema: &Schema) {
+    for column in &schema.columns {
+        let rules = column.rules.iter().map(|r| r.as_str()).collect::<Vec<_>>().join(", ");
+        println!(
+            "{:<20} {:<8} {:<9} {}",
+            column.name,
+            column.column_type.as_str(),
+            if column.nullable { "nullable" } else { "" },
+            rules
+        );
+    }
+}
+
+/// 以表格印出欄位概況；標題與 --json 的欄位名稱相同
+fn print_profiles(profiles: &[ColumnProfile]) {
+    let number = |value: Option<f64>| value.map_or("-".to_string(), |n| n.to_string());
+    println!(
+        "{:<20} {:<8} {:>8} {:>8} {:>8} {:>12} {:>12} {:>12}  most_common",
+        "name", "type", "count", "empty", "distinct", "min", "max", "mean"
+    );
+    for profile in profiles {
+        println!(
+            "{:<20} {:<8} {:>8} {:>8} {:>8} {:>12} {:>12} {:>12}  {}",
+            profile.name,
+            profile.column_type.as_str(),
+            profile.count,
+            profile.empty,
+            profile.distinct,
+            number(profile.min),
+            number(profile.max),
+            profile.mean.map_or("-".to_string(), |n| format!("{:.2}", n)),
+            profile
+                .most_common
+                .as_ref()
+                .map_or("-".to_string(), |value| format!("{} ({})", value, profile.most_common_count)),
+        );
+    }
+}
+
+/// 由命令列旗標建立欄位值轉換設定
+fn value_options(args: &ValueArgs) -> ValueOptions {
+    let mut options = ValueOptions::default();
+
+    if let Some(numeric) = args.numeric {
+        options.numeric = numeric;
+    }
+    if let Some(non_finite) = args.non_finite {
+        options.non_finite = non_finite;
+    }
+    if let Some(locale) = args.locale {
+        options.locale = locale;
+    }
+    options.decimal_columns = args.decimal.clone();
+
+    for symbol in &args.currency {
+        if symbol == "default" {
+            options.currency_symbols.extend(DEFAULT_CURRENCY_SYMBOLS.iter().map(|s| s.to_string()));
+        } else {
+            options.currency_symbols.push(symbol.clone());
+        }
+    }
+    options.percent = args.percent;
+    options.empty_as_null = args.empty_as_null;
+
+    if let Some(BoolTokens::Extended) = args.bool_tokens {
+        options.true_tokens = EXTENDED_TRUE_TOKENS.iter().map(|s| s.to_string()).collect();
+        options.false_tokens = EXTENDED_FALSE_TOKENS.iter().map(|s| s.to_string()).collect();
+    }
+    options.true_tokens.extend(args.true_tokens.iter().cloned());
+    options.false_tokens.extend(args.false_tokens.iter().cloned());
+
+    options
+}
+
+/// 輸入檔的 CSV 方言：依副檔名決定，再套用 --delimiter、--quote、--comment
+fn dialect(global: &GlobalArgs, path: &str) -> Dialect {
+    let mut dialect = Dialect::for_path(path);
+    if let Some(delimiter) = global.delimiter {
+        dialect.delimiter = delimiter;
+    }
+    if let Some(quote) = global.quote {
+        dialect.quote = quote;
+    }
+    if let Some(comment) = global.comment {
+        dialect.comment = Some(comment);
+    }
+    dialect
+}
+
+/// 由命令列旗標建立試算表讀取設定
+fn sheet_options(source: &InputArgs) -> SpreadsheetOptions {
+    SpreadsheetOptions {
+        sheet: source.sheet.clone().unwrap_or_default(),
+        range: source.range.clone(),
+    }
+}
+
+/// 由命令列旗標建立多檔合併設定；未指定方言旗標時依各檔案的副檔名決定
+fn concat_options(global: &GlobalArgs, source: &InputArgs, pattern: &str) -> ConcatOptions {
+    ConcatOptions {
+        source_column: source.source_column,
+        renames: source.rename.clone(),
+        spreadsheet: sheet_options(source),
+        dialect: global.has_dialect().then(|| dialect(global, pattern)),
+    }
+}
+
+/// 由命令列旗標建立 SQL 匯出設定
+fn sql_options(args: &SqlArgs, values: ValueOptions) -> SqlExportOptions {
+    let mut options = SqlExportOptions {
+        indexes: args.index.clone(),
+        values,
+        ..Default::default()
+    };
+    if let Some(table) = &args.table {
+        options.table_name = table.clone();
+    }
+    if let Some(batch_size) = args.batch_size {
+        options.batch_size = batch_size;
+    }
+    options
+}
+
+/// 以 `, ` 連接鍵欄位的值；含有逗號或引號的值加上引號，避免不同的鍵印成同一行
+fn display_key(key: &[String]) -> String {
+    let quote = |value: &String| {
+        if value.contains([',', '"']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.clone()
+        }
+    };
+    key.iter().map(quote).collect::<Vec<_>>().join(", ")
+}
+
+/// 錯誤是否來自讀取端已關閉的管線
+fn is_broken_pipe(error: &anyhow::Error) -> bool {
+    let io_error = match error.downcast_ref::<csv::Error>() {
+        Some(csv_error) => match csv_error.kind() {
+            csv::ErrorKind::Io(io_error) => Some(io_error),
+            _ => None,
+        },
+        None => error.downcast_ref::<std::io::Error>(),
+    };
+    io_error.is_some_and(|e| e.kind() == std::io::ErrorKind::BrokenPipe)
+}
+
+/// 輸入路徑含有萬用字元時視為多檔合併
+fn is_glob(input: &str) -> bool {
+    input.contains(['*', '?', '['])
+}
+
+/// 單一、以逗號分隔的 CSV 檔，可以串流讀取或直接掃描位元組
+fn is_plain_csv(global: &GlobalArgs, source: &InputArgs, input: &str) -> bool {
+    source.layout.is_none()
+        && !is_glob(input)
+        && !is_spreadsheet_path(input)
+        && !input.to_ascii_lowercase().ends_with(".avro")
+        && dialect(global, input) == Dialect::default()
+}
+
+/// 解析單一 ASCII 字元；tab 可寫成 \t 或 tab
+fn parse_ascii_char(s: &str) -> Result<u8, String> {
+    match s {
+        "\\t" | "tab" => Ok(b'\t'),
+        _ if s.len() == 1 && s.is_ascii() => Ok(s.as_bytes()[0]),
+        _ => Err(format!("必須是單一 ASCII 字元: {}", s)),
+    }
+}
+
+/// 解析 `名稱=檔案`
+#[cfg(feature = "query")]
+fn parse_table(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((name, path)) => Ok((name.to_string(), path.to_string())),
+        None => Err(format!("格式應為 名稱=檔案: {}", s)),
+    }
+}
+
+/// 解析 `舊名稱=新名稱`
+fn parse_rename(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((from, to)) => Ok((from.to_string(), to.to_string())),
+        None => Err(format!("格式應為 舊名稱=新名稱: {}", s)),
+    }
+}
@@ -9,7 +9,7 @@ pub fn create_sample_csv_file(filename: &str, record_count: usize) -> std::io::R
     let mut writer = Writer::from_writer(file);
 
     // 寫入 CSV 標題列
-    writer.write_record(&["id", "name", "age", "city", "value", "active"])?;
+    writer.write_record(["id", "name", "age", "city", "value", "active"])?;
 
     let cities = ["New York", "London", "Tokyo", "Paris"];
 
@@ -21,7 +21,7 @@ pub fn create_sample_csv_file(filename: &str, record_count: usize) -> std::io::R
         let value = 100.0 + (i as f64 * 10.0);
         let active = (i % 3) != 0; // 每第 3 筆記錄設為非活躍狀態
 
-        writer.write_record(&[
+        writer.write_record([
             &i.to_string(),
             &name,
             &age.to_string(),